@@ -0,0 +1,29 @@
+#[cfg(all(feature = "std", feature = "serde"))]
+fn main() -> anyhow::Result<()> {
+    use std::{env, fs};
+
+    use greentic_types::CborBytes;
+    use greentic_types::schemas::common::codegen::TypeGenerator;
+    use greentic_types::schemas::component::v0_6_0::ComponentDescribe;
+
+    let mut args = env::args().skip(1);
+    let (Some(path), Some(lang)) = (args.next(), args.next()) else {
+        anyhow::bail!("usage: schema-codegen <describe.cbor> <typescript|rust>");
+    };
+    let describe = CborBytes::new(fs::read(&path)?)
+        .decode::<ComponentDescribe>()
+        .map_err(|err| anyhow::anyhow!("failed to decode {path}: {err}"))?;
+    let generator = TypeGenerator::for_describe(&describe);
+    let output = match lang.as_str() {
+        "typescript" | "ts" => generator.typescript()?,
+        "rust" | "rs" => generator.rust()?,
+        other => anyhow::bail!("unknown language `{other}`; expected typescript or rust"),
+    };
+    print!("{output}");
+    Ok(())
+}
+
+#[cfg(not(all(feature = "std", feature = "serde")))]
+fn main() -> anyhow::Result<()> {
+    anyhow::bail!("enable the `std` and `serde` features to run schema-codegen")
+}
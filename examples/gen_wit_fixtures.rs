@@ -91,6 +91,8 @@ fn write_pack_qa(path: impl AsRef<Path>) {
         },
         required: true,
         default: Some(Value::Text("eu".to_string())),
+        visible_if: None,
+        required_if: None,
     };
 
     let mut defaults = BTreeMap::new();
@@ -123,6 +125,8 @@ fn write_component_qa(path: impl AsRef<Path>) {
         kind: ComponentQuestionKind::Text,
         required: true,
         default: None,
+        visible_if: None,
+        required_if: None,
     };
 
     let spec = ComponentQaSpec {
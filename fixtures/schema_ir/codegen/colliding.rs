@@ -0,0 +1,17 @@
+// Generated from SchemaIr by greentic-types. Do not edit.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Colliding {
+    #[serde(rename = "api-key")]
+    pub api_key: String,
+    #[serde(rename = "api_key", default, skip_serializing_if = "Option::is_none")]
+    pub api_key_2: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extra: Option<String>,
+    #[serde(flatten)]
+    pub extra_2: BTreeMap<String, serde_json::Value>,
+}
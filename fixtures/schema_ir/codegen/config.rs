@@ -0,0 +1,101 @@
+// Generated from SchemaIr by greentic-types. Do not edit.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Byte string: a CBOR byte string, or base64 text in human-readable formats such as JSON.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ByteString(pub Vec<u8>);
+
+type ByteStringAs = serde_with::IfIsHumanReadable<serde_with::base64::Base64, serde_with::Bytes>;
+
+impl Serialize for ByteString {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        <ByteStringAs as serde_with::SerializeAs<Vec<u8>>>::serialize_as(&self.0, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for ByteString {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        <ByteStringAs as serde_with::DeserializeAs<'de, Vec<u8>>>::deserialize_as(deserializer)
+            .map(Self)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    #[serde(rename = "api-key")]
+    pub api_key: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blob: Option<ByteString>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub labels: Option<BTreeMap<String, String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub level: Option<serde_json::Value>,
+    pub shape: ConfigShape,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tree: Option<Box<TreeNode>>,
+    pub r#type: ConfigType,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<ConfigValue>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind")]
+pub enum ConfigShape {
+    #[serde(rename = "circle")]
+    Circle(ConfigShapeCircle),
+    #[serde(rename = "rect")]
+    Rect(ConfigShapeRect),
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ConfigShapeCircle {
+    pub radius: f64,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ConfigShapeRect {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub height: Option<i64>,
+    pub width: i64,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TreeNode {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub children: Option<Vec<TreeNode>>,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<Box<TreeNode>>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ConfigType {
+    #[serde(rename = "fast")]
+    Fast,
+    #[serde(rename = "safe-mode")]
+    SafeMode,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ConfigValue {
+    String(String),
+    Integer(i64),
+    Variant3(ConfigValueVariant3),
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ConfigValueVariant3 {
+    pub x: bool,
+    #[serde(flatten)]
+    pub extra: BTreeMap<String, serde_json::Value>,
+}
+
+pub type RunInput = Vec<String>;
@@ -0,0 +1,44 @@
+// Generated from SchemaIr by greentic-types. Do not edit.
+// `Uint8Array` fields are CBOR byte strings; in JSON they are base64-encoded strings.
+
+export interface Config {
+  "api-key": string;
+  blob?: Uint8Array;
+  labels?: Record<string, string>;
+  level?: 1 | 2;
+  shape: ConfigShape;
+  timeout?: number | null;
+  tree?: TreeNode;
+  type: ConfigType;
+  value?: ConfigValue;
+}
+
+export type ConfigShape = ConfigShapeCircle | ConfigShapeRect;
+
+export interface ConfigShapeCircle {
+  kind: "circle";
+  radius: number;
+}
+
+export interface ConfigShapeRect {
+  kind: "rect";
+  height?: number;
+  width: number;
+}
+
+export interface TreeNode {
+  children?: TreeNode[];
+  name: string;
+  parent?: TreeNode | null;
+}
+
+export type ConfigType = "fast" | "safe-mode";
+
+export type ConfigValue = string | number | ConfigValueVariant3;
+
+export interface ConfigValueVariant3 {
+  x: boolean;
+  [key: string]: unknown;
+}
+
+export type RunInput = string[];
@@ -0,0 +1,124 @@
+//! Adapter from a legacy v0.5.0 component manifest to the v0.6.0 Component describe payload.
+//!
+//! Before v0.6.0 a component described itself through its [`ComponentManifest`]. The manifest's
+//! identity and operations map onto [`ComponentDescribe`]; JSON Schemas are converted to
+//! [`SchemaIr`], and everything without a v0.6.0 counterpart (supported flow kinds, world,
+//! profiles, capabilities, resources) is kept under `metadata` with the manifest field name.
+use alloc::{
+    collections::BTreeMap,
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use ciborium::value::Value;
+
+use crate::component::ComponentManifest;
+use crate::schemas::common::schema_ir::{AdditionalProperties, SchemaIr};
+use crate::schemas::component::v0_6_0::{
+    ComponentDescribe, ComponentInfo, ComponentOperation, ComponentRunInput, ComponentRunOutput,
+    schema_hash,
+};
+
+/// Role assumed for legacy components, which do not declare one.
+const LEGACY_ROLE: &str = "runtime";
+
+/// Registry hop for `greentic.component.describe` 0.5.0 -> 0.6.0.
+///
+/// The document is a legacy [`ComponentManifest`]. Warnings report the assumed role, moved
+/// manifest fields and every lossy JSON Schema conversion.
+pub(crate) fn adapt_component_describe_value(
+    value: Value,
+    warnings: &mut Vec<String>,
+) -> Result<Value, String> {
+    let manifest: ComponentManifest = value.deserialized().map_err(|err| err.to_string())?;
+    let describe = map_component_describe(&manifest, warnings)?;
+    Value::serialized(&describe).map_err(|err| err.to_string())
+}
+
+fn map_component_describe(
+    manifest: &ComponentManifest,
+    warnings: &mut Vec<String>,
+) -> Result<ComponentDescribe, String> {
+    warnings.push(format!(
+        "Legacy manifests have no role; assumed `{LEGACY_ROLE}`."
+    ));
+    let config_schema = match &manifest.config_schema {
+        Some(schema) => convert_schema(schema, "config_schema", warnings),
+        None => SchemaIr::Object {
+            properties: BTreeMap::new(),
+            required: Vec::new(),
+            additional: AdditionalProperties::Allow,
+        },
+    };
+    let operations = manifest
+        .operations
+        .iter()
+        .map(|operation| {
+            let input = convert_schema(
+                &operation.input_schema,
+                &format!("operations/{}/input_schema", operation.name),
+                warnings,
+            );
+            let output = convert_schema(
+                &operation.output_schema,
+                &format!("operations/{}/output_schema", operation.name),
+                warnings,
+            );
+            let schema_hash =
+                schema_hash(&input, &output, &config_schema).map_err(|err| err.to_string())?;
+            Ok(ComponentOperation {
+                id: operation.name.clone(),
+                display_name: None,
+                input: ComponentRunInput { schema: input },
+                output: ComponentRunOutput { schema: output },
+                defaults: BTreeMap::new(),
+                redactions: Vec::new(),
+                constraints: BTreeMap::new(),
+                schema_hash,
+            })
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    let mut metadata = BTreeMap::new();
+    let moved = [
+        ("supports", Value::serialized(&manifest.supports)),
+        ("world", Ok(Value::Text(manifest.world.clone()))),
+        ("profiles", Value::serialized(&manifest.profiles)),
+        ("capabilities", Value::serialized(&manifest.capabilities)),
+        ("resources", Value::serialized(&manifest.resources)),
+    ];
+    for (field, value) in moved {
+        metadata.insert(field.to_string(), value.map_err(|err| err.to_string())?);
+    }
+    warnings.push(
+        "Manifest `supports`, `world`, `profiles`, `capabilities` and `resources` were moved to \
+         `metadata`."
+            .to_string(),
+    );
+    if manifest.configurators.is_some() || !manifest.dev_flows.is_empty() {
+        warnings.push("Manifest `configurators` and `dev_flows` were dropped.".to_string());
+    }
+
+    Ok(ComponentDescribe {
+        info: ComponentInfo {
+            id: manifest.id.to_string(),
+            version: manifest.version.to_string(),
+            role: LEGACY_ROLE.to_string(),
+            display_name: None,
+        },
+        provided_capabilities: Vec::new(),
+        required_capabilities: Vec::new(),
+        metadata,
+        operations,
+        config_schema,
+    })
+}
+
+fn convert_schema(schema: &serde_json::Value, field: &str, warnings: &mut Vec<String>) -> SchemaIr {
+    let converted = SchemaIr::from_json_schema(schema);
+    for note in converted.notes {
+        warnings.push(format!("`{field}{}`: {}", note.pointer, note.message));
+    }
+    converted.schema
+}
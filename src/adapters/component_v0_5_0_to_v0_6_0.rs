@@ -1,5 +1,9 @@
 //! Adapter from legacy v0.5.0 QA to v0.6.0 Component QA.
-use alloc::{collections::BTreeMap, string::String, vec::Vec};
+use alloc::{
+    collections::BTreeMap,
+    string::{String, ToString},
+    vec::Vec,
+};
 
 use ciborium::value::Value;
 
@@ -32,6 +36,18 @@ pub fn adapt_component_qa_spec(
     Ok(CborBytes::new(bytes))
 }
 
+/// Registry hop for `greentic.component.qa` and `greentic.pack.qa` 0.5.0 -> 0.6.0.
+///
+/// Legacy specs carry no mode, so the adapted spec uses [`QaMode::Setup`] and a warning says so.
+pub(crate) fn adapt_component_qa_value(
+    value: Value,
+    warnings: &mut Vec<String>,
+) -> Result<Value, String> {
+    let legacy: LegacyComponentQaSpec = value.deserialized().map_err(|err| err.to_string())?;
+    warnings.push("Legacy QA specs have no mode; assumed `setup`.".to_string());
+    Value::serialized(&map_component_qa_spec(QaMode::Setup, &legacy)).map_err(|err| err.to_string())
+}
+
 fn map_component_qa_spec(mode: QaMode, legacy: &LegacyComponentQaSpec) -> ComponentQaSpec {
     let title_key = "legacy.component.v0_5_0.title".to_string();
     let description_key = "legacy.component.v0_5_0.description".to_string();
@@ -79,6 +95,8 @@ fn map_question(question: &crate::schemas::component::v0_5_0::qa::LegacyQuestion
         kind: map_kind(question),
         required: question.required,
         default: question.default.clone(),
+        visible_if: None,
+        required_if: None,
     }
 }
 
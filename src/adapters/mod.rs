@@ -1,2 +1,4 @@
 //! Legacy adapters for schema migration.
+pub mod component_describe_v0_5_0_to_v0_6_0;
 pub mod component_v0_5_0_to_v0_6_0;
+pub mod registry;
@@ -0,0 +1,387 @@
+//! Registry of versioned document adapters.
+//!
+//! An adapter hop converts one schema-versioned document (as a CBOR [`Value`]) from one version
+//! to another. [`AdapterRegistry`] keys hops by schema name (`greentic.component.qa`) and the
+//! from/to versions, and composes the shortest chain of hops for any requested migration, so a
+//! new schema version only needs one hop from its predecessor. [`AdapterRegistry::upgrade_to_latest`]
+//! takes the target version from [`SCHEMAS`].
+//!
+//! Pack manifests and flows carry a `schema_version` string (`pack-v1`, `flow-v1`) instead of a
+//! [`SCHEMAS`] id. Their hops are registered under the family name (`pack`, `flow`) with
+//! `major.0.0` versions, and [`AdapterRegistry::upgrade_pack_manifest`] /
+//! [`AdapterRegistry::upgrade_flow`] read the version from the document and migrate it to
+//! [`PACK_MANIFEST_SCHEMA_VERSION`] / [`FLOW_SCHEMA_VERSION`].
+
+use alloc::{
+    boxed::Box,
+    collections::{BTreeMap, BTreeSet, VecDeque},
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::fmt;
+
+use ciborium::value::Value;
+use semver::Version;
+use thiserror::Error;
+
+use crate::cbor::canonical;
+use crate::cbor_bytes::CborBytes;
+use crate::schema_registry::SCHEMAS;
+
+/// `schema_version` of the pack manifests written by this crate.
+pub const PACK_MANIFEST_SCHEMA_VERSION: &str = "pack-v1";
+/// `schema_version` of the flows written by this crate.
+pub const FLOW_SCHEMA_VERSION: &str = "flow-v1";
+
+/// Adapter function for a single hop. Pushes human-readable warnings for lossy conversions and
+/// returns an error message when the document cannot be converted.
+pub type AdapterFn = dyn Fn(Value, &mut Vec<String>) -> Result<Value, String> + Send + Sync;
+
+/// Errors produced while migrating a document.
+#[derive(Debug, Error)]
+pub enum AdapterError {
+    /// Schema id is not of the form `name@major.minor.patch`.
+    #[error("invalid schema id `{0}`; expected `name@major.minor.patch`")]
+    InvalidSchemaId(String),
+    /// `schema_version` is not of the form `<family>-v<major>` for the expected family.
+    #[error("invalid schema_version `{found}`; expected `{family}-v<major>`")]
+    InvalidSchemaVersion {
+        /// Expected family (`pack`, `flow`).
+        family: String,
+        /// Value found in the document.
+        found: String,
+    },
+    /// The document has no `schema_version` text field.
+    #[error("document has no `schema_version`")]
+    MissingSchemaVersion,
+    /// Schema name has no entry in [`SCHEMAS`].
+    #[error("schema `{0}` is not listed in SCHEMAS")]
+    UnknownSchema(String),
+    /// No chain of registered hops connects the two versions.
+    #[error("no adapter path for `{schema}` from {from} to {to}")]
+    NoPath {
+        /// Schema name.
+        schema: String,
+        /// Source version.
+        from: String,
+        /// Target version.
+        to: String,
+    },
+    /// A hop rejected the document.
+    #[error("adapter for `{schema}` {from} -> {to} failed: {message}")]
+    Hop {
+        /// Schema name.
+        schema: String,
+        /// Source version of the failing hop.
+        from: String,
+        /// Target version of the failing hop.
+        to: String,
+        /// Message returned by the hop.
+        message: String,
+    },
+    /// Encoding the migrated document failed.
+    #[error(transparent)]
+    Canonical(#[from] canonical::CanonicalError),
+}
+
+/// One applied hop and the warnings it produced.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HopReport {
+    /// Source version.
+    pub from: Version,
+    /// Target version.
+    pub to: Version,
+    /// Lossy or defaulted conversions made by the hop.
+    pub warnings: Vec<String>,
+}
+
+/// Result of a migration.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Migration {
+    /// Schema id of the migrated document (`name@version`).
+    pub schema_id: String,
+    /// Migrated document.
+    pub value: Value,
+    /// Applied hops in order; empty when the document was already at the target version.
+    pub hops: Vec<HopReport>,
+}
+
+impl Migration {
+    /// Warnings of every hop, in order.
+    pub fn warnings(&self) -> impl Iterator<Item = &str> {
+        self.hops
+            .iter()
+            .flat_map(|hop| hop.warnings.iter().map(String::as_str))
+    }
+
+    /// Encodes the migrated document as canonical CBOR.
+    pub fn to_cbor(&self) -> Result<CborBytes, AdapterError> {
+        Ok(CborBytes::new(canonical::to_canonical_cbor(&self.value)?))
+    }
+}
+
+struct Hop {
+    from: Version,
+    to: Version,
+    adapt: Box<AdapterFn>,
+}
+
+/// Adapter hops keyed by schema name and from/to version.
+#[derive(Default)]
+pub struct AdapterRegistry {
+    hops: BTreeMap<String, Vec<Hop>>,
+}
+
+impl fmt::Debug for AdapterRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut map = f.debug_map();
+        for (schema, hops) in &self.hops {
+            let edges: Vec<String> = hops
+                .iter()
+                .map(|hop| format!("{} -> {}", hop.from, hop.to))
+                .collect();
+            map.entry(schema, &edges);
+        }
+        map.finish()
+    }
+}
+
+impl AdapterRegistry {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a registry with the adapters shipped by this crate.
+    ///
+    /// - `greentic.component.qa` 0.5.0 -> 0.6.0 (legacy QA specs; the mode defaults to `setup`).
+    /// - `greentic.pack.qa` 0.5.0 -> 0.6.0 (the same legacy QA specs; pack QA shares the
+    ///   component QA layout).
+    /// - `greentic.component.describe` 0.5.0 -> 0.6.0 (legacy component manifests; the role
+    ///   defaults to `runtime`).
+    pub fn builtin() -> Self {
+        let mut registry = Self::new();
+        for schema in ["greentic.component.qa", "greentic.pack.qa"] {
+            registry.register(
+                schema,
+                Version::new(0, 5, 0),
+                Version::new(0, 6, 0),
+                super::component_v0_5_0_to_v0_6_0::adapt_component_qa_value,
+            );
+        }
+        registry.register(
+            "greentic.component.describe",
+            Version::new(0, 5, 0),
+            Version::new(0, 6, 0),
+            super::component_describe_v0_5_0_to_v0_6_0::adapt_component_describe_value,
+        );
+        registry
+    }
+
+    /// Registers a hop, replacing an existing hop for the same schema and versions.
+    pub fn register<F>(&mut self, schema: &str, from: Version, to: Version, adapt: F) -> &mut Self
+    where
+        F: Fn(Value, &mut Vec<String>) -> Result<Value, String> + Send + Sync + 'static,
+    {
+        let hops = self.hops.entry(schema.to_string()).or_default();
+        hops.retain(|hop| hop.from != from || hop.to != to);
+        hops.push(Hop {
+            from,
+            to,
+            adapt: Box::new(adapt),
+        });
+        self
+    }
+
+    /// Shortest chain of registered hops from `from` to `to`, as version pairs.
+    ///
+    /// Ties are broken by registration order. Returns an empty chain when the versions are equal.
+    pub fn path(
+        &self,
+        schema: &str,
+        from: &Version,
+        to: &Version,
+    ) -> Option<Vec<(Version, Version)>> {
+        self.hop_path(schema, from, to).map(|hops| {
+            hops.into_iter()
+                .map(|hop| (hop.from.clone(), hop.to.clone()))
+                .collect()
+        })
+    }
+
+    /// Migrates a document between two versions of a schema.
+    pub fn migrate(
+        &self,
+        schema: &str,
+        from: &Version,
+        to: &Version,
+        mut value: Value,
+    ) -> Result<Migration, AdapterError> {
+        let hops = self
+            .hop_path(schema, from, to)
+            .ok_or_else(|| AdapterError::NoPath {
+                schema: schema.to_string(),
+                from: from.to_string(),
+                to: to.to_string(),
+            })?;
+        let mut reports = Vec::with_capacity(hops.len());
+        for hop in hops {
+            let mut warnings = Vec::new();
+            value = (hop.adapt)(value, &mut warnings).map_err(|message| AdapterError::Hop {
+                schema: schema.to_string(),
+                from: hop.from.to_string(),
+                to: hop.to.to_string(),
+                message,
+            })?;
+            reports.push(HopReport {
+                from: hop.from.clone(),
+                to: hop.to.clone(),
+                warnings,
+            });
+        }
+        Ok(Migration {
+            schema_id: format!("{schema}@{to}"),
+            value,
+            hops: reports,
+        })
+    }
+
+    /// Migrates a document identified by `schema_id` (`name@version`) to the newest version of
+    /// that schema listed in [`SCHEMAS`].
+    pub fn upgrade_to_latest(
+        &self,
+        schema_id: &str,
+        value: Value,
+    ) -> Result<Migration, AdapterError> {
+        let (schema, from) = split_schema_id(schema_id)?;
+        let latest = latest_version(schema)?;
+        self.migrate(schema, &from, &latest, value)
+    }
+
+    /// CBOR counterpart of [`AdapterRegistry::upgrade_to_latest`].
+    pub fn upgrade_cbor_to_latest(
+        &self,
+        schema_id: &str,
+        bytes: &CborBytes,
+    ) -> Result<Migration, AdapterError> {
+        let value = canonical::from_cbor(bytes.as_slice())?;
+        self.upgrade_to_latest(schema_id, value)
+    }
+
+    /// Migrates a pack manifest document to [`PACK_MANIFEST_SCHEMA_VERSION`], using the hops
+    /// registered under `pack`.
+    pub fn upgrade_pack_manifest(&self, value: Value) -> Result<Migration, AdapterError> {
+        self.upgrade_schema_version(PACK_MANIFEST_SCHEMA_VERSION, value)
+    }
+
+    /// Migrates a flow document to [`FLOW_SCHEMA_VERSION`], using the hops registered under
+    /// `flow`.
+    pub fn upgrade_flow(&self, value: Value) -> Result<Migration, AdapterError> {
+        self.upgrade_schema_version(FLOW_SCHEMA_VERSION, value)
+    }
+
+    /// Migrates a `schema_version`-tagged document and rewrites its `schema_version` to `latest`.
+    fn upgrade_schema_version(
+        &self,
+        latest: &str,
+        value: Value,
+    ) -> Result<Migration, AdapterError> {
+        let (family, to) = split_schema_version(latest, None)?;
+        let found = schema_version_field(&value).ok_or(AdapterError::MissingSchemaVersion)?;
+        let (_, from) = split_schema_version(found, Some(family))?;
+        let mut migration = self.migrate(family, &from, &to, value)?;
+        if let Value::Map(entries) = &mut migration.value {
+            let latest = Value::Text(latest.to_string());
+            match entries
+                .iter_mut()
+                .find(|(key, _)| key.as_text() == Some("schema_version"))
+            {
+                Some((_, value)) => *value = latest,
+                None => entries.push((Value::Text("schema_version".into()), latest)),
+            }
+        }
+        migration.schema_id = latest.to_string();
+        Ok(migration)
+    }
+
+    fn hop_path(&self, schema: &str, from: &Version, to: &Version) -> Option<Vec<&Hop>> {
+        if from == to {
+            return Some(Vec::new());
+        }
+        let hops = self.hops.get(schema)?;
+        // Breadth-first search over versions; `previous` records the hop that reached a version.
+        let mut previous: BTreeMap<&Version, &Hop> = BTreeMap::new();
+        let mut seen = BTreeSet::from([from]);
+        let mut queue = VecDeque::from([from]);
+        while let Some(version) = queue.pop_front() {
+            for hop in hops.iter().filter(|hop| &hop.from == version) {
+                if !seen.insert(&hop.to) {
+                    continue;
+                }
+                previous.insert(&hop.to, hop);
+                if &hop.to == to {
+                    let mut path = Vec::new();
+                    let mut cursor = to;
+                    while let Some(hop) = previous.get(cursor) {
+                        path.push(*hop);
+                        cursor = &hop.from;
+                    }
+                    path.reverse();
+                    return Some(path);
+                }
+                queue.push_back(&hop.to);
+            }
+        }
+        None
+    }
+}
+
+/// Splits `name@major.minor.patch` into the schema name and version.
+pub fn split_schema_id(schema_id: &str) -> Result<(&str, Version), AdapterError> {
+    schema_id
+        .rsplit_once('@')
+        .filter(|(name, _)| !name.is_empty())
+        .and_then(|(name, version)| Version::parse(version).ok().map(|version| (name, version)))
+        .ok_or_else(|| AdapterError::InvalidSchemaId(schema_id.to_string()))
+}
+
+/// Splits a `schema_version` string (`pack-v1`) into its family and `major.0.0` version.
+///
+/// When `family` is given, other families are rejected.
+pub fn split_schema_version<'a>(
+    schema_version: &'a str,
+    family: Option<&str>,
+) -> Result<(&'a str, Version), AdapterError> {
+    let invalid = || AdapterError::InvalidSchemaVersion {
+        family: family.unwrap_or("<family>").to_string(),
+        found: schema_version.to_string(),
+    };
+    let (name, major) = schema_version.rsplit_once("-v").ok_or_else(invalid)?;
+    if name.is_empty() || family.is_some_and(|family| family != name) {
+        return Err(invalid());
+    }
+    let major = major.parse::<u64>().map_err(|_| invalid())?;
+    Ok((name, Version::new(major, 0, 0)))
+}
+
+fn schema_version_field(value: &Value) -> Option<&str> {
+    let Value::Map(entries) = value else {
+        return None;
+    };
+    entries
+        .iter()
+        .find(|(key, _)| key.as_text() == Some("schema_version"))
+        .and_then(|(_, value)| value.as_text())
+}
+
+/// Newest version of `schema` listed in [`SCHEMAS`].
+pub fn latest_version(schema: &str) -> Result<Version, AdapterError> {
+    SCHEMAS
+        .iter()
+        .filter_map(|def| split_schema_id(def.id).ok())
+        .filter(|(name, _)| *name == schema)
+        .map(|(_, version)| version)
+        .max()
+        .ok_or_else(|| AdapterError::UnknownSchema(schema.to_string()))
+}
@@ -0,0 +1,433 @@
+//! Cross-checks between flow resolve sidecars and the pack manifest.
+//!
+//! The `flow_resolve` and `flow_resolve_summary` helpers validate each sidecar in isolation. The
+//! checks in this module load a flow together with its `.ygtc.resolve.json` and
+//! `.ygtc.resolve.summary.json` sidecars and the owning [`PackManifest`], and report any drift
+//! between them as a [`ValidationReport`]:
+//!
+//! - every flow node has a resolve (and summary) entry,
+//! - no stale entries remain for nodes that were removed from the flow,
+//! - summary sources, component ids and digests agree with the resolve sidecar,
+//! - resolve sources and digests agree with the `greentic.pack.component_sources@v1` extension,
+//! - summary manifest metadata agrees with `PackManifest.components`.
+//!
+//! [`regenerate_flow_resolve_summary`] rebuilds the summary deterministically from the same
+//! inputs so tooling can rewrite a drifted summary instead of patching it by hand.
+
+use alloc::borrow::ToOwned;
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use serde_json::json;
+
+use crate::flow::{Flow, Node};
+use crate::flow_resolve::{ComponentSourceRefV1, FlowResolveV1};
+use crate::flow_resolve_summary::{
+    FLOW_RESOLVE_SUMMARY_SCHEMA_VERSION, FlowResolveSummaryManifestV1,
+    FlowResolveSummarySourceRefV1, FlowResolveSummaryV1, NodeResolveSummaryV1,
+};
+use crate::pack::extensions::component_sources::{ComponentSourceEntryV1, ComponentSourcesV1};
+use crate::validate::{Diagnostic, Severity, ValidationReport};
+use crate::{ComponentSourceRef, ErrorCode, GResult, GreenticError, PackManifest};
+
+#[cfg(all(feature = "std", feature = "serde"))]
+use crate::flow_resolve::{read_flow_resolve, sidecar_path_for_flow};
+#[cfg(all(feature = "std", feature = "serde"))]
+use crate::flow_resolve_summary::{
+    read_flow_resolve_summary, resolve_summary_path_for_flow, write_flow_resolve_summary,
+};
+#[cfg(all(feature = "std", feature = "serde"))]
+use std::path::Path;
+
+/// Checks a flow, its resolve sidecar, optional summary sidecar, and the pack manifest for drift.
+///
+/// Returns a report whose `pack_id`/`pack_version` are taken from the manifest. An empty
+/// diagnostics list means the sidecars are consistent.
+#[cfg(feature = "serde")]
+pub fn check_flow_resolve_consistency(
+    flow: &Flow,
+    resolve: &FlowResolveV1,
+    summary: Option<&FlowResolveSummaryV1>,
+    manifest: &PackManifest,
+) -> ValidationReport {
+    let mut report = ValidationReport {
+        pack_id: Some(manifest.pack_id.clone()),
+        pack_version: Some(manifest.version.clone()),
+        diagnostics: Vec::new(),
+    };
+
+    let sources = match manifest.get_component_sources_v1() {
+        Ok(sources) => sources,
+        Err(err) => {
+            report.push(check_diagnostic(
+                "FLOW_RESOLVE_COMPONENT_SOURCES_INVALID",
+                format!("Component sources extension could not be decoded: {err}."),
+                "extensions.greentic.pack.component_sources@v1".to_owned(),
+                "Fix or regenerate the component sources extension.",
+            ));
+            None
+        }
+    };
+
+    if let Some(summary) = summary
+        && summary.flow != resolve.flow
+    {
+        report.push(check_diagnostic(
+            "FLOW_RESOLVE_SUMMARY_FLOW_MISMATCH",
+            format!(
+                "Summary sidecar targets flow '{}' but the resolve sidecar targets '{}'.",
+                summary.flow, resolve.flow
+            ),
+            "flow".to_owned(),
+            "Regenerate the summary from the resolve sidecar.",
+        ));
+    }
+
+    for (node_id, node) in flow.nodes.iter() {
+        let node_name = node_id.as_str();
+        let Some(resolved) = resolve.nodes.get(node_name) else {
+            report.push(check_diagnostic(
+                "FLOW_RESOLVE_NODE_UNRESOLVED",
+                format!("Flow node '{node_name}' has no resolve entry."),
+                format!("nodes.{node_name}"),
+                "Resolve the node component and update the resolve sidecar.",
+            ));
+            continue;
+        };
+
+        let entry = sources
+            .as_ref()
+            .and_then(|sources| find_source_entry(sources, node));
+        if let Some(entry) = entry {
+            check_source_entry(&mut report, node_name, &resolved.source, entry);
+        }
+
+        let Some(summary) = summary else {
+            continue;
+        };
+        let Some(summarized) = summary.nodes.get(node_name) else {
+            report.push(check_diagnostic(
+                "FLOW_RESOLVE_SUMMARY_NODE_MISSING",
+                format!("Flow node '{node_name}' has no resolve summary entry."),
+                format!("nodes.{node_name}"),
+                "Regenerate the resolve summary sidecar.",
+            ));
+            continue;
+        };
+        check_summary_node(&mut report, node_name, node, &resolved.source, summarized);
+        if let Some(entry) = entry
+            && summarized.digest != entry.resolved.digest
+        {
+            report.push(check_diagnostic(
+                "FLOW_RESOLVE_DIGEST_MISMATCH",
+                format!(
+                    "Summary digest for node '{node_name}' does not match the component sources digest '{}'.",
+                    entry.resolved.digest
+                ),
+                format!("nodes.{node_name}.digest"),
+                "Re-resolve the component and regenerate the sidecars.",
+            ));
+        }
+        if let Some(metadata) = &summarized.manifest
+            && let Some(component) = manifest
+                .components
+                .iter()
+                .find(|component| component.id == node.component.id)
+            && (metadata.world != component.world || metadata.version != component.version)
+        {
+            report.push(check_diagnostic(
+                "FLOW_RESOLVE_SUMMARY_MANIFEST_MISMATCH",
+                format!(
+                    "Summary manifest metadata for node '{node_name}' does not match component '{}'.",
+                    component.id.as_str()
+                ),
+                format!("nodes.{node_name}.manifest"),
+                "Regenerate the resolve summary sidecar.",
+            ));
+        }
+    }
+
+    for node_name in resolve.nodes.keys() {
+        if !flow_has_node(flow, node_name) {
+            report.push(check_diagnostic(
+                "FLOW_RESOLVE_STALE_NODE",
+                format!("Resolve entry '{node_name}' does not match any flow node."),
+                format!("nodes.{node_name}"),
+                "Remove the stale entry from the resolve sidecar.",
+            ));
+        }
+    }
+    if let Some(summary) = summary {
+        for node_name in summary.nodes.keys() {
+            if !flow_has_node(flow, node_name) {
+                report.push(check_diagnostic(
+                    "FLOW_RESOLVE_SUMMARY_STALE_NODE",
+                    format!("Resolve summary entry '{node_name}' does not match any flow node."),
+                    format!("nodes.{node_name}"),
+                    "Regenerate the resolve summary sidecar.",
+                ));
+            }
+        }
+    }
+
+    report
+}
+
+/// Reads both sidecars next to `flow_path` and checks them against the flow and manifest.
+///
+/// The summary sidecar is optional; when it does not exist only the resolve sidecar is checked.
+#[cfg(all(feature = "std", feature = "serde"))]
+pub fn check_flow_sidecars(
+    flow_path: &Path,
+    flow: &Flow,
+    manifest: &PackManifest,
+) -> GResult<ValidationReport> {
+    let resolve = read_flow_resolve(&sidecar_path_for_flow(flow_path))?;
+    let summary_path = resolve_summary_path_for_flow(flow_path);
+    let summary = if summary_path.exists() {
+        Some(read_flow_resolve_summary(&summary_path)?)
+    } else {
+        None
+    };
+    Ok(check_flow_resolve_consistency(
+        flow,
+        &resolve,
+        summary.as_ref(),
+        manifest,
+    ))
+}
+
+/// Rebuilds the resolve summary for a flow from its resolve sidecar and the pack manifest.
+///
+/// Only nodes present in the flow are emitted, so stale entries are dropped. Digests come from
+/// the pinned resolve digest, falling back to the component sources extension; manifest metadata
+/// comes from the matching entry in `PackManifest.components`.
+#[cfg(feature = "serde")]
+pub fn regenerate_flow_resolve_summary(
+    flow: &Flow,
+    resolve: &FlowResolveV1,
+    manifest: &PackManifest,
+) -> GResult<FlowResolveSummaryV1> {
+    let sources = manifest.get_component_sources_v1().map_err(|err| {
+        GreenticError::new(
+            ErrorCode::InvalidInput,
+            format!("component sources extension: {err}"),
+        )
+    })?;
+
+    let mut nodes = BTreeMap::new();
+    for (node_id, node) in flow.nodes.iter() {
+        let node_name = node_id.as_str();
+        let resolved = resolve.nodes.get(node_name).ok_or_else(|| {
+            GreenticError::new(
+                ErrorCode::NotFound,
+                format!("flow node '{node_name}' has no resolve entry"),
+            )
+        })?;
+        let digest = source_digest(&resolved.source)
+            .map(ToOwned::to_owned)
+            .or_else(|| {
+                sources
+                    .as_ref()
+                    .and_then(|sources| find_source_entry(sources, node))
+                    .map(|entry| entry.resolved.digest.clone())
+            })
+            .ok_or_else(|| {
+                GreenticError::new(
+                    ErrorCode::InvalidInput,
+                    format!("flow node '{node_name}' has no pinned digest"),
+                )
+            })?;
+        let metadata = manifest
+            .components
+            .iter()
+            .find(|component| component.id == node.component.id)
+            .map(|component| FlowResolveSummaryManifestV1 {
+                world: component.world.clone(),
+                version: component.version.clone(),
+            });
+        nodes.insert(
+            node_name.to_owned(),
+            NodeResolveSummaryV1 {
+                component_id: node.component.id.clone(),
+                source: summary_source(&resolved.source),
+                digest,
+                manifest: metadata,
+            },
+        );
+    }
+
+    Ok(FlowResolveSummaryV1 {
+        schema_version: FLOW_RESOLVE_SUMMARY_SCHEMA_VERSION,
+        flow: resolve.flow.clone(),
+        nodes,
+    })
+}
+
+/// Regenerates the summary sidecar next to `flow_path` and writes it to disk.
+#[cfg(all(feature = "std", feature = "serde"))]
+pub fn write_regenerated_flow_resolve_summary(
+    flow_path: &Path,
+    flow: &Flow,
+    manifest: &PackManifest,
+) -> GResult<FlowResolveSummaryV1> {
+    let resolve = read_flow_resolve(&sidecar_path_for_flow(flow_path))?;
+    let summary = regenerate_flow_resolve_summary(flow, &resolve, manifest)?;
+    write_flow_resolve_summary(&resolve_summary_path_for_flow(flow_path), &summary)?;
+    Ok(summary)
+}
+
+fn flow_has_node(flow: &Flow, node_name: &str) -> bool {
+    flow.nodes
+        .keys()
+        .any(|node_id| node_id.as_str() == node_name)
+}
+
+fn find_source_entry<'a>(
+    sources: &'a ComponentSourcesV1,
+    node: &Node,
+) -> Option<&'a ComponentSourceEntryV1> {
+    let component_id = node.component.id.as_str();
+    sources.components.iter().find(|entry| {
+        entry
+            .component_id
+            .as_ref()
+            .is_some_and(|id| id.as_str() == component_id)
+            || entry.name == component_id
+    })
+}
+
+fn check_source_entry(
+    report: &mut ValidationReport,
+    node_name: &str,
+    source: &ComponentSourceRefV1,
+    entry: &ComponentSourceEntryV1,
+) {
+    if !source_matches_entry(source, &entry.source) {
+        report.push(check_diagnostic(
+            "FLOW_RESOLVE_COMPONENT_SOURCE_MISMATCH",
+            format!(
+                "Resolve source for node '{node_name}' does not match component source '{}'.",
+                entry.source
+            ),
+            format!("nodes.{node_name}.source"),
+            "Re-resolve the node against the pack component sources.",
+        ));
+    }
+    if let Some(digest) = source_digest(source)
+        && digest != entry.resolved.digest
+    {
+        report.push(check_diagnostic(
+            "FLOW_RESOLVE_DIGEST_MISMATCH",
+            format!(
+                "Resolve digest for node '{node_name}' does not match the component sources digest '{}'.",
+                entry.resolved.digest
+            ),
+            format!("nodes.{node_name}.source.digest"),
+            "Re-resolve the component and regenerate the sidecars.",
+        ));
+    }
+}
+
+fn check_summary_node(
+    report: &mut ValidationReport,
+    node_name: &str,
+    node: &Node,
+    source: &ComponentSourceRefV1,
+    summarized: &NodeResolveSummaryV1,
+) {
+    if summarized.component_id != node.component.id {
+        report.push(check_diagnostic(
+            "FLOW_RESOLVE_SUMMARY_COMPONENT_MISMATCH",
+            format!(
+                "Summary component '{}' for node '{node_name}' does not match flow component '{}'.",
+                summarized.component_id.as_str(),
+                node.component.id.as_str()
+            ),
+            format!("nodes.{node_name}.component_id"),
+            "Regenerate the resolve summary sidecar.",
+        ));
+    }
+    if summarized.source != summary_source(source) {
+        report.push(check_diagnostic(
+            "FLOW_RESOLVE_SUMMARY_SOURCE_MISMATCH",
+            format!("Summary source for node '{node_name}' does not match the resolve sidecar."),
+            format!("nodes.{node_name}.source"),
+            "Regenerate the resolve summary sidecar.",
+        ));
+    }
+    if let Some(digest) = source_digest(source)
+        && digest != summarized.digest
+    {
+        report.push(check_diagnostic(
+            "FLOW_RESOLVE_DIGEST_MISMATCH",
+            format!(
+                "Summary digest for node '{node_name}' does not match the pinned resolve digest '{digest}'."
+            ),
+            format!("nodes.{node_name}.digest"),
+            "Regenerate the resolve summary sidecar.",
+        ));
+    }
+}
+
+fn source_digest(source: &ComponentSourceRefV1) -> Option<&str> {
+    match source {
+        ComponentSourceRefV1::Local { digest, .. }
+        | ComponentSourceRefV1::Oci { digest, .. }
+        | ComponentSourceRefV1::Repo { digest, .. }
+        | ComponentSourceRefV1::Store { digest, .. } => digest.as_deref(),
+    }
+}
+
+fn summary_source(source: &ComponentSourceRefV1) -> FlowResolveSummarySourceRefV1 {
+    match source {
+        ComponentSourceRefV1::Local { path, .. } => {
+            FlowResolveSummarySourceRefV1::Local { path: path.clone() }
+        }
+        ComponentSourceRefV1::Oci { r#ref, .. } => FlowResolveSummarySourceRefV1::Oci {
+            r#ref: r#ref.clone(),
+        },
+        ComponentSourceRefV1::Repo { r#ref, .. } => FlowResolveSummarySourceRefV1::Repo {
+            r#ref: r#ref.clone(),
+        },
+        ComponentSourceRefV1::Store { r#ref, .. } => FlowResolveSummarySourceRefV1::Store {
+            r#ref: r#ref.clone(),
+        },
+    }
+}
+
+fn source_matches_entry(source: &ComponentSourceRefV1, entry: &ComponentSourceRef) -> bool {
+    let (reference, scheme) = match (source, entry) {
+        (ComponentSourceRefV1::Local { path, .. }, ComponentSourceRef::File(_)) => (path, "file"),
+        (ComponentSourceRefV1::Oci { r#ref, .. }, ComponentSourceRef::Oci(_)) => (r#ref, "oci"),
+        (ComponentSourceRefV1::Repo { r#ref, .. }, ComponentSourceRef::Repo(_)) => (r#ref, "repo"),
+        (ComponentSourceRefV1::Store { r#ref, .. }, ComponentSourceRef::Store(_)) => {
+            (r#ref, "store")
+        }
+        _ => return false,
+    };
+    let reference = reference
+        .strip_prefix(scheme)
+        .and_then(|rest| rest.strip_prefix("://"))
+        .unwrap_or(reference);
+    reference == entry.reference()
+}
+
+fn check_diagnostic(code: &str, message: String, path: String, hint: &str) -> Diagnostic {
+    let node = path
+        .strip_prefix("nodes.")
+        .map(|rest| rest.split('.').next().unwrap_or(rest).to_owned());
+    Diagnostic {
+        severity: Severity::Error,
+        code: code.to_owned(),
+        message,
+        path: Some(path),
+        hint: Some(hint.to_owned()),
+        data: match node {
+            Some(node) => json!({ "node": node }),
+            None => serde_json::Value::Null,
+        },
+    }
+}
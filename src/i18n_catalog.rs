@@ -0,0 +1,351 @@
+//! Message catalogs with BCP 47 locale fallback.
+//!
+//! A [`MessageCatalog`] maps locale tags to message keys to messages. Lookups walk a fallback
+//! chain built by truncating the requested tag one subtag at a time (`de-Latn-CH` → `de-Latn`
+//! → `de`), followed by the catalog's default locale and its truncations; [`I18nText`] values
+//! finally fall back to their own `fallback` string and then the key. Locale tags are
+//! normalized through [`I18nTag`], so `en_gb` and `en-GB` name the same messages.
+//!
+//! [`bundle_pack_catalogs`] merges a pack catalog with the catalogs of the components it ships:
+//! pack messages always win, component messages only fill keys the pack leaves undefined, the
+//! first component defining a key wins on conflicts, and the pack's default locale is kept.
+
+use alloc::{
+    collections::{BTreeMap, BTreeSet},
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use thiserror::Error;
+
+use crate::cbor::canonical;
+use crate::cbor_bytes::CborBytes;
+use crate::i18n::{I18nTag, I18nTagError};
+use crate::i18n_text::I18nText;
+use crate::validate::{Diagnostic, Severity};
+
+/// Messages keyed by locale, then message key.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct MessageCatalog {
+    /// Locale consulted after the requested locale's chain (the pack default).
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub default_locale: Option<String>,
+    /// Messages keyed by normalized locale tag, then message key.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "BTreeMap::is_empty")
+    )]
+    pub locales: BTreeMap<String, BTreeMap<String, String>>,
+}
+
+/// Errors produced while building or loading a catalog.
+#[derive(Debug, Error)]
+pub enum MessageCatalogError {
+    /// A locale tag is not valid BCP 47.
+    #[error("locale `{locale}`: {source}")]
+    Locale {
+        /// Offending tag.
+        locale: String,
+        /// Parse failure.
+        #[source]
+        source: I18nTagError,
+    },
+    /// Two locale entries normalize to the same tag.
+    #[error("locales `{first}` and `{second}` normalize to the same tag")]
+    DuplicateLocale {
+        /// First spelling.
+        first: String,
+        /// Second spelling.
+        second: String,
+    },
+    /// JSON input could not be parsed.
+    #[error("invalid catalog JSON: {0}")]
+    Json(String),
+    /// CBOR input could not be decoded or encoded.
+    #[error(transparent)]
+    Canonical(#[from] canonical::CanonicalError),
+}
+
+impl MessageCatalog {
+    /// Creates an empty catalog without a default locale.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builds a catalog from per-locale message maps, such as `PackYamlLoad::i18n`.
+    pub fn from_locales(
+        locales: BTreeMap<String, BTreeMap<String, String>>,
+        default_locale: Option<&str>,
+    ) -> Result<Self, MessageCatalogError> {
+        Self {
+            default_locale: default_locale.map(str::to_string),
+            locales,
+        }
+        .normalized()
+    }
+
+    /// Parses a catalog from JSON (`{"default_locale": "en", "locales": {"en": {...}}}`).
+    pub fn from_json(json: &str) -> Result<Self, MessageCatalogError> {
+        let catalog: Self =
+            serde_json::from_str(json).map_err(|err| MessageCatalogError::Json(err.to_string()))?;
+        catalog.normalized()
+    }
+
+    /// Decodes a catalog from CBOR.
+    pub fn from_cbor(bytes: &CborBytes) -> Result<Self, MessageCatalogError> {
+        let catalog: Self = canonical::from_cbor(bytes.as_slice())?;
+        catalog.normalized()
+    }
+
+    /// Encodes the catalog as canonical CBOR.
+    pub fn to_cbor(&self) -> Result<CborBytes, MessageCatalogError> {
+        Ok(CborBytes::new(canonical::to_canonical_cbor(self)?))
+    }
+
+    /// Sets the default locale.
+    pub fn with_default_locale(mut self, locale: &str) -> Result<Self, MessageCatalogError> {
+        self.default_locale = Some(normalize(locale)?);
+        Ok(self)
+    }
+
+    /// Adds or replaces a message.
+    pub fn insert(
+        &mut self,
+        locale: &str,
+        key: impl Into<String>,
+        message: impl Into<String>,
+    ) -> Result<(), MessageCatalogError> {
+        self.locales
+            .entry(normalize(locale)?)
+            .or_default()
+            .insert(key.into(), message.into());
+        Ok(())
+    }
+
+    /// Locales to consult for `locale`, most specific first, ending with the default locale.
+    ///
+    /// Tags that fail to parse are used verbatim.
+    pub fn fallback_chain(&self, locale: &str) -> Vec<String> {
+        let mut chain = Vec::new();
+        push_truncations(locale, &mut chain);
+        if let Some(default) = &self.default_locale {
+            push_truncations(default, &mut chain);
+        }
+        chain
+    }
+
+    /// Looks up `key` along the fallback chain for `locale`.
+    pub fn message(&self, locale: &str, key: &str) -> Option<&str> {
+        self.fallback_chain(locale).iter().find_map(|tag| {
+            self.locales
+                .get(tag)
+                .and_then(|messages| messages.get(key))
+                .map(String::as_str)
+        })
+    }
+
+    /// Resolves `text` for `locale`: the catalog chain, then [`I18nText::fallback`], then the key.
+    pub fn resolve<'a>(&'a self, locale: &str, text: &'a I18nText) -> &'a str {
+        self.message(locale, &text.key)
+            .or(text.fallback.as_deref())
+            .unwrap_or(&text.key)
+    }
+
+    /// Message maps along the fallback chain for `locale`, most specific first.
+    pub fn chain_messages(&self, locale: &str) -> Vec<&BTreeMap<String, String>> {
+        self.fallback_chain(locale)
+            .iter()
+            .filter_map(|tag| self.locales.get(tag))
+            .collect()
+    }
+
+    /// Keys from `keys` that `locale` cannot resolve, even through its fallback chain.
+    pub fn missing_keys<'k>(
+        &self,
+        locale: &str,
+        keys: impl IntoIterator<Item = &'k str>,
+    ) -> Vec<&'k str> {
+        let chain = self.chain_messages(locale);
+        keys.into_iter()
+            .filter(|key| !chain.iter().any(|messages| messages.contains_key(*key)))
+            .collect()
+    }
+
+    fn normalized(self) -> Result<Self, MessageCatalogError> {
+        let default_locale = self.default_locale.as_deref().map(normalize).transpose()?;
+        let mut locales = BTreeMap::new();
+        let mut spellings: BTreeMap<String, String> = BTreeMap::new();
+        for (locale, messages) in self.locales {
+            let tag = normalize(&locale)?;
+            if let Some(first) = spellings.insert(tag.clone(), locale.clone()) {
+                return Err(MessageCatalogError::DuplicateLocale {
+                    first,
+                    second: locale,
+                });
+            }
+            locales.insert(tag, messages);
+        }
+        Ok(Self {
+            default_locale,
+            locales,
+        })
+    }
+}
+
+/// Merges a pack catalog with the catalogs of its components.
+///
+/// Rules, in order:
+/// - pack messages are kept as-is;
+/// - component messages fill keys the pack does not define for that locale, in the given order;
+///   when two components define a key differently the first wins (`I18N_CATALOG_CONFLICT`);
+/// - the bundle uses the pack's default locale, or the first component default when the pack has
+///   none (`I18N_CATALOG_NO_DEFAULT` when neither declares one);
+/// - the default locale must have messages (`I18N_CATALOG_DEFAULT_MISSING`), and every key
+///   defined for another locale should also be defined for it, since lookups end there
+///   (`I18N_CATALOG_MISSING_DEFAULT_KEY`).
+pub fn bundle_pack_catalogs(
+    pack: &MessageCatalog,
+    components: &[(&str, &MessageCatalog)],
+) -> (MessageCatalog, Vec<Diagnostic>) {
+    let mut diagnostics = Vec::new();
+    let mut bundle = pack.clone();
+    // Which component contributed each (locale, key), for conflict reports.
+    let mut origin: BTreeMap<(String, String), &str> = BTreeMap::new();
+    for (component, catalog) in components {
+        for (locale, messages) in &catalog.locales {
+            let pack_messages = pack.locales.get(locale);
+            for (key, message) in messages {
+                if pack_messages.is_some_and(|pack_messages| pack_messages.contains_key(key)) {
+                    continue;
+                }
+                let target = bundle.locales.entry(locale.clone()).or_default();
+                match target.get(key) {
+                    None => {
+                        target.insert(key.clone(), message.clone());
+                        origin.insert((locale.clone(), key.clone()), component);
+                    }
+                    Some(existing) if existing != message => {
+                        let first = origin
+                            .get(&(locale.clone(), key.clone()))
+                            .copied()
+                            .unwrap_or_default();
+                        diagnostics.push(catalog_diagnostic(
+                            Severity::Warn,
+                            "I18N_CATALOG_CONFLICT",
+                            format!(
+                                "Components `{first}` and `{component}` define `{key}` differently for `{locale}`; keeping `{first}`."
+                            ),
+                            locale,
+                            Some(key),
+                        ));
+                    }
+                    Some(_) => {}
+                }
+            }
+        }
+    }
+
+    if bundle.default_locale.is_none() {
+        bundle.default_locale = components
+            .iter()
+            .find_map(|(_, catalog)| catalog.default_locale.clone());
+    }
+    let Some(default) = bundle.default_locale.clone() else {
+        diagnostics.push(catalog_diagnostic(
+            Severity::Warn,
+            "I18N_CATALOG_NO_DEFAULT",
+            "No default locale is declared; lookups fall back to the inline text.".into(),
+            "",
+            None,
+        ));
+        return (bundle, diagnostics);
+    };
+    let Some(default_messages) = bundle.locales.get(&default) else {
+        diagnostics.push(catalog_diagnostic(
+            Severity::Error,
+            "I18N_CATALOG_DEFAULT_MISSING",
+            format!("Default locale `{default}` has no messages."),
+            &default,
+            None,
+        ));
+        return (bundle, diagnostics);
+    };
+    let missing: BTreeSet<(&str, &str)> = bundle
+        .locales
+        .iter()
+        .filter(|(locale, _)| **locale != default)
+        .flat_map(|(locale, messages)| {
+            messages
+                .keys()
+                .filter(|key| !default_messages.contains_key(*key))
+                .map(move |key| (key.as_str(), locale.as_str()))
+        })
+        .collect();
+    let mut reported = BTreeSet::new();
+    for (key, locale) in missing {
+        if reported.insert(key) {
+            diagnostics.push(catalog_diagnostic(
+                Severity::Warn,
+                "I18N_CATALOG_MISSING_DEFAULT_KEY",
+                format!(
+                    "`{key}` is defined for `{locale}` but not for the default locale `{default}`."
+                ),
+                &default,
+                Some(key),
+            ));
+        }
+    }
+    (bundle, diagnostics)
+}
+
+fn catalog_diagnostic(
+    severity: Severity,
+    code: &str,
+    message: String,
+    locale: &str,
+    key: Option<&str>,
+) -> Diagnostic {
+    Diagnostic {
+        severity,
+        code: code.into(),
+        message,
+        path: (!locale.is_empty()).then(|| format!("i18n.{locale}")),
+        hint: None,
+        data: key.map_or_else(|| json!({}), |key| json!({ "key": key })),
+    }
+}
+
+fn normalize(locale: &str) -> Result<String, MessageCatalogError> {
+    I18nTag::normalize_tag(locale)
+        .map(|tag| tag.as_str().to_string())
+        .map_err(|source| MessageCatalogError::Locale {
+            locale: locale.to_string(),
+            source,
+        })
+}
+
+/// Pushes `locale` and its truncations (RFC 4647 lookup), skipping duplicates.
+fn push_truncations(locale: &str, chain: &mut Vec<String>) {
+    let mut tag = normalize(locale).unwrap_or_else(|_| locale.to_string());
+    loop {
+        if !tag.is_empty() && !chain.contains(&tag) {
+            chain.push(tag.clone());
+        }
+        let Some((rest, _)) = tag.rsplit_once('-') else {
+            break;
+        };
+        // Never end on a single-letter singleton such as the `u` of `-u-` extensions.
+        let rest = match rest.rsplit_once('-') {
+            Some((head, singleton)) if singleton.len() == 1 => head,
+            _ => rest,
+        };
+        tag = rest.to_string();
+    }
+}
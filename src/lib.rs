@@ -74,15 +74,23 @@ pub mod events;
 pub mod events_provider;
 pub mod flow;
 pub mod flow_resolve;
+pub mod flow_resolve_check;
 pub mod flow_resolve_summary;
 pub mod i18n;
+pub mod i18n_catalog;
 pub mod i18n_text;
 pub mod messaging;
 pub mod op_descriptor;
+#[cfg(feature = "serde")]
+pub mod pack_docs;
 pub mod pack_manifest;
+#[cfg(feature = "pack-yaml")]
+pub mod pack_yaml;
 pub mod provider;
 pub mod provider_install;
 pub mod qa;
+pub mod qa_session;
+pub mod redaction;
 pub mod schema_id;
 pub mod schema_registry;
 pub mod store;
@@ -150,6 +158,10 @@ pub use flow_resolve::{
 pub use flow_resolve::{read_flow_resolve, write_flow_resolve};
 #[cfg(feature = "std")]
 pub use flow_resolve::{sidecar_path_for_flow, validate_flow_resolve};
+#[cfg(feature = "serde")]
+pub use flow_resolve_check::{check_flow_resolve_consistency, regenerate_flow_resolve_summary};
+#[cfg(all(feature = "std", feature = "serde"))]
+pub use flow_resolve_check::{check_flow_sidecars, write_regenerated_flow_resolve_summary};
 pub use flow_resolve_summary::{
     FLOW_RESOLVE_SUMMARY_SCHEMA_VERSION, FlowResolveSummaryManifestV1,
     FlowResolveSummarySourceRefV1, FlowResolveSummaryV1, NodeResolveSummaryV1,
@@ -159,9 +171,11 @@ pub use flow_resolve_summary::{read_flow_resolve_summary, write_flow_resolve_sum
 #[cfg(feature = "std")]
 pub use flow_resolve_summary::{resolve_summary_path_for_flow, validate_flow_resolve_summary};
 pub use i18n::{Direction, I18nId, I18nTag, MinimalI18nProfile, id_for_tag};
+pub use i18n_catalog::{MessageCatalog, MessageCatalogError, bundle_pack_catalogs};
 pub use i18n_text::I18nText;
 pub use messaging::{
     Actor, Attachment, ChannelMessageEnvelope, Destination, MessageMetadata,
+    qa_form::{QaForm, QaFormRenderer, QaTextPrompt, RenderedQaForm, parse_qa_submit},
     rendering::{
         AdaptiveCardVersion, CapabilityProfile, RenderDiagnostics, RenderPlanHints, RendererMode,
         Tier,
@@ -203,11 +217,24 @@ pub use pack::extensions::component_sources::{
 pub use pack::extensions::component_sources::{
     decode_component_sources_v1_from_cbor_bytes, encode_component_sources_v1_to_cbor_bytes,
 };
+#[cfg(feature = "serde")]
+pub use pack::overlay::apply_pack_overlay;
+pub use pack::overlay::{
+    AppliedPackOverlay, CapabilityScopeOverlayV1, ExtensionOverlayV1, OverlaySelectorV1,
+    PACK_OVERLAY_SCHEMA_VERSION, PackOverlayEntryV1, PackOverlayError, PackOverlayV1,
+};
 pub use pack::{PackRef, Signature, SignatureAlgorithm};
+#[cfg(feature = "serde")]
+pub use pack_docs::PackDocs;
 pub use pack_manifest::{
     BootstrapSpec, ComponentCapability, ExtensionInline, ExtensionRef, PackDependency,
     PackFlowEntry, PackKind, PackManifest, PackSignatures,
 };
+#[cfg(feature = "pack-yaml")]
+pub use pack_yaml::{
+    PACK_YAML_FILE, PackYaml, PackYamlEntry, PackYamlFlow, PackYamlLoad, PackYamlScaffold,
+    load_pack_yaml, parse_pack_yaml, scaffold_pack_yaml,
+};
 pub use policy::{AllowList, NetworkPolicy, PolicyDecision, PolicyDecisionStatus, Protocol};
 pub use provider::{
     PROVIDER_EXTENSION_ID, ProviderDecl, ProviderExtensionInline, ProviderManifest,
@@ -215,8 +242,13 @@ pub use provider::{
 };
 pub use provider_install::{ProviderInstallRecord, ProviderInstallRefs};
 pub use qa::{
-    CanonicalPolicy, ExampleAnswers, QaSpecSource, SetupContract, SetupOutput, validate_answers,
+    AnswerConverter, AnswerError, AnswerErrorKind, AnswerUpdatePlan, AskReason, CanonicalPolicy,
+    ExampleAnswers, MigratedAnswer, QaSpecSource, QuestionToAsk, SchemaResolver, SetupContract,
+    SetupOutput, ValidateAnswersError, diff_answers, diff_answers_with, validate_answers,
+    validate_answers_with,
 };
+pub use qa_session::{QaPrompt, QaSession, QaSessionError};
+pub use redaction::{PayloadDirection, Redact, Redacted, Redactor};
 #[cfg(feature = "time")]
 pub use run::RunResult;
 pub use run::{NodeFailure, NodeStatus, NodeSummary, RunStatus, TranscriptOffset};
@@ -270,6 +302,24 @@ pub use validate::{
     Diagnostic, PackValidator, Severity, ValidationCounts, ValidationReport,
     validate_pack_manifest_core,
 };
+pub use wizard::delegate::{
+    DelegateRequest, FlattenedPlan, ResolvedWizard, WizardDelegateError, WizardPlanResolver,
+    flatten_plan,
+};
+#[cfg(feature = "std")]
+pub use wizard::executor::{
+    ExecutionReport, FileChange, FileChangeKind, StepOutcome, StepReport, WizardExecError,
+    WizardExecutor,
+};
+#[cfg(feature = "std")]
+pub use wizard::journal::{
+    JournalEntry, RollbackReport, WizardJournal, journal_path_for, plan_digest,
+};
+#[cfg(all(feature = "std", feature = "serde"))]
+pub use wizard::journal::{read_wizard_journal, write_wizard_journal};
+pub use wizard::template::{
+    TemplateStep, WizardTemplate, WizardTemplateError, render_wizard_template,
+};
 pub use wizard::{WizardId, WizardMode, WizardPlan, WizardPlanMeta, WizardStep, WizardTarget};
 pub use worker::{WorkerMessage, WorkerRequest, WorkerResponse};
 
@@ -1607,6 +1657,17 @@ pub fn make_idempotency_key(
     fnv1a_128_hex(input.as_bytes())
 }
 
+/// Lowercase hex encoding of `bytes`.
+pub(crate) fn hex_encode(bytes: &[u8]) -> String {
+    const HEX: &[u8; 16] = b"0123456789abcdef";
+    let mut out = String::with_capacity(bytes.len() * 2);
+    for &byte in bytes {
+        out.push(HEX[(byte >> 4) as usize] as char);
+        out.push(HEX[(byte & 0x0f) as usize] as char);
+    }
+    out
+}
+
 const FNV_OFFSET_BASIS: u128 = 0x6c62272e07bb014262b821756295c58d;
 const FNV_PRIME: u128 = 0x0000000001000000000000000000013b;
 
@@ -119,5 +119,6 @@ pub struct ChannelMessageEnvelope {
     pub metadata: MessageMetadata,
 }
 
+pub mod qa_form;
 pub mod rendering;
 pub mod universal_dto;
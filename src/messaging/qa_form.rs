@@ -0,0 +1,730 @@
+//! QA specs rendered as chat forms.
+//!
+//! [`QaFormRenderer`] turns a [`ComponentQaSpec`] into an Adaptive Card with one input per
+//! question, or into sequential text prompts when the destination's [`CapabilityProfile`] has no
+//! input controls. [`parse_qa_submit`] maps the card's submit payload (or text replies keyed by
+//! prompt id) back into QA answers for [`validate_answers`](crate::validate_answers) or a
+//! [`QaSession`](crate::QaSession).
+//!
+//! Inputs of nested group questions use `/`-joined ids (`db/host`). Repeated list questions have
+//! no card equivalent and are left out with a warning; secret questions never collect a value and
+//! are answered with their requirement key.
+//!
+//! The renderer's own text (the submit title, reply hints, the `(required)` marker, current
+//! values and the secret note) resolves through the same catalog as the spec, under the
+//! `qa.form.*` keys exported here, with English fallbacks.
+
+use alloc::{
+    collections::BTreeMap,
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+
+use ciborium::value::Value;
+use serde_json::{Map, Value as JsonValue, json};
+
+use crate::i18n_catalog::MessageCatalog;
+use crate::i18n_text::I18nText;
+use crate::messaging::rendering::{CapabilityProfile, RenderDiagnostics, Tier};
+use crate::qa::{AnswerError, AnswerErrorKind};
+use crate::schemas::common::defaults::parse_bool;
+use crate::schemas::common::json_schema::cbor_to_json;
+use crate::schemas::component::v0_6_0::{ChoiceOption, ComponentQaSpec, Question, QuestionKind};
+
+const CARD_SCHEMA: &str = "http://adaptivecards.io/schemas/adaptive-card.json";
+/// Newest Adaptive Card version the renderer emits.
+const CARD_VERSION: (u32, u32) = (1, 5);
+/// Input `label`, `isRequired`, `errorMessage` and `regex` need Adaptive Cards 1.3.
+const VALIDATION_VERSION: (u32, u32) = (1, 3);
+/// Catalog key for the submit button title.
+pub const SUBMIT_KEY: &str = "qa.form.submit";
+/// Catalog key for the marker after required labels in text prompts (`(required)`).
+pub const REQUIRED_KEY: &str = "qa.form.required";
+/// Catalog key for the note on secret questions; `{label}` and `{key}` are substituted.
+pub const SECRET_KEY: &str = "qa.form.secret";
+/// Catalog key for the prompt's current value; `{value}` is substituted.
+pub const CURRENT_KEY: &str = "qa.form.current";
+/// Catalog keys for how boolean answers are shown.
+pub const YES_KEY: &str = "qa.form.yes";
+/// See [`YES_KEY`].
+pub const NO_KEY: &str = "qa.form.no";
+/// Catalog key for the reply hint of choice questions.
+pub const REPLY_CHOICE_KEY: &str = "qa.form.reply.choice";
+/// Catalog key for the reply hint of multi-select questions.
+pub const REPLY_MULTI_SELECT_KEY: &str = "qa.form.reply.multi_select";
+/// Catalog key for the reply hint of boolean questions.
+pub const REPLY_BOOL_KEY: &str = "qa.form.reply.bool";
+/// Catalog key for the reply hint of integer questions.
+pub const REPLY_INTEGER_KEY: &str = "qa.form.reply.integer";
+/// Catalog key for the reply hint of bounded integer questions; `{min}` and `{max}` are
+/// substituted.
+pub const REPLY_INTEGER_RANGE_KEY: &str = "qa.form.reply.integer_range";
+/// Catalog key for the reply hint of date questions.
+pub const REPLY_DATE_KEY: &str = "qa.form.reply.date";
+/// Catalog key for the reply hint of time questions.
+pub const REPLY_TIME_KEY: &str = "qa.form.reply.time";
+/// Catalog key for the reply hint of date-time questions.
+pub const REPLY_DATE_TIME_KEY: &str = "qa.form.reply.date_time";
+
+/// Form produced by [`QaFormRenderer::render`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum QaForm {
+    /// Adaptive Card with one input per question and a submit action.
+    AdaptiveCard(JsonValue),
+    /// Prompts to send one at a time, for destinations without input controls.
+    TextPrompts(Vec<QaTextPrompt>),
+}
+
+/// One question of a text-only form.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct QaTextPrompt {
+    /// Answer key of the question (`/`-joined for nested questions).
+    pub question_id: String,
+    /// Message to send.
+    pub text: String,
+}
+
+/// Rendered form plus diagnostics describing degradations.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RenderedQaForm {
+    /// The form itself.
+    pub form: QaForm,
+    /// Tier and warnings for the chosen rendering.
+    pub diagnostics: RenderDiagnostics,
+}
+
+/// Renders a QA spec for a destination.
+#[derive(Clone, Debug)]
+pub struct QaFormRenderer<'a> {
+    spec: &'a ComponentQaSpec,
+    messages: Vec<&'a BTreeMap<String, String>>,
+    profile: Option<&'a CapabilityProfile>,
+    answers: Option<&'a BTreeMap<String, Value>>,
+}
+
+impl<'a> QaFormRenderer<'a> {
+    /// Starts rendering a spec with no catalog, an unknown destination and no prior answers.
+    pub fn new(spec: &'a ComponentQaSpec) -> Self {
+        Self {
+            spec,
+            messages: Vec::new(),
+            profile: None,
+            answers: None,
+        }
+    }
+
+    /// Resolves [`I18nText`] through a [`MessageCatalog`], following its BCP 47 fallback chain
+    /// down to the catalog's default locale.
+    ///
+    /// Missing keys use [`I18nText::fallback`], then the key itself.
+    pub fn with_message_catalog(mut self, catalog: &'a MessageCatalog, locale: &str) -> Self {
+        self.messages = catalog.chain_messages(locale);
+        self
+    }
+
+    /// Adapts the form to the destination's capabilities.
+    pub fn with_profile(mut self, profile: &'a CapabilityProfile) -> Self {
+        self.profile = Some(profile);
+        self
+    }
+
+    /// Prefills inputs from answers given so far and evaluates `visible_if` against them.
+    pub fn with_answers(mut self, answers: &'a BTreeMap<String, Value>) -> Self {
+        self.answers = Some(answers);
+        self
+    }
+
+    /// Renders the form.
+    ///
+    /// Destinations that report no input controls, no Adaptive Cards or no actions get text
+    /// prompts; unknown capabilities are assumed to be supported. Questions hidden by
+    /// `visible_if` given the current answers are left out, so callers re-render once those
+    /// answers arrive.
+    pub fn render(&self) -> RenderedQaForm {
+        let mut diagnostics = RenderDiagnostics::default();
+        let root = Value::Map(
+            self.answers
+                .into_iter()
+                .flatten()
+                .map(|(id, value)| (Value::Text(id.clone()), value.clone()))
+                .collect(),
+        );
+        let mut fields = Vec::new();
+        self.collect_fields(
+            &self.spec.questions,
+            &root,
+            &root,
+            "",
+            &mut fields,
+            &mut diagnostics,
+        );
+
+        let profile = self.profile.cloned().unwrap_or_default();
+        let interactive = [
+            profile.supports_input_controls,
+            profile.supports_adaptive_cards,
+            profile.supports_actions,
+        ]
+        .iter()
+        .all(|flag| *flag != Some(false));
+        if !interactive {
+            diagnostics.tier = Some(Tier::TierD);
+            let markdown = profile.supports_markdown.unwrap_or(false);
+            let prompts = fields
+                .iter()
+                .filter(|field| !matches!(field.question.kind, QuestionKind::Secret { .. }))
+                .map(|field| QaTextPrompt {
+                    question_id: field.id.clone(),
+                    text: self.text_prompt(field, markdown),
+                })
+                .collect();
+            return RenderedQaForm {
+                form: QaForm::TextPrompts(prompts),
+                diagnostics,
+            };
+        }
+
+        let version = profile
+            .max_adaptive_card_version
+            .as_ref()
+            .and_then(|version| parse_version(version.as_str()))
+            .map_or(CARD_VERSION, |version| version.min(CARD_VERSION));
+        let validation = version >= VALIDATION_VERSION;
+        diagnostics.tier = Some(if validation { Tier::TierA } else { Tier::TierB });
+        if !validation {
+            diagnostics.warnings.push(format!(
+                "Adaptive Card {}.{} has no input labels or validation; answers are only checked on submit.",
+                version.0, version.1
+            ));
+        }
+
+        let mut body = vec![json!({
+            "type": "TextBlock",
+            "text": self.text(&self.spec.title),
+            "size": "Large",
+            "weight": "Bolder",
+            "wrap": true,
+        })];
+        if let Some(description) = &self.spec.description {
+            body.push(text_block(self.text(description), true));
+        }
+        let mut group: Option<(&str, Vec<JsonValue>)> = None;
+        for field in &fields {
+            let elements = self.card_elements(field, validation);
+            match &field.group {
+                Some((id, label)) => {
+                    if group.as_ref().is_none_or(|(open, _)| open != id) {
+                        flush_group(&mut group, &mut body);
+                        group = Some((*id, vec![text_block(label.clone(), false)]));
+                    }
+                    if let Some((_, items)) = group.as_mut() {
+                        items.extend(elements);
+                    }
+                }
+                None => {
+                    flush_group(&mut group, &mut body);
+                    body.extend(elements);
+                }
+            }
+        }
+        flush_group(&mut group, &mut body);
+
+        let card = json!({
+            "type": "AdaptiveCard",
+            "$schema": CARD_SCHEMA,
+            "version": format!("{}.{}", version.0, version.1),
+            "body": body,
+            "actions": [{ "type": "Action.Submit", "title": self.message(SUBMIT_KEY, "Submit", &[]) }],
+        });
+        RenderedQaForm {
+            form: QaForm::AdaptiveCard(card),
+            diagnostics,
+        }
+    }
+
+    fn text(&self, text: &I18nText) -> String {
+        self.messages
+            .iter()
+            .find_map(|messages| messages.get(&text.key))
+            .or(text.fallback.as_ref())
+            .unwrap_or(&text.key)
+            .clone()
+    }
+
+    /// Resolves one of the renderer's own messages, substituting `{name}` placeholders.
+    fn message(&self, key: &str, fallback: &str, args: &[(&str, &str)]) -> String {
+        let mut text = self.text(&I18nText::new(key, Some(fallback.into())));
+        for (name, value) in args {
+            text = text.replace(&format!("{{{name}}}"), value);
+        }
+        text
+    }
+
+    fn collect_fields<'q>(
+        &self,
+        questions: &'q [Question],
+        scope: &Value,
+        root: &Value,
+        prefix: &str,
+        fields: &mut Vec<Field<'q>>,
+        diagnostics: &mut RenderDiagnostics,
+    ) {
+        for question in questions {
+            let id = format!("{prefix}{}", question.id);
+            if !question.is_visible(scope, root) {
+                continue;
+            }
+            let current = lookup(scope, &question.id);
+            match &question.kind {
+                QuestionKind::Group { questions } => {
+                    let nested = current.cloned().unwrap_or(Value::Map(Vec::new()));
+                    let start = fields.len();
+                    self.collect_fields(
+                        questions,
+                        &nested,
+                        root,
+                        &format!("{id}/"),
+                        fields,
+                        diagnostics,
+                    );
+                    let label = self.text(&question.label);
+                    // Only the outermost group is drawn as a container; it is assigned last.
+                    for field in &mut fields[start..] {
+                        field.group = Some((&question.id, label.clone()));
+                    }
+                }
+                QuestionKind::List { .. } => diagnostics.warnings.push(format!(
+                    "List question `{id}` cannot be rendered as form inputs; ask it separately."
+                )),
+                _ => fields.push(Field {
+                    id,
+                    question,
+                    required: question.is_required(scope, root),
+                    current: current
+                        .or(question.default.as_ref())
+                        .or_else(|| self.spec.defaults.get(&format!("{prefix}{}", question.id)))
+                        .cloned(),
+                    group: None,
+                }),
+            }
+        }
+    }
+
+    fn card_elements(&self, field: &Field<'_>, validation: bool) -> Vec<JsonValue> {
+        let question = field.question;
+        let label = self.text(&question.label);
+        let mut elements = Vec::new();
+        if let QuestionKind::Secret { requirement } = &question.kind {
+            elements.push(text_block(
+                self.message(
+                    SECRET_KEY,
+                    "{label}: provided through the secrets store as `{key}`.",
+                    &[("label", &label), ("key", requirement.key.as_str())],
+                ),
+                true,
+            ));
+            return elements;
+        }
+
+        let mut input = Map::new();
+        let current = field.current.as_ref();
+        let text_value = current.and_then(|value| match value {
+            Value::Text(text) => Some(JsonValue::String(text.clone())),
+            _ => None,
+        });
+        let (kind, value) = match &question.kind {
+            QuestionKind::Number => ("Input.Number", current.and_then(cbor_to_json)),
+            QuestionKind::Integer { min, max } => {
+                if let Some(min) = min {
+                    input.insert("min".into(), json!(min));
+                }
+                if let Some(max) = max {
+                    input.insert("max".into(), json!(max));
+                }
+                ("Input.Number", current.and_then(cbor_to_json))
+            }
+            QuestionKind::Bool => {
+                input.insert("title".into(), JsonValue::String(label.clone()));
+                input.insert("valueOn".into(), json!("true"));
+                input.insert("valueOff".into(), json!("false"));
+                let on = matches!(current, Some(Value::Bool(true)));
+                (
+                    "Input.Toggle",
+                    Some(json!(if on { "true" } else { "false" })),
+                )
+            }
+            QuestionKind::Choice { options } => {
+                input.insert("choices".into(), self.choices(options));
+                input.insert("style".into(), json!("compact"));
+                ("Input.ChoiceSet", text_value)
+            }
+            QuestionKind::MultiSelect { options, .. } => {
+                input.insert("choices".into(), self.choices(options));
+                input.insert("isMultiSelect".into(), json!(true));
+                let selected = match current {
+                    Some(Value::Array(items)) => Some(JsonValue::String(
+                        items
+                            .iter()
+                            .filter_map(|item| item.as_text())
+                            .collect::<Vec<_>>()
+                            .join(","),
+                    )),
+                    _ => None,
+                };
+                ("Input.ChoiceSet", selected)
+            }
+            QuestionKind::Date => ("Input.Date", text_value),
+            QuestionKind::Time => ("Input.Time", text_value),
+            QuestionKind::Url => {
+                input.insert("style".into(), json!("Url"));
+                ("Input.Text", text_value)
+            }
+            QuestionKind::Email => {
+                input.insert("style".into(), json!("Email"));
+                ("Input.Text", text_value)
+            }
+            QuestionKind::DateTime => {
+                input.insert("placeholder".into(), json!("YYYY-MM-DDTHH:MM:SSZ"));
+                ("Input.Text", text_value)
+            }
+            QuestionKind::Pattern { regex } => {
+                if validation {
+                    input.insert("regex".into(), json!(regex));
+                }
+                ("Input.Text", text_value)
+            }
+            QuestionKind::Text
+            | QuestionKind::Secret { .. }
+            | QuestionKind::Group { .. }
+            | QuestionKind::List { .. } => ("Input.Text", text_value),
+        };
+        input.insert("type".into(), json!(kind));
+        input.insert("id".into(), JsonValue::String(field.id.clone()));
+        if let Some(value) = value {
+            input.insert("value".into(), value);
+        }
+        if validation {
+            input.insert("label".into(), JsonValue::String(label));
+            if field.required {
+                input.insert("isRequired".into(), json!(true));
+            }
+            if let Some(error) = &question.error {
+                input.insert("errorMessage".into(), JsonValue::String(self.text(error)));
+            }
+        } else if !matches!(question.kind, QuestionKind::Bool) {
+            elements.push(text_block(label, false));
+        }
+        elements.push(JsonValue::Object(input));
+        if let Some(help) = &question.help {
+            elements.push(text_block(self.text(help), true));
+        }
+        elements
+    }
+
+    fn choices(&self, options: &[ChoiceOption]) -> JsonValue {
+        options
+            .iter()
+            .map(|option| json!({ "title": self.text(&option.label), "value": option.value }))
+            .collect()
+    }
+
+    fn display(&self, value: &Value, question: &Question) -> Option<String> {
+        match (value, &question.kind) {
+            (Value::Bool(true), _) => Some(self.message(YES_KEY, "yes", &[])),
+            (Value::Bool(false), _) => Some(self.message(NO_KEY, "no", &[])),
+            (Value::Text(text), _) => Some(text.clone()),
+            (Value::Array(items), QuestionKind::MultiSelect { .. }) => Some(
+                items
+                    .iter()
+                    .filter_map(|item| item.as_text())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            ),
+            (value, _) => cbor_to_json(value).map(|json| json.to_string()),
+        }
+    }
+
+    fn text_prompt(&self, field: &Field<'_>, markdown: bool) -> String {
+        let question = field.question;
+        let label = self.text(&question.label);
+        let label = if markdown {
+            format!("**{label}**")
+        } else {
+            label
+        };
+        let mut lines = vec![if field.required {
+            format!("{label} {}", self.message(REQUIRED_KEY, "(required)", &[]))
+        } else {
+            label
+        }];
+        if let Some(help) = &question.help {
+            lines.push(self.text(help));
+        }
+        match &question.kind {
+            QuestionKind::Choice { options } | QuestionKind::MultiSelect { options, .. } => {
+                for (index, option) in options.iter().enumerate() {
+                    lines.push(format!("{}. {}", index + 1, self.text(&option.label)));
+                }
+                lines.push(
+                    if matches!(question.kind, QuestionKind::MultiSelect { .. }) {
+                        self.message(
+                            REPLY_MULTI_SELECT_KEY,
+                            "Reply with one or more numbers, separated by commas.",
+                            &[],
+                        )
+                    } else {
+                        self.message(REPLY_CHOICE_KEY, "Reply with a number.", &[])
+                    },
+                );
+            }
+            QuestionKind::Bool => lines.push(self.message(REPLY_BOOL_KEY, "Reply yes or no.", &[])),
+            QuestionKind::Integer {
+                min: Some(min),
+                max: Some(max),
+            } => lines.push(self.message(
+                REPLY_INTEGER_RANGE_KEY,
+                "Reply with a whole number from {min} to {max}.",
+                &[("min", &min.to_string()), ("max", &max.to_string())],
+            )),
+            QuestionKind::Integer { .. } => {
+                lines.push(self.message(REPLY_INTEGER_KEY, "Reply with a whole number.", &[]));
+            }
+            QuestionKind::Date => {
+                lines.push(self.message(REPLY_DATE_KEY, "Reply with a date (YYYY-MM-DD).", &[]));
+            }
+            QuestionKind::Time => lines.push(self.message(
+                REPLY_TIME_KEY,
+                "Reply with a time (HH:MM or HH:MM:SS).",
+                &[],
+            )),
+            QuestionKind::DateTime => lines.push(self.message(
+                REPLY_DATE_TIME_KEY,
+                "Reply with a timestamp (YYYY-MM-DDTHH:MM:SSZ).",
+                &[],
+            )),
+            _ => {}
+        }
+        if let Some(current) = field
+            .current
+            .as_ref()
+            .and_then(|value| self.display(value, question))
+        {
+            lines.push(self.message(CURRENT_KEY, "Current: {value}", &[("value", &current)]));
+        }
+        lines.join("\n")
+    }
+}
+
+/// Maps a card submit payload (or text replies keyed by prompt id) into QA answers.
+///
+/// Values may be strings, as Adaptive Card hosts submit them, or typed JSON. Empty strings count
+/// as unanswered, keys that match no question (such as action data) are ignored, and secret
+/// questions are answered with their requirement key. Choices accept the option value, its
+/// 1-based position or its label fallback. The result still needs
+/// [`validate_answers`](crate::validate_answers) for required questions and constraints.
+pub fn parse_qa_submit(
+    spec: &ComponentQaSpec,
+    payload: &JsonValue,
+) -> Result<BTreeMap<String, Value>, Vec<AnswerError>> {
+    let Some(payload) = payload.as_object() else {
+        return Err(vec![AnswerError {
+            question_id: String::new(),
+            kind: AnswerErrorKind::TypeMismatch {
+                expected: "object".into(),
+                found: json_kind(payload).into(),
+            },
+        }]);
+    };
+    let mut errors = Vec::new();
+    let answers = parse_questions(&spec.questions, payload, "", &mut errors);
+    if errors.is_empty() {
+        Ok(answers)
+    } else {
+        Err(errors)
+    }
+}
+
+fn parse_questions(
+    questions: &[Question],
+    payload: &Map<String, JsonValue>,
+    prefix: &str,
+    errors: &mut Vec<AnswerError>,
+) -> BTreeMap<String, Value> {
+    let mut answers = BTreeMap::new();
+    for question in questions {
+        let id = format!("{prefix}{}", question.id);
+        let parsed = match &question.kind {
+            QuestionKind::Secret { requirement } => {
+                Ok(Some(Value::Text(requirement.key.as_str().to_string())))
+            }
+            QuestionKind::Group { questions } => {
+                let nested = parse_questions(questions, payload, &format!("{id}/"), errors);
+                Ok((!nested.is_empty()).then(|| {
+                    Value::Map(
+                        nested
+                            .into_iter()
+                            .map(|(key, value)| (Value::Text(key), value))
+                            .collect(),
+                    )
+                }))
+            }
+            QuestionKind::List { .. } => Ok(None),
+            kind => match payload.get(&id) {
+                None | Some(JsonValue::Null) => Ok(None),
+                Some(JsonValue::String(text)) if text.trim().is_empty() => Ok(None),
+                Some(value) => coerce(kind, value).map(Some),
+            },
+        };
+        match parsed {
+            Ok(Some(value)) => {
+                answers.insert(question.id.clone(), value);
+            }
+            Ok(None) => {}
+            Err(kind) => errors.push(AnswerError {
+                question_id: id,
+                kind,
+            }),
+        }
+    }
+    answers
+}
+
+fn coerce(kind: &QuestionKind, value: &JsonValue) -> Result<Value, AnswerErrorKind> {
+    let text = value.as_str().map(str::trim);
+    let mismatch = |expected: &str| AnswerErrorKind::TypeMismatch {
+        expected: expected.into(),
+        found: json_kind(value).into(),
+    };
+    match kind {
+        QuestionKind::Bool => match value {
+            JsonValue::Bool(flag) => Ok(Value::Bool(*flag)),
+            _ => text
+                .and_then(parse_bool)
+                .map(Value::Bool)
+                .ok_or_else(|| mismatch("boolean")),
+        },
+        QuestionKind::Number => match (value.as_i64(), value.as_f64(), text) {
+            (Some(int), _, _) => Ok(Value::Integer(int.into())),
+            (_, Some(float), _) => Ok(Value::Float(float)),
+            (_, _, Some(text)) => text
+                .parse::<i64>()
+                .map(|int| Value::Integer(int.into()))
+                .or_else(|_| text.parse::<f64>().map(Value::Float))
+                .map_err(|_| mismatch("number")),
+            _ => Err(mismatch("number")),
+        },
+        QuestionKind::Integer { .. } => value
+            .as_i64()
+            .or_else(|| text.and_then(|text| text.parse().ok()))
+            .map(|int| Value::Integer(int.into()))
+            .ok_or_else(|| mismatch("integer")),
+        QuestionKind::Choice { options } => text
+            .ok_or_else(|| mismatch("string"))
+            .and_then(|text| choice(options, text)),
+        QuestionKind::MultiSelect { options, .. } => {
+            let selected: Vec<&str> = match value {
+                JsonValue::Array(items) => items
+                    .iter()
+                    .map(|item| {
+                        item.as_str()
+                            .map(str::trim)
+                            .ok_or_else(|| mismatch("string"))
+                    })
+                    .collect::<Result<_, _>>()?,
+                JsonValue::String(text) => text
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|item| !item.is_empty())
+                    .collect(),
+                _ => return Err(mismatch("array")),
+            };
+            selected
+                .into_iter()
+                .map(|item| choice(options, item))
+                .collect::<Result<_, _>>()
+                .map(Value::Array)
+        }
+        _ => text
+            .map(|text| Value::Text(text.to_string()))
+            .ok_or_else(|| mismatch("string")),
+    }
+}
+
+fn choice(options: &[ChoiceOption], reply: &str) -> Result<Value, AnswerErrorKind> {
+    options
+        .iter()
+        .find(|option| option.value == reply)
+        .or_else(|| {
+            reply
+                .parse::<usize>()
+                .ok()
+                .and_then(|index| index.checked_sub(1))
+                .and_then(|index| options.get(index))
+        })
+        .or_else(|| {
+            options.iter().find(|option| {
+                option
+                    .label
+                    .fallback
+                    .as_deref()
+                    .is_some_and(|label| label.eq_ignore_ascii_case(reply))
+            })
+        })
+        .map(|option| Value::Text(option.value.clone()))
+        .ok_or_else(|| AnswerErrorKind::InvalidChoice {
+            value: reply.to_string(),
+        })
+}
+
+/// Question flattened for rendering.
+struct Field<'q> {
+    id: String,
+    question: &'q Question,
+    required: bool,
+    current: Option<Value>,
+    /// Outermost enclosing group: its id and resolved label.
+    group: Option<(&'q str, String)>,
+}
+
+fn lookup<'v>(answers: &'v Value, key: &str) -> Option<&'v Value> {
+    match answers {
+        Value::Map(entries) => entries
+            .iter()
+            .find(|(entry, _)| entry.as_text() == Some(key))
+            .map(|(_, value)| value)
+            .filter(|value| !value.is_null()),
+        _ => None,
+    }
+}
+
+fn text_block(text: String, subtle: bool) -> JsonValue {
+    let mut block = json!({ "type": "TextBlock", "text": text, "wrap": true });
+    if subtle && let Some(block) = block.as_object_mut() {
+        block.insert("isSubtle".into(), json!(true));
+    }
+    block
+}
+
+fn flush_group(group: &mut Option<(&str, Vec<JsonValue>)>, body: &mut Vec<JsonValue>) {
+    if let Some((_, items)) = group.take() {
+        body.push(json!({ "type": "Container", "items": items }));
+    }
+}
+
+fn parse_version(version: &str) -> Option<(u32, u32)> {
+    let (major, minor) = version.split_once('.')?;
+    Some((major.parse().ok()?, minor.parse().ok()?))
+}
+
+fn json_kind(value: &JsonValue) -> &'static str {
+    match value {
+        JsonValue::Null => "null",
+        JsonValue::Bool(_) => "boolean",
+        JsonValue::Number(_) => "number",
+        JsonValue::String(_) => "string",
+        JsonValue::Array(_) => "array",
+        JsonValue::Object(_) => "object",
+    }
+}
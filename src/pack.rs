@@ -1,6 +1,7 @@
 //! Pack reference metadata.
 
 pub mod extensions;
+pub mod overlay;
 
 use alloc::string::String;
 use alloc::vec::Vec;
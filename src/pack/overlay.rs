@@ -0,0 +1,540 @@
+//! Environment overlays applied on top of a pack manifest.
+//!
+//! A single pack is deployed to several environments, tenants and teams that differ in
+//! configuration defaults, capability scopes, flow entrypoints and extension values. Instead of
+//! forking the manifest, an overlay document lists [`PackOverlayEntryV1`]s keyed by a
+//! [`OverlaySelectorV1`] and [`apply_pack_overlay`] merges the matching entries into a copy of the
+//! manifest.
+//!
+//! Entries are applied from least to most specific (env, then tenant, then team) and in
+//! declaration order within the same specificity, so the result is deterministic. Every field an
+//! overlay sets is recorded in the returned provenance map.
+
+use alloc::collections::BTreeMap;
+use alloc::collections::btree_map::Entry;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::pack::extensions::capabilities::{CapabilitiesExtensionError, CapabilityScopeV1};
+use crate::pack_manifest::{ExtensionInline, ExtensionRef};
+use crate::{ComponentId, EnvId, FlowId, PackId, PackManifest, TeamId, TenantCtx, TenantId};
+
+/// Current schema version for pack overlay documents.
+pub const PACK_OVERLAY_SCHEMA_VERSION: u32 = 1;
+
+/// Pack overlay document (v1).
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PackOverlayV1 {
+    /// Schema version (must be 1).
+    pub schema_version: u32,
+    /// Pack the overlay targets.
+    pub pack_id: PackId,
+    /// Overlay entries in declaration order.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub overlays: Vec<PackOverlayEntryV1>,
+}
+
+/// Selector deciding which deployments an overlay entry applies to.
+///
+/// Unset fields match any value. A team selector only matches when the tenant selector is set.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct OverlaySelectorV1 {
+    /// Environment the entry applies to.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub env: Option<EnvId>,
+    /// Tenant the entry applies to.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub tenant: Option<TenantId>,
+    /// Team the entry applies to.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub team: Option<TeamId>,
+}
+
+impl OverlaySelectorV1 {
+    /// Returns `true` when the selector matches the tenant context.
+    pub fn matches(&self, ctx: &TenantCtx) -> bool {
+        let team = ctx.team_id.as_ref().or(ctx.team.as_ref());
+        self.env.as_ref().is_none_or(|env| env == &ctx.env)
+            && self
+                .tenant
+                .as_ref()
+                .is_none_or(|tenant| tenant == &ctx.tenant_id)
+            && self
+                .team
+                .as_ref()
+                .is_none_or(|expected| self.tenant.is_some() && team == Some(expected))
+    }
+
+    /// Specificity rank used to order entries (`0` = global).
+    ///
+    /// Entries rank by their most specific dimension (team over tenant over env), then by how
+    /// many dimensions they set, so a tenant-only entry always outranks an env-only entry.
+    pub fn specificity(&self) -> u8 {
+        let dimension = if self.team.is_some() {
+            3
+        } else if self.tenant.is_some() {
+            2
+        } else {
+            u8::from(self.env.is_some())
+        };
+        let count = u8::from(self.env.is_some())
+            + u8::from(self.tenant.is_some())
+            + u8::from(self.team.is_some());
+        dimension * 4 + count
+    }
+}
+
+/// Single overlay entry.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PackOverlayEntryV1 {
+    /// Stable overlay identifier recorded in provenance.
+    pub id: String,
+    /// Deployments this entry applies to.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub selector: OverlaySelectorV1,
+    /// Component config defaults keyed by component id, then config property.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "BTreeMap::is_empty")
+    )]
+    pub component_config: BTreeMap<ComponentId, BTreeMap<String, Value>>,
+    /// Capability scope tweaks keyed by capability offer id.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "BTreeMap::is_empty")
+    )]
+    pub capability_scopes: BTreeMap<String, CapabilityScopeOverlayV1>,
+    /// Flow entrypoint overrides keyed by flow id, then entrypoint name (`null` removes).
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "BTreeMap::is_empty")
+    )]
+    pub flow_entrypoints: BTreeMap<FlowId, BTreeMap<String, Value>>,
+    /// Inline extension payload patches keyed by extension.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "BTreeMap::is_empty")
+    )]
+    pub extensions: BTreeMap<String, ExtensionOverlayV1>,
+}
+
+/// Patch for one inline extension payload.
+///
+/// Only inline payloads can be patched; extensions referenced by `location` or `digest` are
+/// rejected because the overlay cannot see their content.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ExtensionOverlayV1 {
+    /// Extension version. Required when the entry creates the extension; replaces the existing
+    /// version otherwise.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub version: Option<String>,
+    /// JSON merge patch (RFC 7386) applied to the inline payload.
+    pub patch: Value,
+}
+
+/// Structured change to a capability offer scope.
+///
+/// `replace` is applied first, then `add`, then `remove`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CapabilityScopeOverlayV1 {
+    /// Replaces the whole scope (an empty scope clears restrictions).
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub replace: Option<CapabilityScopeV1>,
+    /// Values added to the scope lists.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub add: CapabilityScopeV1,
+    /// Values removed from the scope lists.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub remove: CapabilityScopeV1,
+}
+
+/// Result of applying an overlay document.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AppliedPackOverlay {
+    /// Manifest with all matching entries merged in.
+    pub manifest: PackManifest,
+    /// Identifiers of the applied entries, in application order.
+    pub applied: Vec<String>,
+    /// Field path -> identifier of the entry that last set it.
+    pub provenance: BTreeMap<String, String>,
+}
+
+/// Errors produced while applying pack overlays.
+#[derive(Debug, thiserror::Error)]
+pub enum PackOverlayError {
+    /// Unsupported schema version.
+    #[error("unsupported pack overlay schema_version {0}")]
+    UnsupportedSchemaVersion(u32),
+    /// Overlay targets another pack.
+    #[error("overlay targets pack `{overlay}` but manifest is `{manifest}`")]
+    PackMismatch {
+        /// Pack id declared by the overlay.
+        overlay: String,
+        /// Pack id of the manifest.
+        manifest: String,
+    },
+    /// Overlay references a component that is not in the manifest.
+    #[error("overlay `{overlay}` references unknown component `{component}`")]
+    UnknownComponent {
+        /// Overlay entry identifier.
+        overlay: String,
+        /// Component identifier.
+        component: String,
+    },
+    /// Overlay references a flow that is not in the manifest.
+    #[error("overlay `{overlay}` references unknown flow `{flow}`")]
+    UnknownFlow {
+        /// Overlay entry identifier.
+        overlay: String,
+        /// Flow identifier.
+        flow: String,
+    },
+    /// Overlay references a capability offer that is not in the manifest.
+    #[error("overlay `{overlay}` references unknown capability offer `{offer}`")]
+    UnknownCapabilityOffer {
+        /// Overlay entry identifier.
+        overlay: String,
+        /// Capability offer identifier.
+        offer: String,
+    },
+    /// Component config schema cannot hold defaults.
+    #[error("component `{component}` config_schema must be a JSON object")]
+    InvalidConfigSchema {
+        /// Component identifier.
+        component: String,
+    },
+    /// Extension is not inline, or its patched payload no longer decodes.
+    #[error("overlay `{overlay}` produced an invalid `{extension}` extension: {message}")]
+    InvalidExtension {
+        /// Overlay entry identifier.
+        overlay: String,
+        /// Extension key.
+        extension: String,
+        /// Decode error.
+        message: String,
+    },
+    /// Overlay creates an extension without declaring its version.
+    #[error("overlay `{overlay}` creates extension `{extension}` without a version")]
+    MissingExtensionVersion {
+        /// Overlay entry identifier.
+        overlay: String,
+        /// Extension key.
+        extension: String,
+    },
+    /// Capabilities extension could not be read or written.
+    #[error(transparent)]
+    Capabilities(#[from] CapabilitiesExtensionError),
+}
+
+impl PackOverlayV1 {
+    /// Creates an empty overlay document for a pack.
+    pub fn new(pack_id: PackId) -> Self {
+        Self {
+            schema_version: PACK_OVERLAY_SCHEMA_VERSION,
+            pack_id,
+            overlays: Vec::new(),
+        }
+    }
+
+    /// Returns the matching entries in application order.
+    pub fn matching_entries(&self, ctx: &TenantCtx) -> Vec<&PackOverlayEntryV1> {
+        let mut entries: Vec<_> = self
+            .overlays
+            .iter()
+            .filter(|entry| entry.selector.matches(ctx))
+            .collect();
+        // Stable sort keeps declaration order within the same specificity.
+        entries.sort_by_key(|entry| entry.selector.specificity());
+        entries
+    }
+}
+
+/// Applies every overlay entry matching `ctx` to a copy of `manifest`.
+#[cfg(feature = "serde")]
+pub fn apply_pack_overlay(
+    manifest: &PackManifest,
+    overlay: &PackOverlayV1,
+    ctx: &TenantCtx,
+) -> Result<AppliedPackOverlay, PackOverlayError> {
+    if overlay.schema_version != PACK_OVERLAY_SCHEMA_VERSION {
+        return Err(PackOverlayError::UnsupportedSchemaVersion(
+            overlay.schema_version,
+        ));
+    }
+    if overlay.pack_id != manifest.pack_id {
+        return Err(PackOverlayError::PackMismatch {
+            overlay: overlay.pack_id.to_string(),
+            manifest: manifest.pack_id.to_string(),
+        });
+    }
+
+    let mut applied = AppliedPackOverlay {
+        manifest: manifest.clone(),
+        applied: Vec::new(),
+        provenance: BTreeMap::new(),
+    };
+    for entry in overlay.matching_entries(ctx) {
+        apply_entry(&mut applied, entry)?;
+        applied.applied.push(entry.id.clone());
+    }
+    Ok(applied)
+}
+
+#[cfg(feature = "serde")]
+fn apply_entry(
+    applied: &mut AppliedPackOverlay,
+    entry: &PackOverlayEntryV1,
+) -> Result<(), PackOverlayError> {
+    let AppliedPackOverlay {
+        manifest,
+        provenance,
+        ..
+    } = applied;
+
+    for (component_id, defaults) in &entry.component_config {
+        let component = manifest
+            .components
+            .iter_mut()
+            .find(|component| &component.id == component_id)
+            .ok_or_else(|| PackOverlayError::UnknownComponent {
+                overlay: entry.id.clone(),
+                component: component_id.to_string(),
+            })?;
+        let schema = component
+            .config_schema
+            .get_or_insert_with(|| serde_json::json!({ "type": "object" }));
+        let properties = schema
+            .as_object_mut()
+            .ok_or_else(|| PackOverlayError::InvalidConfigSchema {
+                component: component_id.to_string(),
+            })?
+            .entry("properties")
+            .or_insert_with(|| Value::Object(Map::new()))
+            .as_object_mut()
+            .ok_or_else(|| PackOverlayError::InvalidConfigSchema {
+                component: component_id.to_string(),
+            })?;
+        for (key, value) in defaults {
+            let property = properties
+                .entry(key.clone())
+                .or_insert_with(|| Value::Object(Map::new()));
+            let property =
+                property
+                    .as_object_mut()
+                    .ok_or_else(|| PackOverlayError::InvalidConfigSchema {
+                        component: component_id.to_string(),
+                    })?;
+            property.insert("default".into(), value.clone());
+            provenance.insert(
+                format!("components.{component_id}.config.{key}"),
+                entry.id.clone(),
+            );
+        }
+    }
+
+    if !entry.capability_scopes.is_empty() {
+        let mut capabilities = manifest.get_capabilities_extension_v1()?.ok_or_else(|| {
+            let offer = entry
+                .capability_scopes
+                .keys()
+                .next()
+                .cloned()
+                .unwrap_or_default();
+            PackOverlayError::UnknownCapabilityOffer {
+                overlay: entry.id.clone(),
+                offer,
+            }
+        })?;
+        for (offer_id, tweak) in &entry.capability_scopes {
+            let offer = capabilities
+                .offers
+                .iter_mut()
+                .find(|offer| &offer.offer_id == offer_id)
+                .ok_or_else(|| PackOverlayError::UnknownCapabilityOffer {
+                    overlay: entry.id.clone(),
+                    offer: offer_id.clone(),
+                })?;
+            let scope = apply_scope(offer.scope.take().unwrap_or_default(), tweak);
+            offer.scope = (scope != CapabilityScopeV1::default()).then_some(scope);
+            provenance.insert(
+                format!("capabilities.offers.{offer_id}.scope"),
+                entry.id.clone(),
+            );
+        }
+        manifest.set_capabilities_extension_v1(capabilities)?;
+    }
+
+    for (flow_id, overrides) in &entry.flow_entrypoints {
+        let flow = manifest
+            .flows
+            .iter_mut()
+            .find(|flow| &flow.id == flow_id)
+            .ok_or_else(|| PackOverlayError::UnknownFlow {
+                overlay: entry.id.clone(),
+                flow: flow_id.to_string(),
+            })?;
+        for (name, value) in overrides {
+            if value.is_null() {
+                flow.flow.entrypoints.remove(name);
+                flow.entrypoints.retain(|existing| existing != name);
+            } else {
+                flow.flow.entrypoints.insert(name.clone(), value.clone());
+                if !flow.entrypoints.contains(name) {
+                    flow.entrypoints.push(name.clone());
+                }
+            }
+            provenance.insert(
+                format!("flows.{flow_id}.entrypoints.{name}"),
+                entry.id.clone(),
+            );
+        }
+    }
+
+    for (key, overlay) in &entry.extensions {
+        let extensions = manifest.extensions.get_or_insert_with(BTreeMap::new);
+        let extension = match extensions.entry(key.clone()) {
+            Entry::Occupied(existing) => existing.into_mut(),
+            Entry::Vacant(vacant) => {
+                let version = overlay.version.clone().ok_or_else(|| {
+                    PackOverlayError::MissingExtensionVersion {
+                        overlay: entry.id.clone(),
+                        extension: key.clone(),
+                    }
+                })?;
+                vacant.insert(ExtensionRef {
+                    kind: key.clone(),
+                    version,
+                    digest: None,
+                    location: None,
+                    inline: None,
+                })
+            }
+        };
+        if extension.inline.is_none()
+            && (extension.location.is_some() || extension.digest.is_some())
+        {
+            return Err(PackOverlayError::InvalidExtension {
+                overlay: entry.id.clone(),
+                extension: key.clone(),
+                message: "payload is referenced by location or digest, not inline".to_string(),
+            });
+        }
+        if let Some(version) = &overlay.version {
+            extension.version = version.clone();
+            provenance.insert(format!("extensions.{key}.version"), entry.id.clone());
+        }
+        let is_provider = matches!(extension.inline, Some(ExtensionInline::Provider(_)));
+        let mut value = match extension.inline.take() {
+            Some(ExtensionInline::Other(value)) => value,
+            Some(ExtensionInline::Provider(inline)) => {
+                serde_json::to_value(inline).map_err(|err| PackOverlayError::InvalidExtension {
+                    overlay: entry.id.clone(),
+                    extension: key.clone(),
+                    message: err.to_string(),
+                })?
+            }
+            None => Value::Null,
+        };
+        merge_patch(
+            &mut value,
+            &overlay.patch,
+            &format!("extensions.{key}"),
+            &entry.id,
+            provenance,
+        );
+        extension.inline = Some(if is_provider {
+            ExtensionInline::Provider(serde_json::from_value(value).map_err(|err| {
+                PackOverlayError::InvalidExtension {
+                    overlay: entry.id.clone(),
+                    extension: key.clone(),
+                    message: err.to_string(),
+                }
+            })?)
+        } else {
+            ExtensionInline::Other(value)
+        });
+    }
+
+    Ok(())
+}
+
+fn apply_scope(scope: CapabilityScopeV1, tweak: &CapabilityScopeOverlayV1) -> CapabilityScopeV1 {
+    let mut scope = tweak.replace.clone().unwrap_or(scope);
+    extend_unique(&mut scope.envs, &tweak.add.envs);
+    extend_unique(&mut scope.tenants, &tweak.add.tenants);
+    extend_unique(&mut scope.teams, &tweak.add.teams);
+    scope
+        .envs
+        .retain(|value| !tweak.remove.envs.contains(value));
+    scope
+        .tenants
+        .retain(|value| !tweak.remove.tenants.contains(value));
+    scope
+        .teams
+        .retain(|value| !tweak.remove.teams.contains(value));
+    scope
+}
+
+fn extend_unique(target: &mut Vec<String>, values: &[String]) {
+    for value in values {
+        if !target.contains(value) {
+            target.push(value.clone());
+        }
+    }
+}
+
+/// RFC 7386 merge patch that records the path of every leaf it sets or removes.
+fn merge_patch(
+    target: &mut Value,
+    patch: &Value,
+    path: &str,
+    overlay_id: &str,
+    provenance: &mut BTreeMap<String, String>,
+) {
+    let Value::Object(patch_map) = patch else {
+        *target = patch.clone();
+        provenance.insert(path.to_string(), overlay_id.to_string());
+        return;
+    };
+    if !target.is_object() {
+        *target = Value::Object(Map::new());
+    }
+    let Value::Object(target_map) = target else {
+        return;
+    };
+    for (key, value) in patch_map {
+        let child_path = format!("{path}.{key}");
+        if value.is_null() {
+            target_map.remove(key);
+            provenance.insert(child_path, overlay_id.to_string());
+        } else {
+            let child = target_map.entry(key.clone()).or_insert(Value::Null);
+            merge_patch(child, value, &child_path, overlay_id, provenance);
+        }
+    }
+}
@@ -0,0 +1,667 @@
+//! Human-readable reference docs rendered from pack metadata.
+//!
+//! [`PackDocs`] collects a [`PackManifest`] plus optional describe/QA documents and renders the
+//! same section model to Markdown ([`PackDocs::to_markdown`]) or standalone HTML
+//! ([`PackDocs::to_html`]), so both outputs always cover the same content. Output is
+//! deterministic: sections follow manifest order and maps are iterated in key order.
+
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+
+use ciborium::value::Value;
+
+use crate::i18n_catalog::MessageCatalog;
+use crate::i18n_text::I18nText;
+use crate::schemas::common::schema_ir::{AdditionalProperties, SchemaIr};
+use crate::schemas::component::v0_6_0::ComponentDescribe;
+use crate::schemas::pack::v0_6_0::{PackDescribe, PackQaSpec, QuestionKind};
+use crate::{PackManifest, SecretRequirement};
+
+/// Builder collecting the documents rendered into pack reference docs.
+#[derive(Clone, Debug)]
+pub struct PackDocs<'a> {
+    manifest: &'a PackManifest,
+    describe: Option<&'a PackDescribe>,
+    components: Vec<&'a ComponentDescribe>,
+    qa_specs: Vec<&'a PackQaSpec>,
+    messages: Vec<&'a BTreeMap<String, String>>,
+}
+
+impl<'a> PackDocs<'a> {
+    /// Starts documentation for a manifest.
+    pub fn new(manifest: &'a PackManifest) -> Self {
+        Self {
+            manifest,
+            describe: None,
+            components: Vec::new(),
+            qa_specs: Vec::new(),
+            messages: Vec::new(),
+        }
+    }
+
+    /// Adds the pack describe document (display name, provided/required capabilities).
+    pub fn with_describe(mut self, describe: &'a PackDescribe) -> Self {
+        self.describe = Some(describe);
+        self
+    }
+
+    /// Adds a component describe document (operations and typed schemas).
+    pub fn with_component(mut self, component: &'a ComponentDescribe) -> Self {
+        self.components.push(component);
+        self
+    }
+
+    /// Adds a pack QA spec; one section is rendered per spec.
+    pub fn with_qa_spec(mut self, spec: &'a PackQaSpec) -> Self {
+        self.qa_specs.push(spec);
+        self
+    }
+
+    /// Resolves [`I18nText`] keys through a [`MessageCatalog`], following its fallback chain for
+    /// `locale`.
+    ///
+    /// Keys missing from the chain fall back to [`I18nText::fallback`], then the key itself.
+    pub fn with_catalog(mut self, catalog: &'a MessageCatalog, locale: &str) -> Self {
+        self.messages = catalog.chain_messages(locale);
+        self
+    }
+
+    /// Renders the docs as Markdown.
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::new();
+        for block in self.blocks() {
+            block.write_markdown(&mut out);
+        }
+        out
+    }
+
+    /// Renders the docs as a standalone HTML document.
+    pub fn to_html(&self) -> String {
+        let mut out = String::from("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+        out.push_str(&format!(
+            "<title>{}</title>\n</head>\n<body>\n",
+            escape_html(&self.title())
+        ));
+        for block in self.blocks() {
+            block.write_html(&mut out);
+        }
+        out.push_str("</body>\n</html>\n");
+        out
+    }
+
+    fn text(&self, text: &I18nText) -> String {
+        self.messages
+            .iter()
+            .find_map(|messages| messages.get(&text.key))
+            .or(text.fallback.as_ref())
+            .unwrap_or(&text.key)
+            .clone()
+    }
+
+    fn title(&self) -> String {
+        self.describe
+            .and_then(|describe| describe.info.display_name.as_ref())
+            .map(|name| self.text(name))
+            .or_else(|| self.manifest.name.clone())
+            .unwrap_or_else(|| self.manifest.pack_id.to_string())
+    }
+
+    fn blocks(&self) -> Vec<Block> {
+        let manifest = self.manifest;
+        let mut blocks = vec![
+            Block::Heading(1, self.title()),
+            Block::Table {
+                headers: vec!["Field".into(), "Value".into()],
+                rows: vec![
+                    vec!["Pack ID".into(), manifest.pack_id.to_string()],
+                    vec!["Version".into(), manifest.version.to_string()],
+                    vec!["Kind".into(), serde_label(&manifest.kind)],
+                    vec!["Publisher".into(), manifest.publisher.clone()],
+                ],
+            },
+        ];
+
+        blocks.push(Block::Heading(2, "Flows".into()));
+        if manifest.flows.is_empty() {
+            blocks.push(Block::Paragraph("This pack declares no flows.".into()));
+        }
+        for entry in &manifest.flows {
+            blocks.push(Block::Heading(3, entry.id.to_string()));
+            let mut facts = vec![format!("Kind: {}", serde_label(&entry.kind))];
+            if !entry.tags.is_empty() {
+                facts.push(format!("Tags: {}", entry.tags.join(", ")));
+            }
+            if let Some(first) = entry.flow.nodes.keys().next() {
+                facts.push(format!("Ingress node: {first}"));
+            }
+            facts.push(format!("Nodes: {}", entry.flow.nodes.len()));
+            blocks.push(Block::List(facts));
+            let mut names: Vec<&String> = entry.flow.entrypoints.keys().collect();
+            names.extend(
+                entry
+                    .entrypoints
+                    .iter()
+                    .filter(|name| !entry.flow.entrypoints.contains_key(*name)),
+            );
+            if !names.is_empty() {
+                blocks.push(Block::Table {
+                    headers: vec!["Entrypoint".into(), "Binding".into()],
+                    rows: names
+                        .into_iter()
+                        .map(|name| {
+                            let binding = entry
+                                .flow
+                                .entrypoints
+                                .get(name)
+                                .filter(|value| !value.is_null())
+                                .map(ToString::to_string)
+                                .unwrap_or_default();
+                            vec![name.clone(), binding]
+                        })
+                        .collect(),
+                });
+            }
+        }
+
+        blocks.push(Block::Heading(2, "Components".into()));
+        if manifest.components.is_empty() && self.components.is_empty() {
+            blocks.push(Block::Paragraph("This pack bundles no components.".into()));
+        }
+        for component in &manifest.components {
+            blocks.push(Block::Heading(3, component.id.to_string()));
+            blocks.push(Block::List(vec![
+                format!("Version: {}", component.version),
+                format!("World: {}", component.world),
+            ]));
+            let describe = self
+                .components
+                .iter()
+                .find(|describe| describe.info.id == component.id.as_str());
+            match describe {
+                Some(describe) => self.component_blocks(describe, &mut blocks),
+                None => {
+                    for operation in &component.operations {
+                        blocks.push(Block::Heading(4, format!("Operation `{}`", operation.name)));
+                    }
+                }
+            }
+        }
+        for describe in self.components.iter().filter(|describe| {
+            !manifest
+                .components
+                .iter()
+                .any(|component| component.id.as_str() == describe.info.id)
+        }) {
+            blocks.push(Block::Heading(3, describe.info.id.clone()));
+            blocks.push(Block::List(vec![format!(
+                "Version: {}",
+                describe.info.version
+            )]));
+            self.component_blocks(describe, &mut blocks);
+        }
+
+        blocks.push(Block::Heading(2, "Secrets".into()));
+        if manifest.secret_requirements.is_empty() {
+            blocks.push(Block::Paragraph("No secrets are required.".into()));
+        } else {
+            blocks.push(Block::Table {
+                headers: vec![
+                    "Key".into(),
+                    "Required".into(),
+                    "Scope".into(),
+                    "Description".into(),
+                ],
+                rows: manifest
+                    .secret_requirements
+                    .iter()
+                    .map(secret_row)
+                    .collect(),
+            });
+        }
+
+        blocks.push(Block::Heading(2, "Capabilities".into()));
+        let mut rows: Vec<Vec<String>> = manifest
+            .capabilities
+            .iter()
+            .map(|capability| {
+                vec![
+                    capability.name.clone(),
+                    "provided".into(),
+                    capability.description.clone().unwrap_or_default(),
+                ]
+            })
+            .collect();
+        if let Some(describe) = self.describe {
+            for (direction, descriptors) in [
+                ("provided", &describe.provided_capabilities),
+                ("required", &describe.required_capabilities),
+            ] {
+                rows.extend(descriptors.iter().map(|descriptor| {
+                    vec![
+                        descriptor.capability_id.clone(),
+                        direction.into(),
+                        format!("version {}", descriptor.version_req),
+                    ]
+                }));
+            }
+        }
+        for dependency in &manifest.dependencies {
+            rows.extend(dependency.required_capabilities.iter().map(|capability| {
+                vec![
+                    capability.clone(),
+                    "required".into(),
+                    format!("via dependency `{}`", dependency.alias),
+                ]
+            }));
+        }
+        if rows.is_empty() {
+            blocks.push(Block::Paragraph("No capabilities are declared.".into()));
+        } else {
+            blocks.push(Block::Table {
+                headers: vec!["Capability".into(), "Direction".into(), "Notes".into()],
+                rows,
+            });
+        }
+
+        if !self.qa_specs.is_empty() {
+            blocks.push(Block::Heading(2, "Setup questions".into()));
+        }
+        for spec in &self.qa_specs {
+            blocks.push(Block::Heading(
+                3,
+                format!("{} ({})", self.text(&spec.title), spec.mode),
+            ));
+            if let Some(description) = &spec.description {
+                blocks.push(Block::Paragraph(self.text(description)));
+            }
+            blocks.push(Block::Table {
+                headers: vec![
+                    "ID".into(),
+                    "Question".into(),
+                    "Kind".into(),
+                    "Required".into(),
+                    "Default".into(),
+                ],
+                rows: spec
+                    .questions
+                    .iter()
+                    .map(|question| {
+                        let mut label = self.text(&question.label);
+                        if let Some(help) = &question.help {
+                            label = format!("{label} — {}", self.text(help));
+                        }
+                        let kind = match &question.kind {
+                            QuestionKind::Text => "text".to_string(),
+                            QuestionKind::Number => "number".to_string(),
+                            QuestionKind::Bool => "bool".to_string(),
+                            QuestionKind::Choice { options } => format!(
+                                "choice: {}",
+                                options
+                                    .iter()
+                                    .map(|option| format!(
+                                        "{} ({})",
+                                        option.value,
+                                        self.text(&option.label)
+                                    ))
+                                    .collect::<Vec<_>>()
+                                    .join(", ")
+                            ),
+                            QuestionKind::MultiSelect { options, .. } => format!(
+                                "multi-select: {}",
+                                options
+                                    .iter()
+                                    .map(|option| format!(
+                                        "{} ({})",
+                                        option.value,
+                                        self.text(&option.label)
+                                    ))
+                                    .collect::<Vec<_>>()
+                                    .join(", ")
+                            ),
+                            QuestionKind::Secret { requirement } => {
+                                format!("secret: {}", requirement.key.as_str())
+                            }
+                            QuestionKind::Url => "url".to_string(),
+                            QuestionKind::Email => "email".to_string(),
+                            QuestionKind::Pattern { regex } => format!("text matching `{regex}`"),
+                            QuestionKind::Integer { min, max } => match (min, max) {
+                                (None, None) => "integer".to_string(),
+                                (min, max) => format!(
+                                    "integer {}..={}",
+                                    min.map(|min| min.to_string()).unwrap_or_default(),
+                                    max.map(|max| max.to_string()).unwrap_or_default()
+                                ),
+                            },
+                            QuestionKind::Date => "date".to_string(),
+                            QuestionKind::Time => "time".to_string(),
+                            QuestionKind::DateTime => "date-time".to_string(),
+                            QuestionKind::Group { questions } => format!(
+                                "group: {}",
+                                questions
+                                    .iter()
+                                    .map(|question| question.id.as_str())
+                                    .collect::<Vec<_>>()
+                                    .join(", ")
+                            ),
+                            QuestionKind::List { questions, .. } => format!(
+                                "list of: {}",
+                                questions
+                                    .iter()
+                                    .map(|question| question.id.as_str())
+                                    .collect::<Vec<_>>()
+                                    .join(", ")
+                            ),
+                        };
+                        let default = question
+                            .default
+                            .as_ref()
+                            .or_else(|| spec.defaults.get(&question.id))
+                            .map(value_label)
+                            .unwrap_or_default();
+                        vec![
+                            question.id.clone(),
+                            label,
+                            kind,
+                            if question.required_if.is_some() && !question.required {
+                                "conditional".into()
+                            } else {
+                                yes_no(question.required)
+                            },
+                            default,
+                        ]
+                    })
+                    .collect(),
+            });
+        }
+
+        blocks
+    }
+
+    fn component_blocks(&self, describe: &ComponentDescribe, blocks: &mut Vec<Block>) {
+        if !is_empty_object(&describe.config_schema) {
+            blocks.push(Block::Heading(4, "Configuration".into()));
+            blocks.push(schema_table(&describe.config_schema));
+        }
+        for operation in &describe.operations {
+            let title = match &operation.display_name {
+                Some(name) => format!("Operation `{}` — {}", operation.id, self.text(name)),
+                None => format!("Operation `{}`", operation.id),
+            };
+            blocks.push(Block::Heading(4, title));
+            blocks.push(Block::Paragraph("Input:".into()));
+            blocks.push(schema_table(&operation.input.schema));
+            blocks.push(Block::Paragraph("Output:".into()));
+            blocks.push(schema_table(&operation.output.schema));
+        }
+    }
+}
+
+/// Section model shared by the Markdown and HTML renderers.
+enum Block {
+    Heading(usize, String),
+    Paragraph(String),
+    List(Vec<String>),
+    Table {
+        headers: Vec<String>,
+        rows: Vec<Vec<String>>,
+    },
+}
+
+impl Block {
+    fn write_markdown(&self, out: &mut String) {
+        match self {
+            Self::Heading(level, text) => {
+                out.push_str(&format!("{} {text}\n\n", "#".repeat(*level)));
+            }
+            Self::Paragraph(text) => out.push_str(&format!("{text}\n\n")),
+            Self::List(items) => {
+                for item in items {
+                    out.push_str(&format!("- {item}\n"));
+                }
+                out.push('\n');
+            }
+            Self::Table { headers, rows } => {
+                let row = |cells: &[String]| {
+                    let cells: Vec<String> = cells
+                        .iter()
+                        .map(|cell| escape_markdown_cell(cell))
+                        .collect();
+                    format!("| {} |\n", cells.join(" | "))
+                };
+                out.push_str(&row(headers));
+                out.push_str(&format!("|{}\n", " --- |".repeat(headers.len())));
+                for cells in rows {
+                    out.push_str(&row(cells));
+                }
+                out.push('\n');
+            }
+        }
+    }
+
+    fn write_html(&self, out: &mut String) {
+        match self {
+            Self::Heading(level, text) => {
+                out.push_str(&format!("<h{level}>{}</h{level}>\n", escape_html(text)));
+            }
+            Self::Paragraph(text) => out.push_str(&format!("<p>{}</p>\n", escape_html(text))),
+            Self::List(items) => {
+                out.push_str("<ul>\n");
+                for item in items {
+                    out.push_str(&format!("<li>{}</li>\n", escape_html(item)));
+                }
+                out.push_str("</ul>\n");
+            }
+            Self::Table { headers, rows } => {
+                out.push_str("<table>\n<thead><tr>");
+                for header in headers {
+                    out.push_str(&format!("<th>{}</th>", escape_html(header)));
+                }
+                out.push_str("</tr></thead>\n<tbody>\n");
+                for cells in rows {
+                    out.push_str("<tr>");
+                    for cell in cells {
+                        out.push_str(&format!("<td>{}</td>", escape_html(cell)));
+                    }
+                    out.push_str("</tr>\n");
+                }
+                out.push_str("</tbody>\n</table>\n");
+            }
+        }
+    }
+}
+
+fn schema_table(schema: &SchemaIr) -> Block {
+    let mut rows = Vec::new();
+    schema_rows(schema, "", true, &mut rows);
+    Block::Table {
+        headers: vec![
+            "Field".into(),
+            "Type".into(),
+            "Required".into(),
+            "Constraints".into(),
+        ],
+        rows,
+    }
+}
+
+/// Flattens a schema into one row per field; nested objects and array items use dotted paths.
+fn schema_rows(schema: &SchemaIr, path: &str, required: bool, rows: &mut Vec<Vec<String>>) {
+    let field = if path.is_empty() { "(root)" } else { path };
+    match schema {
+        SchemaIr::Object {
+            properties,
+            required: required_fields,
+            additional,
+        } => {
+            if !path.is_empty() || properties.is_empty() {
+                let extra = match additional {
+                    AdditionalProperties::Allow => String::new(),
+                    AdditionalProperties::Forbid => "no additional properties".into(),
+                    AdditionalProperties::Schema(schema) => {
+                        format!("additional: {}", type_label(schema))
+                    }
+                };
+                rows.push(vec![field.into(), "object".into(), yes_no(required), extra]);
+            }
+            for (name, property) in properties {
+                let child = if path.is_empty() {
+                    name.clone()
+                } else {
+                    format!("{path}.{name}")
+                };
+                schema_rows(property, &child, required_fields.contains(name), rows);
+            }
+        }
+        SchemaIr::Array {
+            items,
+            min_items,
+            max_items,
+        } => {
+            rows.push(vec![
+                field.into(),
+                format!("array<{}>", type_label(items)),
+                yes_no(required),
+                bounds("items", *min_items, *max_items),
+            ]);
+            if matches!(**items, SchemaIr::Object { .. }) {
+                schema_rows(items, &format!("{field}[]"), true, rows);
+            }
+        }
+        other => rows.push(vec![
+            field.into(),
+            type_label(other),
+            yes_no(required),
+            constraints(other),
+        ]),
+    }
+}
+
+fn type_label(schema: &SchemaIr) -> String {
+    match schema {
+        SchemaIr::Object { .. } => "object".into(),
+        SchemaIr::Array { items, .. } => format!("array<{}>", type_label(items)),
+        SchemaIr::String {
+            format: Some(format),
+            ..
+        } => format!("string ({format})"),
+        SchemaIr::String { .. } => "string".into(),
+        SchemaIr::Int { .. } => "integer".into(),
+        SchemaIr::Float { .. } => "number".into(),
+        SchemaIr::Bool => "boolean".into(),
+        SchemaIr::Null => "null".into(),
+        SchemaIr::Bytes => "bytes".into(),
+        SchemaIr::Enum { .. } => "enum".into(),
+        SchemaIr::OneOf { variants } => variants
+            .iter()
+            .map(type_label)
+            .collect::<Vec<_>>()
+            .join(" | "),
+        SchemaIr::Ref { id } => format!("ref {id}"),
+    }
+}
+
+fn constraints(schema: &SchemaIr) -> String {
+    match schema {
+        SchemaIr::String {
+            min_len,
+            max_len,
+            regex,
+            ..
+        } => {
+            let mut parts = Vec::new();
+            let length = bounds("length", *min_len, *max_len);
+            if !length.is_empty() {
+                parts.push(length);
+            }
+            if let Some(regex) = regex {
+                parts.push(format!("pattern `{regex}`"));
+            }
+            parts.join("; ")
+        }
+        SchemaIr::Int { min, max } => bounds("value", *min, *max),
+        SchemaIr::Float { min, max } => bounds("value", *min, *max),
+        SchemaIr::Enum { values } => format!(
+            "one of: {}",
+            values
+                .iter()
+                .map(value_label)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        _ => String::new(),
+    }
+}
+
+fn bounds<T: core::fmt::Display>(label: &str, min: Option<T>, max: Option<T>) -> String {
+    match (min, max) {
+        (Some(min), Some(max)) => format!("{label} {min}..={max}"),
+        (Some(min), None) => format!("{label} >= {min}"),
+        (None, Some(max)) => format!("{label} <= {max}"),
+        (None, None) => String::new(),
+    }
+}
+
+fn is_empty_object(schema: &SchemaIr) -> bool {
+    matches!(schema, SchemaIr::Object { properties, .. } if properties.is_empty())
+}
+
+fn secret_row(secret: &SecretRequirement) -> Vec<String> {
+    let scope = secret
+        .scope
+        .as_ref()
+        .map(|scope| match &scope.team {
+            Some(team) => format!("{}/{}/{team}", scope.env, scope.tenant),
+            None => format!("{}/{}", scope.env, scope.tenant),
+        })
+        .unwrap_or_default();
+    vec![
+        secret.key.as_str().to_string(),
+        yes_no(secret.required),
+        scope,
+        secret.description.clone().unwrap_or_default(),
+    ]
+}
+
+fn value_label(value: &Value) -> String {
+    match value {
+        Value::Text(text) => text.clone(),
+        Value::Integer(int) => i128::from(*int).to_string(),
+        Value::Float(float) => float.to_string(),
+        Value::Bool(flag) => flag.to_string(),
+        Value::Null => "null".into(),
+        other => format!("{other:?}"),
+    }
+}
+
+fn serde_label<T: serde::Serialize>(value: &T) -> String {
+    match serde_json::to_value(value) {
+        Ok(serde_json::Value::String(label)) => label,
+        _ => String::new(),
+    }
+}
+
+fn yes_no(flag: bool) -> String {
+    if flag { "yes" } else { "no" }.into()
+}
+
+fn escape_markdown_cell(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', " ")
+}
+
+fn escape_html(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(ch),
+        }
+    }
+    out
+}
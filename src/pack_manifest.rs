@@ -265,6 +265,74 @@ pub struct ExtensionRef {
 }
 
 impl PackManifest {
+    /// Sorts order-insensitive vectors by canonical keys and removes identical duplicates.
+    ///
+    /// Two builds of the same pack otherwise produce different bytes when inputs are visited in a
+    /// different order. Normalization applies these rules:
+    ///
+    /// - `components` are sorted by `(id, version)`,
+    /// - `flows` are sorted by `id` (node order inside a flow is preserved because the first node
+    ///   is the flow ingress),
+    /// - `dependencies` are sorted by `alias`, and each `required_capabilities` list is sorted,
+    /// - `capabilities` are sorted by `name`,
+    /// - `secret_requirements` are sorted by `key`, then `scope` (`env`, `tenant`, `team`).
+    ///
+    /// Sorting is stable, so entries that share a key but differ in content keep their relative
+    /// order. Entries that are fully identical are collapsed into one. Normalization is
+    /// idempotent; duplicate keys with differing content are left for validation to report.
+    pub fn normalize(&mut self) {
+        self.components
+            .sort_by(|a, b| (a.id.as_str(), &a.version).cmp(&(b.id.as_str(), &b.version)));
+        dedup_identical(&mut self.components);
+
+        self.flows.sort_by(|a, b| a.id.as_str().cmp(b.id.as_str()));
+        dedup_identical(&mut self.flows);
+
+        for dependency in &mut self.dependencies {
+            dependency.required_capabilities.sort();
+            dependency.required_capabilities.dedup();
+        }
+        self.dependencies.sort_by(|a, b| a.alias.cmp(&b.alias));
+        dedup_identical(&mut self.dependencies);
+
+        self.capabilities.sort_by(|a, b| a.name.cmp(&b.name));
+        dedup_identical(&mut self.capabilities);
+
+        self.secret_requirements.sort_by(|a, b| {
+            let scope_key = |req: &SecretRequirement| {
+                req.scope
+                    .as_ref()
+                    .map(|scope| (scope.env.clone(), scope.tenant.clone(), scope.team.clone()))
+            };
+            (a.key.as_str(), scope_key(a)).cmp(&(b.key.as_str(), scope_key(b)))
+        });
+        dedup_identical(&mut self.secret_requirements);
+    }
+
+    /// Returns a normalized copy of the manifest (see [`PackManifest::normalize`]).
+    pub fn normalized(&self) -> Self {
+        let mut manifest = self.clone();
+        manifest.normalize();
+        manifest
+    }
+
+    /// Computes the pack identity digest (`sha256:<hex>`).
+    ///
+    /// The digest covers the canonical CBOR encoding of the normalized manifest with signatures
+    /// removed, so it is stable across input ordering and can itself be signed.
+    #[cfg(feature = "std")]
+    pub fn content_digest(&self) -> Result<String, crate::CborError> {
+        use sha2::{Digest, Sha256};
+
+        let mut manifest = self.normalized();
+        manifest.signatures = PackSignatures::default();
+        let encoded = crate::encode_pack_manifest(&manifest)?;
+        let canonical = crate::cbor::canonical::canonicalize_allow_floats(&encoded)
+            .map_err(|err| crate::CborError::Encode(err.to_string()))?;
+        let digest = Sha256::digest(canonical.as_slice());
+        Ok(alloc::format!("sha256:{}", crate::hex_encode(&digest)))
+    }
+
     /// Returns the inline provider extension payload if present.
     pub fn provider_extension_inline(&self) -> Option<&ProviderExtensionInline> {
         self.extensions
@@ -400,3 +468,13 @@ impl PackManifest {
         Ok(())
     }
 }
+
+fn dedup_identical<T: PartialEq>(items: &mut Vec<T>) {
+    let mut unique: Vec<T> = Vec::with_capacity(items.len());
+    for item in items.drain(..) {
+        if !unique.contains(&item) {
+            unique.push(item);
+        }
+    }
+    *items = unique;
+}
@@ -0,0 +1,605 @@
+//! `pack.yaml` authoring format.
+//!
+//! Pack authors keep a small `pack.yaml` next to their flows and component manifests instead of
+//! hand-writing a [`PackManifest`]. Flows, components, QA specs and i18n catalogs can be pulled in
+//! with relative includes:
+//!
+//! ```yaml
+//! pack_id: dev.local.weather
+//! name: Weather
+//! version: 0.1.0
+//! kind: application
+//! publisher: acme
+//! components:
+//!   - include: components/weather.json
+//! flows:
+//!   - include: flows/main.ygtc
+//!     tags: [chat]
+//! qa:
+//!   - qa/setup.yaml
+//! i18n:
+//!   en: i18n/en.json
+//! ```
+//!
+//! [`load_pack_yaml`] resolves includes relative to the directory holding `pack.yaml` and reports
+//! problems as [`Diagnostic`]s whose `data` carries the `file`, `line` and `column` they point at.
+//! [`scaffold_pack_yaml`] goes the other way and splits an existing manifest into a `pack.yaml`
+//! plus one `.ygtc` file per flow.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use saphyr_parser::{Event, Parser};
+use semver::Version;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::adapters::registry::PACK_MANIFEST_SCHEMA_VERSION;
+use crate::i18n_catalog::{MessageCatalog, MessageCatalogError};
+use crate::schemas::pack::v0_6_0::PackQaSpec;
+use crate::validate::{Diagnostic, Severity, ValidationReport};
+use crate::{
+    BootstrapSpec, ComponentCapability, ComponentManifest, ErrorCode, ExtensionRef, Flow, FlowId,
+    GResult, GreenticError, PackDependency, PackFlowEntry, PackId, PackKind, PackManifest,
+    PackSignatures, SecretRequirement,
+};
+
+/// Conventional file name for the pack authoring document.
+pub const PACK_YAML_FILE: &str = "pack.yaml";
+
+fn default_schema_version() -> String {
+    PACK_MANIFEST_SCHEMA_VERSION.into()
+}
+
+/// Typed `pack.yaml` document.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PackYaml {
+    /// Manifest schema version (defaults to `pack-v1`).
+    #[serde(default = "default_schema_version")]
+    pub schema_version: String,
+    /// Logical pack identifier.
+    pub pack_id: PackId,
+    /// Optional human-readable name.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Pack semantic version.
+    pub version: Version,
+    /// Pack kind.
+    pub kind: PackKind,
+    /// Pack publisher.
+    pub publisher: String,
+    /// Component manifests, inline or included.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub components: Vec<PackYamlEntry<ComponentManifest>>,
+    /// Flow includes.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub flows: Vec<PackYamlFlow>,
+    /// Pack dependencies.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dependencies: Vec<PackDependency>,
+    /// Capability declarations.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub capabilities: Vec<ComponentCapability>,
+    /// Pack-level secret requirements.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub secret_requirements: Vec<SecretRequirement>,
+    /// Optional bootstrap hints.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bootstrap: Option<BootstrapSpec>,
+    /// Extension descriptors.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub extensions: BTreeMap<String, ExtensionRef>,
+    /// QA spec includes.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub qa: Vec<String>,
+    /// I18n catalog includes keyed by locale (each file is a flat key -> message map).
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub i18n: BTreeMap<String, String>,
+}
+
+/// List entry that is either an include or an inline document.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum PackYamlEntry<T> {
+    /// Relative path to a JSON or YAML document.
+    Include {
+        /// Path relative to `pack.yaml`.
+        include: String,
+    },
+    /// Document embedded in `pack.yaml`.
+    Inline(T),
+}
+
+/// Flow include with pack-level metadata.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PackYamlFlow {
+    /// Path to the `.ygtc` flow document relative to `pack.yaml`.
+    pub include: String,
+    /// Expected flow identifier (defaults to the id in the flow document).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<FlowId>,
+    /// Flow tags.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Additional entrypoint identifiers for discoverability.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub entrypoints: Vec<String>,
+}
+
+/// Result of loading a `pack.yaml` document and its includes.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PackYamlLoad {
+    /// Assembled manifest; `None` when the report contains errors.
+    pub manifest: Option<PackManifest>,
+    /// QA specs loaded from the `qa` includes, in declaration order.
+    pub qa_specs: Vec<PackQaSpec>,
+    /// Messages of the `i18n` includes, keyed by normalized locale tag.
+    pub i18n: MessageCatalog,
+    /// Diagnostics collected while loading.
+    pub report: ValidationReport,
+}
+
+/// `pack.yaml` document plus the flow files it includes.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PackYamlScaffold {
+    /// Authoring document.
+    pub pack_yaml: PackYaml,
+    /// Included files keyed by path relative to `pack.yaml`.
+    pub files: BTreeMap<String, String>,
+}
+
+impl PackYaml {
+    /// Serializes the document to YAML.
+    pub fn to_yaml_string(&self) -> GResult<String> {
+        serde_yaml_bw::to_string(self).map_err(|err| yaml_error("serialize pack.yaml", err))
+    }
+}
+
+impl PackYamlScaffold {
+    /// Writes `pack.yaml` and the included files under `dir`.
+    pub fn write_to(&self, dir: &Path) -> GResult<()> {
+        for (relative, contents) in &self.files {
+            let path = dir.join(relative);
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent).map_err(|err| io_error("create pack dir", err))?;
+            }
+            fs::write(&path, contents).map_err(|err| io_error("write pack include", err))?;
+        }
+        fs::create_dir_all(dir).map_err(|err| io_error("create pack dir", err))?;
+        fs::write(dir.join(PACK_YAML_FILE), self.pack_yaml.to_yaml_string()?)
+            .map_err(|err| io_error("write pack.yaml", err))
+    }
+}
+
+/// Reads `pack.yaml` from disk and resolves its includes.
+///
+/// Only failing to read the root document is an error; everything else is reported through
+/// [`PackYamlLoad::report`].
+pub fn load_pack_yaml(path: &Path) -> GResult<PackYamlLoad> {
+    let source = fs::read_to_string(path).map_err(|err| io_error("read pack.yaml", err))?;
+    let base_dir = path.parent().unwrap_or_else(|| Path::new(""));
+    Ok(parse_pack_yaml(&source, base_dir))
+}
+
+/// Parses `pack.yaml` source and resolves includes relative to `base_dir`.
+pub fn parse_pack_yaml(source: &str, base_dir: &Path) -> PackYamlLoad {
+    let mut loader = Loader {
+        locations: node_locations(source),
+        base_dir,
+        report: ValidationReport::default(),
+    };
+    let mut load = PackYamlLoad {
+        manifest: None,
+        qa_specs: Vec::new(),
+        i18n: MessageCatalog::new(),
+        report: ValidationReport::default(),
+    };
+
+    let doc: PackYaml = match serde_yaml_bw::from_str(source) {
+        Ok(doc) => doc,
+        Err(err) => {
+            let location = err.location().map(|loc| (loc.line(), loc.column()));
+            loader.push(
+                Severity::Error,
+                "PACK_YAML_PARSE_ERROR",
+                format!("pack.yaml could not be parsed: {err}."),
+                None,
+                PACK_YAML_FILE,
+                location,
+            );
+            load.report = loader.report;
+            return load;
+        }
+    };
+    loader.report.pack_id = Some(doc.pack_id.clone());
+    loader.report.pack_version = Some(doc.version.clone());
+
+    let mut components = Vec::new();
+    for (index, entry) in doc.components.iter().enumerate() {
+        let path = format!("components[{index}]");
+        let component = match entry {
+            PackYamlEntry::Inline(component) => Some(component.clone()),
+            PackYamlEntry::Include { include } => loader.include(include, &path),
+        };
+        let Some(component) = component else { continue };
+        if components
+            .iter()
+            .any(|existing: &ComponentManifest| existing.id == component.id)
+        {
+            loader.push_root(
+                Severity::Error,
+                "PACK_YAML_DUPLICATE_COMPONENT",
+                format!("Component `{}` is declared more than once.", component.id),
+                &path,
+                &[&format!("{path}.id"), &format!("{path}.include")],
+            );
+            continue;
+        }
+        components.push(component);
+    }
+
+    let mut flows: Vec<PackFlowEntry> = Vec::new();
+    for (index, entry) in doc.flows.iter().enumerate() {
+        let path = format!("flows[{index}]");
+        let Some(flow) = loader.include::<Flow>(&entry.include, &path) else {
+            continue;
+        };
+        if let Some(expected) = &entry.id
+            && expected != &flow.id
+        {
+            loader.push_root(
+                Severity::Error,
+                "PACK_YAML_FLOW_ID_MISMATCH",
+                format!(
+                    "Flow include `{}` declares id `{}` but pack.yaml expects `{expected}`.",
+                    entry.include, flow.id
+                ),
+                &path,
+                &[&format!("{path}.id")],
+            );
+            continue;
+        }
+        if flows.iter().any(|existing| existing.id == flow.id) {
+            loader.push_root(
+                Severity::Error,
+                "PACK_YAML_DUPLICATE_FLOW",
+                format!("Flow `{}` is declared more than once.", flow.id),
+                &path,
+                &[&format!("{path}.include")],
+            );
+            continue;
+        }
+        flows.push(PackFlowEntry {
+            id: flow.id.clone(),
+            kind: flow.kind,
+            flow,
+            tags: entry.tags.clone(),
+            entrypoints: entry.entrypoints.clone(),
+        });
+    }
+
+    for (index, include) in doc.qa.iter().enumerate() {
+        if let Some(spec) = loader.include::<PackQaSpec>(include, &format!("qa[{index}]")) {
+            load.qa_specs.push(spec);
+        }
+    }
+    let mut catalogs = BTreeMap::new();
+    for (locale, include) in &doc.i18n {
+        if let Some(catalog) = loader.include(include, &format!("i18n.{locale}")) {
+            catalogs.insert(locale.clone(), catalog);
+        }
+    }
+    let mut loaded: Vec<String> = catalogs.keys().cloned().collect();
+    match MessageCatalog::from_locales(catalogs, None) {
+        Ok(catalog) => load.i18n = catalog,
+        Err(err) => {
+            let locale = match &err {
+                MessageCatalogError::Locale { locale, .. } => locale.clone(),
+                MessageCatalogError::DuplicateLocale { second, .. } => second.clone(),
+                _ => String::new(),
+            };
+            loaded.clear();
+            loader.push_root(
+                Severity::Error,
+                "PACK_YAML_I18N_INVALID_LOCALE",
+                format!("I18n catalogs could not be loaded: {err}."),
+                "i18n",
+                &[&format!("i18n.{locale}")],
+            );
+        }
+    }
+    let keys: BTreeSet<String> = load
+        .qa_specs
+        .iter()
+        .flat_map(PackQaSpec::i18n_keys)
+        .collect();
+    // Keys a locale inherits through its fallback chain (`en-GB` -> `en`) are not missing.
+    for locale in &loaded {
+        for key in load
+            .i18n
+            .missing_keys(locale, keys.iter().map(String::as_str))
+        {
+            loader.push(
+                Severity::Warn,
+                "PACK_YAML_I18N_MISSING_KEY",
+                format!("Catalog `{locale}` has no message for `{key}`."),
+                Some(format!("i18n.{locale}")),
+                &doc.i18n[locale],
+                None,
+            );
+        }
+    }
+
+    load.report = loader.report;
+    if !load.report.has_errors() {
+        load.manifest = Some(PackManifest {
+            schema_version: doc.schema_version,
+            pack_id: doc.pack_id,
+            name: doc.name,
+            version: doc.version,
+            kind: doc.kind,
+            publisher: doc.publisher,
+            components,
+            flows,
+            dependencies: doc.dependencies,
+            capabilities: doc.capabilities,
+            secret_requirements: doc.secret_requirements,
+            signatures: PackSignatures::default(),
+            bootstrap: doc.bootstrap,
+            extensions: (!doc.extensions.is_empty()).then_some(doc.extensions),
+        });
+    }
+    load
+}
+
+/// Builds a `pack.yaml` document from an existing manifest.
+///
+/// Components, dependencies and extensions stay inline; each flow is written to
+/// `flows/<id>.ygtc`. Signatures are not part of the authoring format and are dropped.
+pub fn scaffold_pack_yaml(manifest: &PackManifest) -> GResult<PackYamlScaffold> {
+    let mut files = BTreeMap::new();
+    let mut flows = Vec::with_capacity(manifest.flows.len());
+    for entry in &manifest.flows {
+        let include = format!("flows/{}.ygtc", entry.id);
+        let contents = serde_yaml_bw::to_string(&entry.flow)
+            .map_err(|err| yaml_error("serialize flow", err))?;
+        files.insert(include.clone(), contents);
+        flows.push(PackYamlFlow {
+            include,
+            id: (entry.id != entry.flow.id).then(|| entry.id.clone()),
+            tags: entry.tags.clone(),
+            entrypoints: entry.entrypoints.clone(),
+        });
+    }
+
+    Ok(PackYamlScaffold {
+        pack_yaml: PackYaml {
+            schema_version: manifest.schema_version.clone(),
+            pack_id: manifest.pack_id.clone(),
+            name: manifest.name.clone(),
+            version: manifest.version.clone(),
+            kind: manifest.kind,
+            publisher: manifest.publisher.clone(),
+            components: manifest
+                .components
+                .iter()
+                .cloned()
+                .map(PackYamlEntry::Inline)
+                .collect(),
+            flows,
+            dependencies: manifest.dependencies.clone(),
+            capabilities: manifest.capabilities.clone(),
+            secret_requirements: manifest.secret_requirements.clone(),
+            bootstrap: manifest.bootstrap.clone(),
+            extensions: manifest.extensions.clone().unwrap_or_default(),
+            qa: Vec::new(),
+            i18n: BTreeMap::new(),
+        },
+        files,
+    })
+}
+
+struct Loader<'a> {
+    /// Start of every node in `pack.yaml`, keyed by diagnostic path.
+    locations: BTreeMap<String, (usize, usize)>,
+    base_dir: &'a Path,
+    report: ValidationReport,
+}
+
+impl Loader<'_> {
+    /// Reads and decodes an include, reporting failures against its line in `pack.yaml`.
+    fn include<T: DeserializeOwned>(&mut self, include: &str, path: &str) -> Option<T> {
+        let Some(relative) = normalize_include(include) else {
+            self.push_root(
+                Severity::Error,
+                "PACK_YAML_INCLUDE_INVALID_PATH",
+                format!("Include `{include}` must be a relative path inside the pack."),
+                path,
+                &[&format!("{path}.include")],
+            );
+            return None;
+        };
+        let source = match fs::read_to_string(self.base_dir.join(&relative)) {
+            Ok(source) => source,
+            Err(err) => {
+                self.push_root(
+                    Severity::Error,
+                    "PACK_YAML_INCLUDE_NOT_FOUND",
+                    format!("Include `{include}` could not be read: {err}."),
+                    path,
+                    &[&format!("{path}.include")],
+                );
+                return None;
+            }
+        };
+        let is_json = relative
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+        let decoded = if is_json {
+            serde_json::from_str(&source)
+                .map_err(|err| (err.to_string(), Some((err.line(), err.column()))))
+        } else {
+            serde_yaml_bw::from_str(&source).map_err(|err| {
+                let location = err.location().map(|loc| (loc.line(), loc.column()));
+                (err.to_string(), location)
+            })
+        };
+        match decoded {
+            Ok(value) => Some(value),
+            Err((message, location)) => {
+                self.push(
+                    Severity::Error,
+                    "PACK_YAML_INCLUDE_PARSE_ERROR",
+                    format!("Include `{include}` could not be parsed: {message}."),
+                    Some(path.into()),
+                    include,
+                    location,
+                );
+                None
+            }
+        }
+    }
+
+    /// Pushes a diagnostic located at the first of `nodes` present in `pack.yaml`, falling back
+    /// to the node at `path` itself.
+    fn push_root(
+        &mut self,
+        severity: Severity,
+        code: &str,
+        message: String,
+        path: &str,
+        nodes: &[&str],
+    ) {
+        let location = nodes
+            .iter()
+            .copied()
+            .chain([path])
+            .find_map(|node| self.locations.get(node).copied());
+        self.push(
+            severity,
+            code,
+            message,
+            Some(path.into()),
+            PACK_YAML_FILE,
+            location,
+        );
+    }
+
+    fn push(
+        &mut self,
+        severity: Severity,
+        code: &str,
+        message: String,
+        path: Option<String>,
+        file: &str,
+        location: Option<(usize, usize)>,
+    ) {
+        let data = match location {
+            Some((line, column)) => json!({ "file": file, "line": line, "column": column }),
+            None => json!({ "file": file }),
+        };
+        self.report.diagnostics.push(Diagnostic {
+            severity,
+            code: code.into(),
+            message,
+            path,
+            hint: None,
+            data,
+        });
+    }
+}
+
+/// Rejects absolute includes and includes that climb out of the pack directory.
+fn normalize_include(include: &str) -> Option<PathBuf> {
+    let mut normalized = PathBuf::new();
+    for component in Path::new(include).components() {
+        match component {
+            Component::Normal(part) => normalized.push(part),
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !normalized.pop() {
+                    return None;
+                }
+            }
+            Component::RootDir | Component::Prefix(_) => return None,
+        }
+    }
+    (!normalized.as_os_str().is_empty()).then_some(normalized)
+}
+
+enum Frame {
+    Sequence { path: String, next: usize },
+    Mapping { path: String, key: Option<String> },
+}
+
+/// Maps the diagnostic path of every node (`flows[1].include`, `i18n.en`) to its 1-based line
+/// and column. Nodes after a YAML syntax error are not located.
+fn node_locations(source: &str) -> BTreeMap<String, (usize, usize)> {
+    let mut locations = BTreeMap::new();
+    let mut stack: Vec<Frame> = Vec::new();
+    for (event, span) in Parser::new_from_str(source).map_while(Result::ok) {
+        let (is_key, child) = match &event {
+            Event::Scalar(..)
+            | Event::Alias(_)
+            | Event::SequenceStart(..)
+            | Event::MappingStart(..) => {
+                match stack.last_mut() {
+                    None => (false, String::new()),
+                    Some(Frame::Sequence { path, next }) => {
+                        *next += 1;
+                        (false, format!("{path}[{}]", *next - 1))
+                    }
+                    Some(Frame::Mapping { path, key }) => match key.take() {
+                        Some(key) if path.is_empty() => (false, key),
+                        Some(key) => (false, format!("{path}.{key}")),
+                        None => {
+                            // This node is a key; non-scalar keys are tracked as `?`.
+                            *key = Some(match &event {
+                                Event::Scalar(text, ..) => text.to_string(),
+                                _ => "?".into(),
+                            });
+                            (true, String::from("?"))
+                        }
+                    },
+                }
+            }
+            Event::SequenceEnd | Event::MappingEnd => {
+                stack.pop();
+                continue;
+            }
+            _ => continue,
+        };
+        if !is_key {
+            locations
+                .entry(child.clone())
+                .or_insert((span.start.line(), span.start.col() + 1));
+        }
+        match event {
+            Event::SequenceStart(..) => stack.push(Frame::Sequence {
+                path: child,
+                next: 0,
+            }),
+            Event::MappingStart(..) => stack.push(Frame::Mapping {
+                path: child,
+                key: None,
+            }),
+            _ => {}
+        }
+    }
+    locations
+}
+
+fn yaml_error(context: &str, err: serde_yaml_bw::Error) -> GreenticError {
+    GreenticError::new(ErrorCode::InvalidInput, format!("{context}: {err}")).with_source(err)
+}
+
+fn io_error(context: &str, err: std::io::Error) -> GreenticError {
+    let code = match err.kind() {
+        std::io::ErrorKind::NotFound => ErrorCode::NotFound,
+        std::io::ErrorKind::PermissionDenied => ErrorCode::PermissionDenied,
+        _ => ErrorCode::Unavailable,
+    };
+    GreenticError::new(code, format!("{context}: {err}")).with_source(err)
+}
@@ -1,11 +1,25 @@
 //! Types used by QA setup contracts.
-use alloc::{string::String, vec::Vec};
+use alloc::{
+    collections::BTreeMap,
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
 
 use ciborium::value::Value;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
-use crate::{cbor::canonical, cbor_bytes::CborBytes, schema_id::SchemaSource};
+use crate::schemas::common::defaults::parse_bool;
+use crate::schemas::common::schema_ir::SchemaIr;
+use crate::schemas::common::validation::{is_partial_time, value_kind};
+use crate::schemas::component::v0_6_0::{ChoiceOption, ComponentQaSpec, Question, QuestionKind};
+use crate::{
+    cbor::canonical,
+    cbor_bytes::CborBytes,
+    schema_id::{SchemaSource, schema_id_for_cbor},
+};
 
 /// Where the QA specification lives.
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -86,22 +100,183 @@ pub enum ValidateAnswersError {
     /// Canonicalization check failed.
     #[error(transparent)]
     Canonical(#[from] canonical::CanonicalError),
+    /// Schema source could not be resolved to CBOR bytes.
+    #[error("schema source could not be resolved: {0}")]
+    UnresolvedSchema(String),
+    /// Resolved schema bytes do not hash to the referenced schema ID.
+    #[error("schema bytes do not match schema ID {0}")]
+    SchemaIdMismatch(String),
+    /// Schema bytes are neither a QA spec nor a `SchemaIr`.
+    #[error("schema is neither a QA spec nor a SchemaIr: {0}")]
+    InvalidSchema(String),
+    /// Answers do not satisfy the schema.
+    #[error("answers failed validation ({} errors)", .0.len())]
+    Invalid(Vec<AnswerError>),
 }
 
-/// MVP validator that ensures answer CBOR is a map and optionally canonical.
+/// Validation failure for a single answer.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AnswerError {
+    /// Question identifier (or top-level answer key) the error refers to.
+    pub question_id: String,
+    /// What went wrong.
+    pub kind: AnswerErrorKind,
+}
+
+/// Reason an answer was rejected.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AnswerErrorKind {
+    /// Required question has no answer and no default.
+    Missing,
+    /// Answer has the wrong type for the question kind.
+    TypeMismatch {
+        /// Expected type label.
+        expected: String,
+        /// Type label of the submitted value.
+        found: String,
+    },
+    /// Choice answer is not one of the declared option values.
+    InvalidChoice {
+        /// Submitted value.
+        value: String,
+    },
+    /// Answer key does not correspond to any question.
+    UnknownKey,
+    /// Answer violates a constraint of the question kind (range, pattern, format, count).
+    Constraint {
+        /// Human-readable description.
+        message: String,
+    },
+    /// Secret answer holds an inline value instead of the requirement key.
+    InlineSecret,
+    /// `SchemaIr` violation for the answer.
+    Schema {
+        /// JSON pointer to the offending value.
+        pointer: String,
+        /// Violation code (for example `SCHEMA_REQUIRED`).
+        code: String,
+        /// Human-readable description.
+        message: String,
+    },
+}
+
+/// Resolves schema sources that are not embedded inline.
+pub trait SchemaResolver {
+    /// Returns canonical CBOR schema bytes for `source`, if known.
+    fn resolve(&self, source: &SchemaSource) -> Option<CborBytes>;
+}
+
+/// Resolver keyed by schema ID, pack path or URI string.
+impl SchemaResolver for BTreeMap<String, CborBytes> {
+    fn resolve(&self, source: &SchemaSource) -> Option<CborBytes> {
+        let key = match source {
+            SchemaSource::CborSchemaId(id) => id.as_str(),
+            SchemaSource::RefPackPath(path) => path.as_str(),
+            SchemaSource::RefUri(uri) => uri.as_str(),
+            _ => return None,
+        };
+        self.get(key).cloned()
+    }
+}
+
+/// Resolver that knows no external schemas; only inline sources validate.
+struct InlineOnly;
+
+impl SchemaResolver for InlineOnly {
+    fn resolve(&self, _source: &SchemaSource) -> Option<CborBytes> {
+        None
+    }
+}
+
+/// Validates answers against an inline schema and applies QA defaults.
+///
+/// Schemas referenced by ID, pack path or URI cannot be resolved here, so for those sources the
+/// answers are only checked to be a map, as before schema validation existed. Use
+/// [`validate_answers_with`] and a [`SchemaResolver`] to validate them against the schema.
 pub fn validate_answers(
     schema: &SchemaSource,
     answers_cbor: &CborBytes,
     policy: CanonicalPolicy,
 ) -> Result<CborBytes, ValidateAnswersError> {
-    let _ = schema;
+    match schema {
+        SchemaSource::CborSchemaId(_) | SchemaSource::RefPackPath(_) | SchemaSource::RefUri(_) => {
+            let value: Value = ciborium::de::from_reader(answers_cbor.as_slice())
+                .map_err(|err| ValidateAnswersError::Decode(err.to_string()))?;
+            if !matches!(value, Value::Map(_)) {
+                return Err(ValidateAnswersError::NotMap);
+            }
+            apply_policy(answers_cbor, policy)
+        }
+        _ => validate_answers_with(schema, answers_cbor, policy, &InlineOnly),
+    }
+}
+
+/// Validates answers against a schema resolved through `resolver`.
+///
+/// The schema may be a QA spec (questions are checked for presence, type and choice membership,
+/// unknown keys are rejected and spec/question defaults fill missing answers) or a `SchemaIr`.
+/// Questions hidden by `visible_if` are skipped, `required_if` makes questions required, and
+/// group/list answers are checked recursively.
+/// All failures are returned together in [`ValidateAnswersError::Invalid`].
+pub fn validate_answers_with(
+    schema: &SchemaSource,
+    answers_cbor: &CborBytes,
+    policy: CanonicalPolicy,
+    resolver: &dyn SchemaResolver,
+) -> Result<CborBytes, ValidateAnswersError> {
     let value: Value = ciborium::de::from_reader(answers_cbor.as_slice())
         .map_err(|err| ValidateAnswersError::Decode(err.to_string()))?;
 
-    if !matches!(value, Value::Map(_)) {
+    let Value::Map(mut answers) = value else {
         return Err(ValidateAnswersError::NotMap);
+    };
+
+    let filled = match resolve_schema(schema, resolver)? {
+        AnswersSchema::Qa(spec) => {
+            check_qa_answers(&spec, &mut answers).map_err(ValidateAnswersError::Invalid)?
+        }
+        AnswersSchema::Ir(ir) => {
+            ir.validate(&Value::Map(answers.clone()))
+                .map_err(|violations| {
+                    ValidateAnswersError::Invalid(
+                        violations
+                            .into_iter()
+                            .map(|violation| AnswerError {
+                                question_id: top_level_key(&violation.pointer),
+                                kind: AnswerErrorKind::Schema {
+                                    pointer: violation.pointer,
+                                    code: violation.code,
+                                    message: violation.message,
+                                },
+                            })
+                            .collect(),
+                    )
+                })?;
+            false
+        }
+    };
+
+    if filled {
+        // Defaults changed the payload; re-encode it but keep the policy semantics for the input.
+        if policy == CanonicalPolicy::RequireCanonical {
+            answers_cbor.ensure_canonical()?;
+        }
+        let mut bytes = Vec::new();
+        ciborium::ser::into_writer(&Value::Map(answers), &mut bytes)
+            .map_err(|err| canonical::CanonicalError::Encode(err.to_string()))?;
+        return Ok(CborBytes(match policy {
+            CanonicalPolicy::Off => bytes,
+            _ => canonical::canonicalize(&bytes)?,
+        }));
     }
 
+    apply_policy(answers_cbor, policy)
+}
+
+fn apply_policy(
+    answers_cbor: &CborBytes,
+    policy: CanonicalPolicy,
+) -> Result<CborBytes, ValidateAnswersError> {
     match policy {
         CanonicalPolicy::Off => Ok(answers_cbor.clone()),
         CanonicalPolicy::RequireCanonical => {
@@ -115,28 +290,658 @@ pub fn validate_answers(
     }
 }
 
+enum AnswersSchema {
+    Qa(ComponentQaSpec),
+    Ir(SchemaIr),
+}
+
+fn resolve_schema(
+    schema: &SchemaSource,
+    resolver: &dyn SchemaResolver,
+) -> Result<AnswersSchema, ValidateAnswersError> {
+    let bytes = match schema {
+        SchemaSource::InlineCbor(bytes) => bytes.clone(),
+        #[cfg(feature = "json-compat")]
+        SchemaSource::InlineJson(json) => {
+            let value: serde_json::Value = serde_json::from_str(json)
+                .map_err(|err| ValidateAnswersError::InvalidSchema(err.to_string()))?;
+            return decode_schema_value(&value);
+        }
+        SchemaSource::CborSchemaId(id) => {
+            let bytes = resolver
+                .resolve(schema)
+                .ok_or_else(|| ValidateAnswersError::UnresolvedSchema(id.to_string()))?;
+            let actual = schema_id_for_cbor(bytes.as_slice())
+                .map_err(|_| ValidateAnswersError::SchemaIdMismatch(id.to_string()))?;
+            if &actual != id {
+                return Err(ValidateAnswersError::SchemaIdMismatch(id.to_string()));
+            }
+            bytes
+        }
+        SchemaSource::RefPackPath(reference) | SchemaSource::RefUri(reference) => resolver
+            .resolve(schema)
+            .ok_or_else(|| ValidateAnswersError::UnresolvedSchema(reference.clone()))?,
+    };
+    let value: Value = ciborium::de::from_reader(bytes.as_slice())
+        .map_err(|err| ValidateAnswersError::InvalidSchema(err.to_string()))?;
+    decode_schema_value(&value)
+}
+
+fn decode_schema_value<T: Serialize>(value: &T) -> Result<AnswersSchema, ValidateAnswersError> {
+    let mut bytes = Vec::new();
+    ciborium::ser::into_writer(value, &mut bytes)
+        .map_err(|err| ValidateAnswersError::InvalidSchema(err.to_string()))?;
+    if let Ok(spec) = canonical::from_cbor::<ComponentQaSpec>(&bytes) {
+        return Ok(AnswersSchema::Qa(spec));
+    }
+    canonical::from_cbor::<SchemaIr>(&bytes)
+        .map(AnswersSchema::Ir)
+        .map_err(|err| ValidateAnswersError::InvalidSchema(err.to_string()))
+}
+
+/// Checks answers against QA questions; returns `true` when defaults were filled in.
+pub(crate) fn check_qa_answers(
+    spec: &ComponentQaSpec,
+    answers: &mut Vec<(Value, Value)>,
+) -> Result<bool, Vec<AnswerError>> {
+    let mut errors = Vec::new();
+    let filled = check_questions(
+        &spec.questions,
+        &spec.defaults,
+        answers,
+        None,
+        "",
+        &mut errors,
+    );
+    if errors.is_empty() {
+        Ok(filled)
+    } else {
+        Err(errors)
+    }
+}
+
+/// Checks a single top-level answer against its question, with `root` holding the other answers.
+///
+/// Returns the answer with nested defaults filled in; a `null` answer is replaced by the question
+/// default when there is one.
+pub(crate) fn check_answer(
+    question: &Question,
+    answer: Value,
+    root: &Value,
+) -> Result<Value, Vec<AnswerError>> {
+    let mut answers = vec![(Value::Text(question.id.clone()), answer)];
+    let mut errors = Vec::new();
+    check_questions(
+        core::slice::from_ref(question),
+        &BTreeMap::new(),
+        &mut answers,
+        Some(root),
+        "",
+        &mut errors,
+    );
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+    Ok(answers.pop().map(|(_, value)| value).unwrap_or(Value::Null))
+}
+
+/// Checks one level of questions (the top level, a group or a list item).
+///
+/// `root` is `None` at the top level, where the scope is the root. Hidden questions are skipped;
+/// nested errors are reported under `/`-joined ids such as `db/host` or `servers/0/name`.
+fn check_questions(
+    questions: &[Question],
+    defaults: &BTreeMap<String, Value>,
+    answers: &mut Vec<(Value, Value)>,
+    root: Option<&Value>,
+    prefix: &str,
+    errors: &mut Vec<AnswerError>,
+) -> bool {
+    for (key, _) in answers.iter() {
+        let known = matches!(key, Value::Text(key) if questions.iter().any(|q| &q.id == key));
+        if !known {
+            errors.push(AnswerError {
+                question_id: match key {
+                    Value::Text(key) => format!("{prefix}{key}"),
+                    other => format!("{prefix}{other:?}"),
+                },
+                kind: AnswerErrorKind::UnknownKey,
+            });
+        }
+    }
+
+    let mut filled = false;
+    for question in questions {
+        let id = format!("{prefix}{}", question.id);
+        // Snapshot so predicates see defaults filled for earlier questions.
+        let scope = Value::Map(answers.clone());
+        let root_value = root.unwrap_or(&scope);
+        if !question.is_visible(&scope, root_value) {
+            continue;
+        }
+        let required = question.is_required(&scope, root_value);
+        let position = answers.iter().position(|(key, value)| {
+            matches!(key, Value::Text(key) if key == &question.id) && !value.is_null()
+        });
+        let Some(position) = position else {
+            match question.default.as_ref().or_else(|| defaults.get(&id)) {
+                Some(default) => {
+                    answers
+                        .retain(|(key, _)| !matches!(key, Value::Text(key) if key == &question.id));
+                    answers.push((Value::Text(question.id.clone()), default.clone()));
+                    filled = true;
+                }
+                None if required => errors.push(AnswerError {
+                    question_id: id,
+                    kind: AnswerErrorKind::Missing,
+                }),
+                None => {}
+            }
+            continue;
+        };
+        let root_snapshot = root.cloned().unwrap_or(scope);
+        let answer = &mut answers[position].1;
+        match (&question.kind, answer) {
+            (QuestionKind::Group { questions }, Value::Map(entries)) => {
+                filled |= check_questions(
+                    questions,
+                    defaults,
+                    entries,
+                    Some(&root_snapshot),
+                    &format!("{id}/"),
+                    errors,
+                );
+            }
+            (
+                QuestionKind::List {
+                    questions,
+                    min_items,
+                    max_items,
+                },
+                Value::Array(items),
+            ) => {
+                if let Some(message) = count_violation(items.len(), *min_items, *max_items, "items")
+                {
+                    errors.push(AnswerError {
+                        question_id: id.clone(),
+                        kind: AnswerErrorKind::Constraint { message },
+                    });
+                }
+                for (index, item) in items.iter_mut().enumerate() {
+                    let item_id = format!("{id}/{index}");
+                    let Value::Map(entries) = item else {
+                        errors.push(AnswerError {
+                            question_id: item_id,
+                            kind: AnswerErrorKind::TypeMismatch {
+                                expected: "object".into(),
+                                found: value_kind(item).into(),
+                            },
+                        });
+                        continue;
+                    };
+                    filled |= check_questions(
+                        questions,
+                        defaults,
+                        entries,
+                        Some(&root_snapshot),
+                        &format!("{item_id}/"),
+                        errors,
+                    );
+                }
+            }
+            (kind, answer) => {
+                if let Some(kind) = check_question_kind(kind, answer) {
+                    errors.push(AnswerError {
+                        question_id: id,
+                        kind,
+                    });
+                }
+            }
+        }
+    }
+    filled
+}
+
+fn check_question_kind(kind: &QuestionKind, answer: &Value) -> Option<AnswerErrorKind> {
+    let expected = match (kind, answer) {
+        (QuestionKind::Text, Value::Text(_))
+        | (QuestionKind::Number, Value::Integer(_) | Value::Float(_))
+        | (QuestionKind::Bool, Value::Bool(_)) => return None,
+        (QuestionKind::Choice { options }, Value::Text(value)) => {
+            return (!options.iter().any(|option| &option.value == value)).then(|| {
+                AnswerErrorKind::InvalidChoice {
+                    value: value.clone(),
+                }
+            });
+        }
+        (
+            QuestionKind::MultiSelect {
+                options,
+                min_selected,
+                max_selected,
+            },
+            Value::Array(selected),
+        ) => {
+            let mut seen: Vec<&str> = Vec::new();
+            for item in selected {
+                let Value::Text(value) = item else {
+                    return Some(AnswerErrorKind::TypeMismatch {
+                        expected: "string".into(),
+                        found: value_kind(item).into(),
+                    });
+                };
+                if !options.iter().any(|option| &option.value == value) {
+                    return Some(AnswerErrorKind::InvalidChoice {
+                        value: value.clone(),
+                    });
+                }
+                if seen.contains(&value.as_str()) {
+                    return Some(AnswerErrorKind::Constraint {
+                        message: format!("`{value}` is selected more than once."),
+                    });
+                }
+                seen.push(value);
+            }
+            return count_violation(selected.len(), *min_selected, *max_selected, "selections")
+                .map(|message| AnswerErrorKind::Constraint { message });
+        }
+        (QuestionKind::Secret { requirement }, Value::Text(value)) => {
+            return (value != requirement.key.as_str()).then_some(AnswerErrorKind::InlineSecret);
+        }
+        (QuestionKind::Url, Value::Text(_)) => return check_text(answer, None, Some("uri")),
+        (QuestionKind::Email, Value::Text(_)) => return check_text(answer, None, Some("email")),
+        (QuestionKind::Date, Value::Text(_)) => return check_text(answer, None, Some("date")),
+        (QuestionKind::Time, Value::Text(value)) => {
+            return (!is_partial_time(value)).then(|| AnswerErrorKind::Constraint {
+                message: format!("`{value}` is not a time of day (HH:MM[:SS])."),
+            });
+        }
+        (QuestionKind::DateTime, Value::Text(_)) => {
+            return check_text(answer, None, Some("date-time"));
+        }
+        (QuestionKind::Pattern { regex }, Value::Text(_)) => {
+            return check_text(answer, Some(regex), None);
+        }
+        (QuestionKind::Integer { min, max }, Value::Integer(value)) => {
+            let value = i128::from(*value);
+            let below = min.is_some_and(|min| value < i128::from(min));
+            let above = max.is_some_and(|max| value > i128::from(max));
+            return (below || above).then(|| AnswerErrorKind::Constraint {
+                message: format!("{value} is outside {}.", range_label(*min, *max)),
+            });
+        }
+        (
+            QuestionKind::Text
+            | QuestionKind::Choice { .. }
+            | QuestionKind::Url
+            | QuestionKind::Email
+            | QuestionKind::Pattern { .. }
+            | QuestionKind::Date
+            | QuestionKind::Time
+            | QuestionKind::DateTime,
+            _,
+        ) => "string",
+        (QuestionKind::Secret { .. }, _) => return Some(AnswerErrorKind::InlineSecret),
+        (QuestionKind::Number, _) => "number",
+        (QuestionKind::Integer { .. }, _) => "integer",
+        (QuestionKind::Bool, _) => "boolean",
+        (QuestionKind::MultiSelect { .. } | QuestionKind::List { .. }, _) => "array",
+        (QuestionKind::Group { .. }, _) => "object",
+    };
+    Some(AnswerErrorKind::TypeMismatch {
+        expected: expected.into(),
+        found: value_kind(answer).into(),
+    })
+}
+
+/// Validates a text answer through the `SchemaIr` string checks.
+fn check_text(
+    answer: &Value,
+    regex: Option<&String>,
+    format: Option<&str>,
+) -> Option<AnswerErrorKind> {
+    let schema = SchemaIr::String {
+        min_len: None,
+        max_len: None,
+        regex: regex.cloned(),
+        format: format.map(Into::into),
+    };
+    schema.validate(answer).err().and_then(|violations| {
+        violations
+            .into_iter()
+            .next()
+            .map(|violation| AnswerErrorKind::Constraint {
+                message: violation.message,
+            })
+    })
+}
+
+fn count_violation(count: usize, min: Option<u32>, max: Option<u32>, noun: &str) -> Option<String> {
+    let count = u64::try_from(count).unwrap_or(u64::MAX);
+    let below = min.is_some_and(|min| count < u64::from(min));
+    let above = max.is_some_and(|max| count > u64::from(max));
+    (below || above).then(|| {
+        format!(
+            "{count} {noun} is outside {}.",
+            range_label(min.map(i64::from), max.map(i64::from))
+        )
+    })
+}
+
+fn range_label(min: Option<i64>, max: Option<i64>) -> String {
+    match (min, max) {
+        (Some(min), Some(max)) => format!("{min}..={max}"),
+        (Some(min), None) => format!("{min}.."),
+        (None, Some(max)) => format!("..={max}"),
+        (None, None) => "..".into(),
+    }
+}
+
+/// Plan for carrying answers over to a new version of a QA spec, built by [`diff_answers`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AnswerUpdatePlan {
+    /// Answers that remain valid under the new spec (after any conversion), keyed by question id.
+    pub preserved: BTreeMap<String, Value>,
+    /// Questions to ask in update mode, in new spec order.
+    pub ask: Vec<QuestionToAsk>,
+    /// Old answer keys the new spec no longer accepts: removed or hidden questions.
+    pub dropped: Vec<String>,
+    /// Answers converted because their question changed kind.
+    pub migrated: Vec<MigratedAnswer>,
+}
+
+/// Question the update flow has to ask.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct QuestionToAsk {
+    /// Top-level question id.
+    pub question_id: String,
+    /// Why the question is asked.
+    pub reason: AskReason,
+}
+
+/// Reason a question is part of an [`AnswerUpdatePlan`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AskReason {
+    /// The question did not exist in the old spec.
+    New,
+    /// The question changed kind and no conversion applied to the old answer.
+    KindChanged,
+    /// The old answer no longer satisfies the question (for example a removed choice option).
+    Invalid(Vec<AnswerError>),
+    /// The question was unanswered and is now required.
+    NowRequired,
+}
+
+/// Answer converted between question kinds.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MigratedAnswer {
+    /// Top-level question id.
+    pub question_id: String,
+    /// Answer under the old spec.
+    pub from: Value,
+    /// Converted answer.
+    pub to: Value,
+}
+
+/// Hook converting an answer when its question changes kind between spec versions.
+pub trait AnswerConverter {
+    /// Returns the converted answer, or `None` to fall back to the built-in conversions.
+    fn convert(
+        &self,
+        question_id: &str,
+        from: &QuestionKind,
+        to: &QuestionKind,
+        answer: &Value,
+    ) -> Option<Value>;
+}
+
+impl<F> AnswerConverter for F
+where
+    F: Fn(&str, &QuestionKind, &QuestionKind, &Value) -> Option<Value>,
+{
+    fn convert(
+        &self,
+        question_id: &str,
+        from: &QuestionKind,
+        to: &QuestionKind,
+        answer: &Value,
+    ) -> Option<Value> {
+        self(question_id, from, to, answer)
+    }
+}
+
+/// Converter with no custom conversions; only the built-in ones apply.
+struct BuiltinOnly;
+
+impl AnswerConverter for BuiltinOnly {
+    fn convert(&self, _: &str, _: &QuestionKind, _: &QuestionKind, _: &Value) -> Option<Value> {
+        None
+    }
+}
+
+/// Plans an update-mode run from answers given under `old_spec`, using the built-in conversions.
+///
+/// See [`diff_answers_with`] for custom type-migration hooks.
+pub fn diff_answers(
+    old_spec: &ComponentQaSpec,
+    new_spec: &ComponentQaSpec,
+    old_answers: &CborBytes,
+) -> Result<AnswerUpdatePlan, ValidateAnswersError> {
+    diff_answers_with(old_spec, new_spec, old_answers, &BuiltinOnly)
+}
+
+/// Plans an update-mode run, converting answers of questions that changed kind through
+/// `converter` first and the built-in conversions second.
+///
+/// Built-in conversions map text to the choice whose value or label fallback matches
+/// (case-insensitively), a single choice to a multi-select, numbers and booleans to text, and
+/// text to numbers, integers and booleans when it parses. Every carried-over answer is checked
+/// against the new question like [`validate_answers`] does; visibility is evaluated against the
+/// old answers. Only top-level questions are compared; group and list answers are kept or
+/// re-asked as a whole.
+pub fn diff_answers_with(
+    old_spec: &ComponentQaSpec,
+    new_spec: &ComponentQaSpec,
+    old_answers: &CborBytes,
+    converter: &dyn AnswerConverter,
+) -> Result<AnswerUpdatePlan, ValidateAnswersError> {
+    let value: Value = ciborium::de::from_reader(old_answers.as_slice())
+        .map_err(|err| ValidateAnswersError::Decode(err.to_string()))?;
+    let Value::Map(entries) = &value else {
+        return Err(ValidateAnswersError::NotMap);
+    };
+
+    let mut plan = AnswerUpdatePlan::default();
+    for question in &new_spec.questions {
+        let old = entries
+            .iter()
+            .find(|(key, answer)| key.as_text() == Some(question.id.as_str()) && !answer.is_null())
+            .map(|(_, answer)| answer);
+        let old_question = old_spec
+            .questions
+            .iter()
+            .find(|candidate| candidate.id == question.id);
+        let ask = |reason| QuestionToAsk {
+            question_id: question.id.clone(),
+            reason,
+        };
+        if !question.is_visible(&value, &value) {
+            if old.is_some() {
+                plan.dropped.push(question.id.clone());
+            }
+            continue;
+        }
+        let Some(old) = old else {
+            match old_question {
+                None => plan.ask.push(ask(AskReason::New)),
+                Some(_) if question.is_required(&value, &value) => {
+                    let has_default =
+                        question.default.is_some() || new_spec.defaults.contains_key(&question.id);
+                    if !has_default {
+                        plan.ask.push(ask(AskReason::NowRequired));
+                    }
+                }
+                Some(_) => {}
+            }
+            continue;
+        };
+        let answer = match old_question {
+            Some(old_question)
+                if core::mem::discriminant(&old_question.kind)
+                    != core::mem::discriminant(&question.kind) =>
+            {
+                let converted = converter
+                    .convert(&question.id, &old_question.kind, &question.kind, old)
+                    .or_else(|| convert_answer(&question.kind, old));
+                let Some(converted) = converted else {
+                    plan.ask.push(ask(AskReason::KindChanged));
+                    continue;
+                };
+                if &converted != old {
+                    plan.migrated.push(MigratedAnswer {
+                        question_id: question.id.clone(),
+                        from: old.clone(),
+                        to: converted.clone(),
+                    });
+                }
+                converted
+            }
+            _ => old.clone(),
+        };
+        match check_answer(question, answer, &value) {
+            Ok(answer) => {
+                plan.preserved.insert(question.id.clone(), answer);
+            }
+            Err(errors) => plan.ask.push(ask(AskReason::Invalid(errors))),
+        }
+    }
+    for (key, _) in entries {
+        let known = key
+            .as_text()
+            .is_some_and(|key| new_spec.questions.iter().any(|question| question.id == key));
+        if !known {
+            plan.dropped.push(match key {
+                Value::Text(key) => key.clone(),
+                other => format!("{other:?}"),
+            });
+        }
+    }
+    Ok(plan)
+}
+
+/// Built-in conversions between question kinds; the result is validated by the caller.
+fn convert_answer(to: &QuestionKind, answer: &Value) -> Option<Value> {
+    let text = answer.as_text().map(str::trim);
+    match to {
+        QuestionKind::Choice { options } => match answer {
+            Value::Array(items) if items.len() == 1 => convert_answer(to, &items[0]),
+            _ => match_option(options, text?),
+        },
+        QuestionKind::MultiSelect { options, .. } => match answer {
+            Value::Array(_) => Some(answer.clone()),
+            _ => match_option(options, text?).map(|value| Value::Array(vec![value])),
+        },
+        QuestionKind::Text => match answer {
+            Value::Text(_) => Some(answer.clone()),
+            Value::Integer(int) => Some(Value::Text(i128::from(*int).to_string())),
+            Value::Float(float) => Some(Value::Text(float.to_string())),
+            Value::Bool(flag) => Some(Value::Text(flag.to_string())),
+            _ => None,
+        },
+        QuestionKind::Url
+        | QuestionKind::Email
+        | QuestionKind::Pattern { .. }
+        | QuestionKind::Date
+        | QuestionKind::Time
+        | QuestionKind::DateTime => text.map(|text| Value::Text(text.to_string())),
+        QuestionKind::Number => match answer {
+            Value::Integer(_) | Value::Float(_) => Some(answer.clone()),
+            _ => {
+                let text = text?;
+                text.parse::<i64>()
+                    .ok()
+                    .map(Value::from)
+                    .or_else(|| text.parse::<f64>().ok().map(Value::Float))
+            }
+        },
+        QuestionKind::Integer { .. } => match answer {
+            Value::Integer(_) => Some(answer.clone()),
+            // Integral floats within the exactly representable range.
+            Value::Float(float) if float.fract() == 0.0 && float.abs() < 9.0e15 => {
+                Some(Value::from(*float as i64))
+            }
+            _ => text?.parse::<i64>().ok().map(Value::from),
+        },
+        QuestionKind::Bool => text.and_then(parse_bool).map(Value::Bool),
+        QuestionKind::Secret { .. } | QuestionKind::Group { .. } | QuestionKind::List { .. } => {
+            None
+        }
+    }
+}
+
+fn match_option(options: &[ChoiceOption], text: &str) -> Option<Value> {
+    options
+        .iter()
+        .find(|option| option.value == text)
+        .or_else(|| {
+            options.iter().find(|option| {
+                option.value.eq_ignore_ascii_case(text)
+                    || option
+                        .label
+                        .fallback
+                        .as_deref()
+                        .is_some_and(|label| label.eq_ignore_ascii_case(text))
+            })
+        })
+        .map(|option| Value::Text(option.value.clone()))
+}
+
+/// First reference token of a JSON pointer (the answer key).
+fn top_level_key(pointer: &str) -> String {
+    pointer
+        .trim_start_matches('/')
+        .split('/')
+        .next()
+        .unwrap_or_default()
+        .replace("~1", "/")
+        .replace("~0", "~")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use alloc::collections::BTreeMap;
 
-    fn schema_bytes() -> Vec<u8> {
+    fn answers_bytes() -> Vec<u8> {
         let mut map = BTreeMap::new();
         map.insert("key", "value");
         match canonical::to_canonical_cbor(&map) {
             Ok(bytes) => bytes,
-            Err(err) => panic!("schema canonicalization failed: {err:?}"),
+            Err(err) => panic!("answers canonicalization failed: {err:?}"),
         }
     }
 
-    fn schema_blob() -> CborBytes {
-        CborBytes(schema_bytes())
+    fn answers_blob() -> CborBytes {
+        CborBytes(answers_bytes())
+    }
+
+    /// Object schema accepting any properties.
+    fn open_schema() -> SchemaSource {
+        let schema = SchemaIr::Object {
+            properties: BTreeMap::new(),
+            required: Vec::new(),
+            additional: Default::default(),
+        };
+        match canonical::to_canonical_cbor(&schema) {
+            Ok(bytes) => SchemaSource::InlineCbor(CborBytes(bytes)),
+            Err(err) => panic!("schema canonicalization failed: {err:?}"),
+        }
     }
 
     #[test]
     fn validate_accepts_map_off_policy() {
-        let bytes = schema_blob();
-        let source = SchemaSource::InlineCbor(bytes.clone());
+        let bytes = answers_blob();
+        let source = open_schema();
         let result = match validate_answers(&source, &bytes, CanonicalPolicy::Off) {
             Ok(value) => value,
             Err(err) => panic!("validation failed: {err:?}"),
@@ -150,7 +955,7 @@ mod tests {
             Ok(value) => value,
             Err(err) => panic!("canonicalize string failed: {err:?}"),
         };
-        let source = SchemaSource::InlineCbor(CborBytes(bytes.clone()));
+        let source = open_schema();
         assert!(matches!(
             validate_answers(&source, &CborBytes(bytes), CanonicalPolicy::Off),
             Err(ValidateAnswersError::NotMap)
@@ -160,7 +965,7 @@ mod tests {
     #[test]
     fn canonicalize_policy_rewrites_indefinite_map() {
         let indefinite = vec![0xBF, 0x61, b'a', 0x01, 0xFF];
-        let source = SchemaSource::InlineCbor(CborBytes::new(indefinite.clone()));
+        let source = open_schema();
         let canonical_bytes = match validate_answers(
             &source,
             &CborBytes(indefinite),
@@ -177,7 +982,7 @@ mod tests {
     #[test]
     fn require_canonical_rejects_indefinite() {
         let indefinite = vec![0xBF, 0x61, b'a', 0x01, 0xFF];
-        let source = SchemaSource::InlineCbor(CborBytes::new(indefinite.clone()));
+        let source = open_schema();
         assert!(matches!(
             validate_answers(
                 &source,
@@ -0,0 +1,260 @@
+//! Headless driver that walks a QA spec one question at a time.
+//!
+//! [`QaSession`] holds the spec, the answers given so far and the order they were given in, so
+//! every front end (CLI, console, chat) asks the same questions in the same order. Sessions are
+//! plain serde values: a chat runtime can store one in [`SessionData`](crate::SessionData)
+//! between turns and resume it on the next message.
+//!
+//! Questions are walked at the top level in spec order, skipping questions hidden by
+//! `visible_if`. Group and list questions are answered as a whole (an object or an array of
+//! objects). In [`QaMode::Update`] every visible question is asked again with the existing answer
+//! as its current value; in the other modes questions that already have an existing answer are
+//! skipped.
+
+use alloc::{
+    collections::BTreeMap,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use ciborium::value::Value;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::cbor::canonical;
+use crate::cbor_bytes::CborBytes;
+use crate::qa::{
+    AnswerError, CanonicalPolicy, ValidateAnswersError, check_answer, validate_answers,
+};
+use crate::schema_id::SchemaSource;
+use crate::schemas::component::v0_6_0::{ComponentQaSpec, QaMode, Question};
+use crate::schemas::pack::v0_6_0::{PackQaSpec, QaMode as PackQaMode};
+
+/// Errors produced while driving a [`QaSession`].
+#[derive(Debug, Error)]
+pub enum QaSessionError {
+    /// The answered question id is not a top-level question of the spec.
+    #[error("unknown question `{0}`")]
+    UnknownQuestion(String),
+    /// The question is hidden by its `visible_if` predicate.
+    #[error("question `{0}` is not visible")]
+    HiddenQuestion(String),
+    /// The answer does not satisfy the question.
+    #[error("answer failed validation ({} errors)", .0.len())]
+    Invalid(Vec<AnswerError>),
+    /// The session was finished while a question was still pending.
+    #[error("question `{0}` has not been answered")]
+    Incomplete(String),
+    /// Encoding or decoding CBOR failed.
+    #[error(transparent)]
+    Canonical(#[from] canonical::CanonicalError),
+    /// The final answers were rejected by [`validate_answers`].
+    #[error(transparent)]
+    Validate(#[from] ValidateAnswersError),
+}
+
+/// Question presented to the user by [`QaSession::next_question`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct QaPrompt<'a> {
+    /// Question to ask.
+    pub question: &'a Question,
+    /// Current value: the existing answer in update mode, otherwise the question/spec default.
+    pub current: Option<&'a Value>,
+    /// Whether an answer is required, given the answers so far.
+    pub required: bool,
+}
+
+/// State machine walking a QA spec.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, PartialEq)]
+pub struct QaSession {
+    spec: ComponentQaSpec,
+    mode: QaMode,
+    /// Answers passed in when the session started; restored by [`QaSession::back`].
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "BTreeMap::is_empty")
+    )]
+    existing: BTreeMap<String, Value>,
+    answers: BTreeMap<String, Value>,
+    /// Question ids answered in this session, oldest first.
+    history: Vec<String>,
+}
+
+impl QaSession {
+    /// Starts a session over a component QA spec.
+    pub fn new(spec: ComponentQaSpec, mode: QaMode) -> Self {
+        Self {
+            spec,
+            mode,
+            existing: BTreeMap::new(),
+            answers: BTreeMap::new(),
+            history: Vec::new(),
+        }
+    }
+
+    /// Starts a session over a pack QA spec.
+    pub fn for_pack(spec: &PackQaSpec, mode: PackQaMode) -> Result<Self, QaSessionError> {
+        // Pack and component QA specs share one wire format.
+        let spec = canonical::from_cbor(&canonical::to_canonical_cbor(spec)?)?;
+        let mode = match mode {
+            PackQaMode::Default => QaMode::Default,
+            PackQaMode::Setup => QaMode::Setup,
+            PackQaMode::Update => QaMode::Update,
+            PackQaMode::Remove => QaMode::Remove,
+        };
+        Ok(Self::new(spec, mode))
+    }
+
+    /// Seeds the session with previously stored answers (a CBOR map), typically for update mode.
+    pub fn with_existing_answers(mut self, answers: &CborBytes) -> Result<Self, QaSessionError> {
+        self.existing = canonical::from_cbor(answers.as_slice())?;
+        self.answers = self.existing.clone();
+        self.history.clear();
+        Ok(self)
+    }
+
+    /// Spec being walked.
+    pub fn spec(&self) -> &ComponentQaSpec {
+        &self.spec
+    }
+
+    /// Mode the session runs in.
+    pub fn mode(&self) -> &QaMode {
+        &self.mode
+    }
+
+    /// Answers collected so far, including existing answers.
+    pub fn answers(&self) -> &BTreeMap<String, Value> {
+        &self.answers
+    }
+
+    /// Next question to ask, or `None` when every visible question has been handled.
+    pub fn next_question(&self) -> Option<QaPrompt<'_>> {
+        let root = self.root();
+        self.spec
+            .questions
+            .iter()
+            .find(|question| self.is_pending(question, &root))
+            .map(|question| QaPrompt {
+                question,
+                current: self
+                    .answers
+                    .get(&question.id)
+                    .or(question.default.as_ref())
+                    .or_else(|| self.spec.defaults.get(&question.id)),
+                required: question.is_required(&root, &root),
+            })
+    }
+
+    /// Records an answer for a visible top-level question.
+    ///
+    /// Questions may be answered out of order. A `null` answer skips an optional question (or
+    /// takes its default). Answers are checked like [`validate_answers`] checks them; a rejected
+    /// answer leaves the session unchanged.
+    pub fn answer(&mut self, id: &str, value: Value) -> Result<(), QaSessionError> {
+        let root = self.root();
+        let question = self
+            .spec
+            .questions
+            .iter()
+            .find(|question| question.id == id)
+            .ok_or_else(|| QaSessionError::UnknownQuestion(id.to_string()))?;
+        if !question.is_visible(&root, &root) {
+            return Err(QaSessionError::HiddenQuestion(id.to_string()));
+        }
+        let value = check_answer(question, value, &root).map_err(QaSessionError::Invalid)?;
+        if value.is_null() {
+            self.answers.remove(id);
+        } else {
+            self.answers.insert(id.to_string(), value);
+        }
+        self.history.retain(|answered| answered != id);
+        self.history.push(id.to_string());
+        Ok(())
+    }
+
+    /// Undoes the most recent answer and returns the question to ask again.
+    ///
+    /// The question's existing answer, if any, is restored.
+    pub fn back(&mut self) -> Option<&Question> {
+        let id = self.history.pop()?;
+        match self.existing.get(&id) {
+            Some(value) => self.answers.insert(id.clone(), value.clone()),
+            None => self.answers.remove(&id),
+        };
+        self.spec
+            .questions
+            .iter()
+            .find(|question| question.id == id)
+    }
+
+    /// Whether every visible question has been handled.
+    pub fn is_complete(&self) -> bool {
+        self.next_question().is_none()
+    }
+
+    /// Keys of existing answers that match no question of the spec, e.g. after a spec upgrade.
+    ///
+    /// [`QaSession::finish`] leaves these out of the final answers.
+    pub fn stale_answers(&self) -> Vec<&str> {
+        self.answers
+            .keys()
+            .filter(|id| {
+                !self
+                    .spec
+                    .questions
+                    .iter()
+                    .any(|question| &question.id == *id)
+            })
+            .map(String::as_str)
+            .collect()
+    }
+
+    /// Validates the collected answers and returns them as canonical CBOR.
+    ///
+    /// Answers to questions that became hidden, and existing answers whose key is no longer a
+    /// question of the spec (see [`QaSession::stale_answers`]), are dropped; defaults are filled
+    /// in by [`validate_answers`].
+    pub fn finish(&self) -> Result<CborBytes, QaSessionError> {
+        if let Some(prompt) = self.next_question() {
+            return Err(QaSessionError::Incomplete(prompt.question.id.clone()));
+        }
+        let root = self.root();
+        let answers: BTreeMap<&String, &Value> = self
+            .answers
+            .iter()
+            .filter(|(id, _)| {
+                self.spec
+                    .questions
+                    .iter()
+                    .find(|question| &question.id == *id)
+                    .is_some_and(|question| question.is_visible(&root, &root))
+            })
+            .collect();
+        let schema =
+            SchemaSource::InlineCbor(CborBytes::new(canonical::to_canonical_cbor(&self.spec)?));
+        let answers = CborBytes::new(canonical::to_canonical_cbor(&answers)?);
+        Ok(validate_answers(
+            &schema,
+            &answers,
+            CanonicalPolicy::Canonicalize,
+        )?)
+    }
+
+    fn is_pending(&self, question: &Question, root: &Value) -> bool {
+        !self.history.contains(&question.id)
+            && (self.mode == QaMode::Update || !self.existing.contains_key(&question.id))
+            && question.is_visible(root, root)
+    }
+
+    fn root(&self) -> Value {
+        Value::Map(
+            self.answers
+                .iter()
+                .map(|(id, value)| (Value::Text(id.clone()), value.clone()))
+                .collect(),
+        )
+    }
+}
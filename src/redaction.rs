@@ -0,0 +1,504 @@
+//! Applies component [`RedactionRule`]s to payloads before they are logged or displayed.
+//!
+//! Rules address values with JSON pointers; a `*` segment matches every array element or object
+//! member at that level (`/items/*/token`). When several rules hit the same value the strongest
+//! wins (`Drop` > `Secret` > `Mask`). Rules can also be derived from a [`SchemaIr`]: string
+//! fields whose `format` is `secret` or `password` are treated as secrets.
+//!
+//! ```
+//! use greentic_types::redaction::Redactor;
+//! use greentic_types::{ComponentRedactionKind, ComponentRedactionRule};
+//! use serde_json::json;
+//!
+//! let redactor = Redactor::new(vec![ComponentRedactionRule {
+//!     json_pointer: "/users/*/token".into(),
+//!     kind: ComponentRedactionKind::Secret,
+//! }]);
+//! let value = redactor.redacted_json(&json!({ "users": [{ "name": "ada", "token": "t0k" }] }));
+//! assert_eq!(value, json!({ "users": [{ "name": "ada", "token": "[REDACTED]" }] }));
+//! ```
+use alloc::{
+    borrow::ToOwned,
+    collections::BTreeMap,
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::fmt;
+
+use ciborium::value::Value as CborValue;
+use serde_json::Value as JsonValue;
+
+use crate::schemas::common::definitions::SchemaDefinitions;
+use crate::schemas::common::schema_ir::{AdditionalProperties, SchemaIr};
+use crate::schemas::component::v0_6_0::{ComponentOperation, RedactionKind, RedactionRule};
+use crate::{InvocationEnvelope, NodeFailure};
+
+/// Replacement written for `Secret` values.
+pub const REDACTED: &str = "[REDACTED]";
+/// Prefix written for `Mask` values.
+pub const MASK: &str = "****";
+/// Wildcard pointer segment matching every array element or object member.
+pub const WILDCARD: &str = "*";
+
+/// String formats treated as secrets when deriving rules from a schema.
+const SECRET_FORMATS: &[&str] = &["secret", "password"];
+/// Masked strings at least this long keep their last four characters.
+const MASK_REVEAL_MIN_LEN: usize = 12;
+
+/// Payload of an operation that a redactor is built for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PayloadDirection {
+    /// Invocation input, described by the operation's input schema.
+    Input,
+    /// Invocation output, described by the operation's output schema.
+    Output,
+}
+
+/// Applies redaction rules to JSON and CBOR values.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Redactor {
+    rules: Vec<RedactionRule>,
+}
+
+impl Redactor {
+    /// Creates a redactor from explicit rules.
+    pub fn new(rules: Vec<RedactionRule>) -> Self {
+        let mut redactor = Self::default();
+        redactor.extend(rules);
+        redactor
+    }
+
+    /// Creates a redactor from the rules derived from `schema` (see [`rules_from_schema`]).
+    pub fn from_schema(schema: &SchemaIr) -> Self {
+        Self::new(rules_from_schema(schema))
+    }
+
+    /// Creates a redactor for one payload of an operation: declared rules plus rules derived
+    /// from the input or output schema, so a pointer from one schema never hits the other payload.
+    pub fn for_operation(operation: &ComponentOperation, direction: PayloadDirection) -> Self {
+        let schema = match direction {
+            PayloadDirection::Input => &operation.input.schema,
+            PayloadDirection::Output => &operation.output.schema,
+        };
+        let mut redactor = Self::new(operation.redactions.clone());
+        redactor.extend(rules_from_schema(schema));
+        redactor
+    }
+
+    /// Adds rules, skipping exact duplicates.
+    pub fn extend(&mut self, rules: impl IntoIterator<Item = RedactionRule>) {
+        for rule in rules {
+            if !self.rules.contains(&rule) {
+                self.rules.push(rule);
+            }
+        }
+    }
+
+    /// Returns the active rules.
+    pub fn rules(&self) -> &[RedactionRule] {
+        &self.rules
+    }
+
+    /// Returns `true` when no rules are configured.
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Redacts a JSON value in place.
+    pub fn redact_json(&self, value: &mut JsonValue) {
+        self.apply(value);
+    }
+
+    /// Returns a redacted copy of a JSON value.
+    pub fn redacted_json(&self, value: &JsonValue) -> JsonValue {
+        let mut value = value.clone();
+        self.redact_json(&mut value);
+        value
+    }
+
+    /// Redacts a CBOR value in place.
+    pub fn redact_cbor(&self, value: &mut CborValue) {
+        self.apply(value);
+    }
+
+    /// Returns a redacted copy of a CBOR value.
+    pub fn redacted_cbor(&self, value: &CborValue) -> CborValue {
+        let mut value = value.clone();
+        self.redact_cbor(&mut value);
+        value
+    }
+
+    /// Redacts an encoded payload, keeping its encoding (JSON or CBOR).
+    ///
+    /// Payloads that decode as neither are replaced by [`REDACTED`] whenever rules are present,
+    /// since their contents cannot be inspected.
+    pub fn redact_payload(&self, payload: &mut Vec<u8>) {
+        if self.is_empty() || payload.is_empty() {
+            return;
+        }
+        if let Ok(mut json) = serde_json::from_slice::<JsonValue>(payload) {
+            self.redact_json(&mut json);
+            if let Ok(encoded) = serde_json::to_vec(&json) {
+                *payload = encoded;
+                return;
+            }
+        }
+        if let Ok(mut cbor) = ciborium::de::from_reader::<CborValue, _>(payload.as_slice()) {
+            self.redact_cbor(&mut cbor);
+            let mut encoded = Vec::new();
+            if ciborium::ser::into_writer(&cbor, &mut encoded).is_ok() {
+                *payload = encoded;
+                return;
+            }
+        }
+        *payload = REDACTED.as_bytes().to_vec();
+    }
+
+    fn apply<T: Node>(&self, value: &mut T) {
+        // Weakest first, so stronger rules overwrite what weaker ones produced.
+        for kind in [
+            RedactionKind::Mask,
+            RedactionKind::Secret,
+            RedactionKind::Drop,
+        ] {
+            for rule in self.rules.iter().filter(|rule| rule.kind == kind) {
+                let segments = parse_pointer(&rule.json_pointer);
+                apply_at(value, &segments, &rule.kind);
+            }
+        }
+    }
+}
+
+/// Types that can be redacted with a [`Redactor`].
+pub trait Redact {
+    /// Redacts `self` in place.
+    fn redact(&mut self, redactor: &Redactor);
+}
+
+impl Redact for JsonValue {
+    fn redact(&mut self, redactor: &Redactor) {
+        redactor.redact_json(self);
+    }
+}
+
+impl Redact for CborValue {
+    fn redact(&mut self, redactor: &Redactor) {
+        redactor.redact_cbor(self);
+    }
+}
+
+impl Redact for InvocationEnvelope {
+    /// Redacts the payload; routing fields and metadata are left as-is.
+    fn redact(&mut self, redactor: &Redactor) {
+        redactor.redact_payload(&mut self.payload);
+    }
+}
+
+impl Redact for NodeFailure {
+    /// Redacts `details`, addressing entries as `/<key>`.
+    fn redact(&mut self, redactor: &Redactor) {
+        let mut details = JsonValue::Object(
+            self.details
+                .iter()
+                .map(|(key, value)| (key.clone(), JsonValue::String(value.clone())))
+                .collect(),
+        );
+        redactor.redact_json(&mut details);
+        self.details = match details {
+            JsonValue::Object(entries) => entries
+                .into_iter()
+                .map(|(key, value)| match value {
+                    JsonValue::String(text) => (key, text),
+                    other => (key, other.to_string()),
+                })
+                .collect(),
+            _ => BTreeMap::new(),
+        };
+    }
+}
+
+/// Borrowed value that formats in redacted form, for logging.
+///
+/// `Debug` prints a redacted copy. For envelopes, `Display` renders the payload as JSON.
+pub struct Redacted<'a, T> {
+    value: &'a T,
+    redactor: &'a Redactor,
+}
+
+impl<'a, T> Redacted<'a, T> {
+    /// Wraps `value` for redacted formatting.
+    pub fn new(value: &'a T, redactor: &'a Redactor) -> Self {
+        Self { value, redactor }
+    }
+}
+
+impl<T: Redact + Clone + fmt::Debug> fmt::Debug for Redacted<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut copy = self.value.clone();
+        copy.redact(self.redactor);
+        fmt::Debug::fmt(&copy, f)
+    }
+}
+
+impl fmt::Display for Redacted<'_, InvocationEnvelope> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let envelope = self.value;
+        write!(f, "{} {}", envelope.flow_id, envelope.op)?;
+        if let Some(node_id) = &envelope.node_id {
+            write!(f, " node={node_id}")?;
+        }
+        let mut payload = envelope.payload.clone();
+        self.redactor.redact_payload(&mut payload);
+        let rendered = serde_json::from_slice::<JsonValue>(&payload)
+            .ok()
+            .or_else(|| {
+                ciborium::de::from_reader::<CborValue, _>(payload.as_slice())
+                    .ok()
+                    .and_then(|cbor| serde_json::to_value(cbor).ok())
+            });
+        match rendered {
+            Some(json) => write!(f, " payload={json}"),
+            None => write!(f, " payload=<{} bytes>", payload.len()),
+        }
+    }
+}
+
+impl fmt::Display for Redacted<'_, NodeFailure> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut failure = self.value.clone();
+        failure.redact(self.redactor);
+        write!(f, "{}: {}", failure.code, failure.message)?;
+        for (key, value) in &failure.details {
+            write!(f, " {key}={value}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Derives `Secret` rules for string fields with a `secret` or `password` format.
+///
+/// Array items and additional properties become `*` segments; `OneOf` variants share the pointer
+/// of the union. Refs are not followed; use [`rules_from_schema_with`] for that.
+pub fn rules_from_schema(schema: &SchemaIr) -> Vec<RedactionRule> {
+    let mut rules = Vec::new();
+    collect_rules(schema, None, "", &mut Vec::new(), &mut rules);
+    rules
+}
+
+/// Like [`rules_from_schema`], following refs through `definitions`.
+pub fn rules_from_schema_with(
+    schema: &SchemaIr,
+    definitions: &SchemaDefinitions,
+) -> Vec<RedactionRule> {
+    let mut rules = Vec::new();
+    collect_rules(schema, Some(definitions), "", &mut Vec::new(), &mut rules);
+    rules
+}
+
+fn collect_rules(
+    schema: &SchemaIr,
+    definitions: Option<&SchemaDefinitions>,
+    pointer: &str,
+    active: &mut Vec<String>,
+    rules: &mut Vec<RedactionRule>,
+) {
+    match schema {
+        SchemaIr::String {
+            format: Some(format),
+            ..
+        } if SECRET_FORMATS.contains(&format.as_str()) => {
+            let rule = RedactionRule {
+                json_pointer: pointer.to_owned(),
+                kind: RedactionKind::Secret,
+            };
+            if !rules.contains(&rule) {
+                rules.push(rule);
+            }
+        }
+        SchemaIr::Object {
+            properties,
+            additional,
+            ..
+        } => {
+            for (name, property) in properties {
+                let child = format!("{pointer}/{}", escape_segment(name));
+                collect_rules(property, definitions, &child, active, rules);
+            }
+            if let AdditionalProperties::Schema(extra) = additional {
+                let child = format!("{pointer}/{WILDCARD}");
+                collect_rules(extra, definitions, &child, active, rules);
+            }
+        }
+        SchemaIr::Array { items, .. } => {
+            let child = format!("{pointer}/{WILDCARD}");
+            collect_rules(items, definitions, &child, active, rules);
+        }
+        SchemaIr::OneOf { variants } => {
+            for variant in variants {
+                collect_rules(variant, definitions, pointer, active, rules);
+            }
+        }
+        SchemaIr::Ref { id } => {
+            if let Some(target) = definitions.and_then(|defs| defs.get(id))
+                && !active.contains(id)
+            {
+                active.push(id.clone());
+                collect_rules(target, definitions, pointer, active, rules);
+                active.pop();
+            }
+        }
+        _ => {}
+    }
+}
+
+fn escape_segment(segment: &str) -> String {
+    segment.replace('~', "~0").replace('/', "~1")
+}
+
+fn parse_pointer(pointer: &str) -> Vec<String> {
+    if pointer.is_empty() {
+        return Vec::new();
+    }
+    pointer
+        .strip_prefix('/')
+        .unwrap_or(pointer)
+        .split('/')
+        .map(|segment| segment.replace("~1", "/").replace("~0", "~"))
+        .collect()
+}
+
+fn apply_at<T: Node>(value: &mut T, segments: &[String], kind: &RedactionKind) {
+    match segments {
+        // The root cannot be removed, so dropping it redacts it instead.
+        [] => value.replace(kind),
+        [last] if *kind == RedactionKind::Drop => value.remove(last),
+        [first, rest @ ..] => {
+            for child in value.children_mut(first) {
+                apply_at(child, rest, kind);
+            }
+        }
+    }
+}
+
+fn mask(text: &str) -> String {
+    let len = text.chars().count();
+    if len >= MASK_REVEAL_MIN_LEN {
+        let tail: String = text.chars().skip(len - 4).collect();
+        format!("{MASK}{tail}")
+    } else {
+        MASK.to_owned()
+    }
+}
+
+/// Minimal tree access shared by the JSON and CBOR walkers.
+trait Node: Sized {
+    /// Children addressed by `segment` (`*` selects all of them).
+    fn children_mut(&mut self, segment: &str) -> Vec<&mut Self>;
+    /// Removes the children addressed by `segment`.
+    fn remove(&mut self, segment: &str);
+    /// Replaces the value according to `kind`.
+    fn replace(&mut self, kind: &RedactionKind);
+}
+
+impl Node for JsonValue {
+    fn children_mut(&mut self, segment: &str) -> Vec<&mut Self> {
+        if segment == WILDCARD {
+            return match self {
+                JsonValue::Object(entries) => entries.values_mut().collect(),
+                JsonValue::Array(items) => items.iter_mut().collect(),
+                _ => Vec::new(),
+            };
+        }
+        match self {
+            JsonValue::Object(entries) => entries.get_mut(segment).into_iter().collect(),
+            JsonValue::Array(items) => segment
+                .parse::<usize>()
+                .ok()
+                .and_then(|index| items.get_mut(index))
+                .into_iter()
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    fn remove(&mut self, segment: &str) {
+        match self {
+            JsonValue::Object(entries) if segment == WILDCARD => entries.clear(),
+            JsonValue::Object(entries) => {
+                entries.remove(segment);
+            }
+            JsonValue::Array(items) if segment == WILDCARD => items.clear(),
+            JsonValue::Array(items) => {
+                if let Ok(index) = segment.parse::<usize>()
+                    && index < items.len()
+                {
+                    items.remove(index);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn replace(&mut self, kind: &RedactionKind) {
+        *self = match (kind, &*self) {
+            (RedactionKind::Mask, JsonValue::String(text)) => JsonValue::String(mask(text)),
+            (RedactionKind::Mask, _) => JsonValue::String(MASK.into()),
+            _ => JsonValue::String(REDACTED.into()),
+        };
+    }
+}
+
+impl Node for CborValue {
+    fn children_mut(&mut self, segment: &str) -> Vec<&mut Self> {
+        match self {
+            CborValue::Map(entries) => entries
+                .iter_mut()
+                .filter(|(key, _)| {
+                    segment == WILDCARD || matches!(key, CborValue::Text(key) if key == segment)
+                })
+                .map(|(_, value)| value)
+                .collect(),
+            CborValue::Array(items) => {
+                if segment == WILDCARD {
+                    items.iter_mut().collect()
+                } else {
+                    segment
+                        .parse::<usize>()
+                        .ok()
+                        .and_then(|index| items.get_mut(index))
+                        .into_iter()
+                        .collect()
+                }
+            }
+            CborValue::Tag(_, inner) => inner.children_mut(segment),
+            _ => Vec::new(),
+        }
+    }
+
+    fn remove(&mut self, segment: &str) {
+        match self {
+            CborValue::Map(entries) => entries.retain(|(key, _)| {
+                segment != WILDCARD && !matches!(key, CborValue::Text(key) if key == segment)
+            }),
+            CborValue::Array(items) if segment == WILDCARD => items.clear(),
+            CborValue::Array(items) => {
+                if let Ok(index) = segment.parse::<usize>()
+                    && index < items.len()
+                {
+                    items.remove(index);
+                }
+            }
+            CborValue::Tag(_, inner) => inner.remove(segment),
+            _ => {}
+        }
+    }
+
+    fn replace(&mut self, kind: &RedactionKind) {
+        *self = match (kind, &*self) {
+            (RedactionKind::Mask, CborValue::Text(text)) => CborValue::Text(mask(text)),
+            (RedactionKind::Mask, _) => CborValue::Text(MASK.into()),
+            _ => CborValue::Text(REDACTED.into()),
+        };
+    }
+}
@@ -0,0 +1,856 @@
+//! TypeScript and Rust type declarations generated from [`SchemaIr`].
+//!
+//! [`TypeGenerator`] lowers one or more named root schemas into type definitions and renders them
+//! as TypeScript (`export interface`/`export type`) or Rust (serde-annotated structs and enums).
+//! Output is deterministic: types appear in discovery order starting from the roots, and fields
+//! follow property order.
+//!
+//! Mapping rules:
+//!
+//! - objects with properties become interfaces/structs; optional fields are `?`/`Option<T>`,
+//!   `Forbid` adds `#[serde(deny_unknown_fields)]` and open objects get an index
+//!   signature/flattened `extra` map. Objects without properties become maps,
+//! - string-only enums become string literal unions/unit enums; other enums fall back to literal
+//!   unions in TypeScript and `serde_json::Value` in Rust,
+//! - `OneOf` with a `Null` variant becomes nullable/`Option<T>`. Object variants that all share a
+//!   required single-string-value property are rendered as a union tagged by that property
+//!   (`#[serde(tag = "...")]`); anything else is an untagged union,
+//! - `Ref`s become named types taken from the definitions; Rust boxes direct ref fields so
+//!   recursive types compile,
+//! - bytes are `Uint8Array` in TypeScript and a generated `ByteString` newtype in Rust. Both
+//!   follow the wire format [`SchemaIr::validate`] and [`SchemaIr::to_json_schema`] expect: a CBOR
+//!   byte string, or base64 text in JSON (`ByteString` switches on `is_human_readable` through
+//!   `serde_with`).
+use alloc::{
+    borrow::ToOwned,
+    boxed::Box,
+    collections::{BTreeMap, BTreeSet},
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::fmt::Write;
+
+use serde_json::Value as JsonValue;
+
+use super::definitions::{SchemaDefinitions, SchemaRefError};
+use super::json_schema::cbor_to_json;
+use super::schema_ir::{AdditionalProperties, SchemaIr};
+use crate::schemas::component::v0_6_0::ComponentDescribe;
+
+const HEADER: &str = "Generated from SchemaIr by greentic-types. Do not edit.";
+/// Note prepended to TypeScript output that uses `Uint8Array`.
+const TS_BYTES_NOTE: &str =
+    "// `Uint8Array` fields are CBOR byte strings; in JSON they are base64-encoded strings.\n";
+/// Rust type generated for `SchemaIr::Bytes`.
+const RUST_BYTES_TYPE: &str = "ByteString";
+/// Definition of [`RUST_BYTES_TYPE`], emitted once when a generated type uses it.
+const RUST_BYTES_DEF: &str = "
+/// Byte string: a CBOR byte string, or base64 text in human-readable formats such as JSON.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ByteString(pub Vec<u8>);
+
+type ByteStringAs = serde_with::IfIsHumanReadable<serde_with::base64::Base64, serde_with::Bytes>;
+
+impl Serialize for ByteString {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        <ByteStringAs as serde_with::SerializeAs<Vec<u8>>>::serialize_as(&self.0, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for ByteString {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        <ByteStringAs as serde_with::DeserializeAs<'de, Vec<u8>>>::deserialize_as(deserializer)
+            .map(Self)
+    }
+}
+";
+
+/// Generates TypeScript and Rust type declarations from schemas.
+#[derive(Clone, Debug, Default)]
+pub struct TypeGenerator<'a> {
+    roots: Vec<(String, &'a SchemaIr)>,
+    definitions: Option<&'a SchemaDefinitions>,
+}
+
+impl<'a> TypeGenerator<'a> {
+    /// Creates a generator with no roots.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a generator for a component: `Config` plus `<Op>Input`/`<Op>Output` per operation.
+    pub fn for_describe(describe: &'a ComponentDescribe) -> Self {
+        let mut generator = Self::new().with_root("Config", &describe.config_schema);
+        for operation in &describe.operations {
+            let base = pascal_case(&operation.id);
+            generator = generator
+                .with_root(format!("{base}Input"), &operation.input.schema)
+                .with_root(format!("{base}Output"), &operation.output.schema);
+        }
+        generator
+    }
+
+    /// Adds a root schema emitted under `name` (converted to PascalCase).
+    pub fn with_root(mut self, name: impl AsRef<str>, schema: &'a SchemaIr) -> Self {
+        self.roots.push((pascal_case(name.as_ref()), schema));
+        self
+    }
+
+    /// Resolves `Ref` schemas through `definitions`.
+    pub fn with_definitions(mut self, definitions: &'a SchemaDefinitions) -> Self {
+        self.definitions = Some(definitions);
+        self
+    }
+
+    /// Renders TypeScript declarations.
+    pub fn typescript(&self) -> Result<String, SchemaRefError> {
+        let defs = self.lower()?;
+        let mut body = String::new();
+        for def in &defs.items {
+            body.push('\n');
+            render_ts(def, &mut body);
+        }
+        let mut out = format!("// {HEADER}\n");
+        if body.contains("Uint8Array") {
+            out.push_str(TS_BYTES_NOTE);
+        }
+        out.push_str(&body);
+        Ok(out)
+    }
+
+    /// Renders Rust declarations using `serde` derives (and `serde_json::Value` for open values).
+    pub fn rust(&self) -> Result<String, SchemaRefError> {
+        let defs = self.lower()?;
+        let mut body = String::new();
+        for def in &defs.items {
+            body.push('\n');
+            render_rust(def, &defs.boxed, &mut body);
+        }
+        let mut out = format!("// {HEADER}\n\n");
+        if body.contains("BTreeMap<") {
+            out.push_str("use std::collections::BTreeMap;\n\n");
+        }
+        out.push_str("use serde::{Deserialize, Serialize};\n");
+        if body.contains(RUST_BYTES_TYPE) {
+            out.push_str(RUST_BYTES_DEF);
+        }
+        out.push_str(&body);
+        Ok(out)
+    }
+
+    fn lower(&self) -> Result<Lowered, SchemaRefError> {
+        let mut lowering = Lowering {
+            definitions: self.definitions,
+            taken: BTreeSet::from([RUST_BYTES_TYPE.to_owned()]),
+            refs: BTreeMap::new(),
+            out: Lowered::default(),
+        };
+        for (name, _) in &self.roots {
+            lowering.taken.insert(name.clone());
+        }
+        for (name, schema) in &self.roots {
+            let ty = lowering.define(schema, name)?;
+            // Named roots that lowered to a primitive still get an alias.
+            if ty != Ty::Named(name.clone()) {
+                lowering.out.items.push(Def::Alias {
+                    name: name.clone(),
+                    ty,
+                });
+            }
+        }
+        Ok(lowering.out)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Ty {
+    String,
+    Int,
+    Float,
+    Bool,
+    Null,
+    Bytes,
+    Any,
+    Literals(Vec<JsonValue>),
+    Array(Box<Ty>),
+    Map(Box<Ty>),
+    Named(String),
+    Optional(Box<Ty>),
+}
+
+#[derive(Debug)]
+struct Field {
+    name: String,
+    ty: Ty,
+    required: bool,
+}
+
+#[derive(Debug)]
+struct Variant {
+    name: String,
+    ty: Ty,
+}
+
+#[derive(Debug)]
+enum Def {
+    Alias {
+        name: String,
+        ty: Ty,
+    },
+    Struct {
+        name: String,
+        fields: Vec<Field>,
+        extra: Option<Ty>,
+        deny_unknown: bool,
+        tag: Option<(String, String)>,
+    },
+    StringEnum {
+        name: String,
+        values: Vec<String>,
+    },
+    Union {
+        name: String,
+        tag: Option<String>,
+        variants: Vec<Variant>,
+    },
+}
+
+#[derive(Debug, Default)]
+struct Lowered {
+    items: Vec<Def>,
+    /// Named types coming from refs; Rust boxes them in fields.
+    boxed: BTreeSet<String>,
+}
+
+struct Lowering<'a> {
+    definitions: Option<&'a SchemaDefinitions>,
+    taken: BTreeSet<String>,
+    refs: BTreeMap<String, String>,
+    out: Lowered,
+}
+
+impl<'a> Lowering<'a> {
+    fn reserve(&mut self, name: &str) -> String {
+        let mut candidate = name.to_owned();
+        let mut counter = 2;
+        while self.taken.contains(&candidate) {
+            candidate = format!("{name}{counter}");
+            counter += 1;
+        }
+        self.taken.insert(candidate.clone());
+        candidate
+    }
+
+    /// Lowers `schema`, emitting named definitions under `name` (already reserved).
+    fn define(&mut self, schema: &SchemaIr, name: &str) -> Result<Ty, SchemaRefError> {
+        match schema {
+            SchemaIr::Object {
+                properties,
+                required,
+                additional,
+            } => {
+                if properties.is_empty() && *additional != AdditionalProperties::Forbid {
+                    return Ok(Ty::Map(Box::new(self.additional(additional, name)?)));
+                }
+                self.object(name, properties, required, additional, None)?;
+                Ok(Ty::Named(name.to_owned()))
+            }
+            SchemaIr::Enum { values } => {
+                let texts: Option<Vec<String>> = values
+                    .iter()
+                    .map(|value| match value {
+                        ciborium::value::Value::Text(text) => Some(text.clone()),
+                        _ => None,
+                    })
+                    .collect();
+                match texts {
+                    Some(values) if !values.is_empty() => {
+                        self.out.items.push(Def::StringEnum {
+                            name: name.to_owned(),
+                            values,
+                        });
+                        Ok(Ty::Named(name.to_owned()))
+                    }
+                    _ => Ok(values
+                        .iter()
+                        .map(cbor_to_json)
+                        .collect::<Option<Vec<_>>>()
+                        .map_or(Ty::Any, Ty::Literals)),
+                }
+            }
+            SchemaIr::OneOf { variants } => {
+                let non_null: Vec<&SchemaIr> = variants
+                    .iter()
+                    .filter(|variant| **variant != SchemaIr::Null)
+                    .collect();
+                let nullable = non_null.len() < variants.len();
+                let inner = match non_null.as_slice() {
+                    [] => Ty::Null,
+                    [only] => self.define(only, name)?,
+                    many => self.union(many, name)?,
+                };
+                Ok(if nullable && inner != Ty::Null {
+                    Ty::Optional(Box::new(inner))
+                } else {
+                    inner
+                })
+            }
+            SchemaIr::Array { items, .. } => Ok(Ty::Array(Box::new(
+                self.nested(items, &format!("{name}Item"))?,
+            ))),
+            SchemaIr::Ref { id } => self.reference(id),
+            SchemaIr::String { .. } => Ok(Ty::String),
+            SchemaIr::Int { .. } => Ok(Ty::Int),
+            SchemaIr::Float { .. } => Ok(Ty::Float),
+            SchemaIr::Bool => Ok(Ty::Bool),
+            SchemaIr::Null => Ok(Ty::Null),
+            SchemaIr::Bytes => Ok(Ty::Bytes),
+        }
+    }
+
+    /// Emits a struct; the definition is pushed before its fields so parents precede children.
+    fn object(
+        &mut self,
+        name: &str,
+        properties: &BTreeMap<String, SchemaIr>,
+        required: &[String],
+        additional: &AdditionalProperties,
+        tag: Option<(String, String)>,
+    ) -> Result<(), SchemaRefError> {
+        let index = self.out.items.len();
+        self.out.items.push(Def::Struct {
+            name: name.to_owned(),
+            fields: Vec::new(),
+            extra: None,
+            // Internally tagged variants receive the tag field, so they cannot deny unknowns.
+            deny_unknown: *additional == AdditionalProperties::Forbid && tag.is_none(),
+            tag: None,
+        });
+        let mut lowered = Vec::new();
+        for (property, schema) in properties {
+            if tag.as_ref().is_some_and(|(key, _)| key == property) {
+                continue;
+            }
+            let ty = self.nested(schema, &format!("{name}{}", pascal_case(property)))?;
+            lowered.push(Field {
+                name: property.clone(),
+                ty,
+                required: required.contains(property),
+            });
+        }
+        let extra_ty = match additional {
+            AdditionalProperties::Forbid => None,
+            _ => Some(self.additional(additional, name)?),
+        };
+        if let Some(Def::Struct {
+            fields,
+            extra,
+            tag: slot,
+            ..
+        }) = self.out.items.get_mut(index)
+        {
+            *fields = lowered;
+            *extra = extra_ty;
+            *slot = tag;
+        }
+        Ok(())
+    }
+
+    /// Lowers a child schema, reserving `name` only when it needs a named definition.
+    fn nested(&mut self, schema: &SchemaIr, name: &str) -> Result<Ty, SchemaRefError> {
+        if needs_name(schema) {
+            let name = self.reserve(name);
+            self.define(schema, &name)
+        } else {
+            self.define(schema, name)
+        }
+    }
+
+    fn additional(
+        &mut self,
+        additional: &AdditionalProperties,
+        name: &str,
+    ) -> Result<Ty, SchemaRefError> {
+        match additional {
+            AdditionalProperties::Schema(schema) => self.nested(schema, &format!("{name}Value")),
+            _ => Ok(Ty::Any),
+        }
+    }
+
+    fn reference(&mut self, id: &str) -> Result<Ty, SchemaRefError> {
+        if let Some(name) = self.refs.get(id) {
+            return Ok(Ty::Named(name.clone()));
+        }
+        let target = self
+            .definitions
+            .ok_or_else(|| SchemaRefError::Unresolved(id.to_owned()))?
+            .resolve(id)?;
+        let base = id.rsplit(['/', '#', ':']).next().unwrap_or(id);
+        let name = self.reserve(&pascal_case(base));
+        self.refs.insert(id.to_owned(), name.clone());
+        self.out.boxed.insert(name.clone());
+        let ty = self.define(target, &name)?;
+        if ty != Ty::Named(name.clone()) {
+            self.out.items.push(Def::Alias {
+                name: name.clone(),
+                ty,
+            });
+        }
+        Ok(Ty::Named(name))
+    }
+
+    fn union(&mut self, variants: &[&SchemaIr], name: &str) -> Result<Ty, SchemaRefError> {
+        let definitions = self.definitions;
+        let resolved: Vec<&SchemaIr> = variants
+            .iter()
+            .map(|variant| resolve_shallow(variant, definitions))
+            .collect();
+        if let Some(tag) = discriminator(&resolved) {
+            // Push the union first so it precedes its variant structs.
+            let index = self.out.items.len();
+            self.out.items.push(Def::Union {
+                name: name.to_owned(),
+                tag: Some(tag.clone()),
+                variants: Vec::new(),
+            });
+            let mut lowered = Vec::new();
+            for variant in resolved {
+                let SchemaIr::Object {
+                    properties,
+                    required,
+                    additional,
+                } = variant
+                else {
+                    continue;
+                };
+                let value = tag_value(variant, &tag).unwrap_or_default();
+                let struct_name = self.reserve(&format!("{name}{}", pascal_case(&value)));
+                self.object(
+                    &struct_name,
+                    properties,
+                    required,
+                    additional,
+                    Some((tag.clone(), value.clone())),
+                )?;
+                lowered.push(Variant {
+                    name: value,
+                    ty: Ty::Named(struct_name),
+                });
+            }
+            if let Some(Def::Union { variants, .. }) = self.out.items.get_mut(index) {
+                *variants = lowered;
+            }
+            return Ok(Ty::Named(name.to_owned()));
+        }
+
+        let index = self.out.items.len();
+        self.out.items.push(Def::Union {
+            name: name.to_owned(),
+            tag: None,
+            variants: Vec::new(),
+        });
+        let mut lowered = Vec::new();
+        let mut names = BTreeSet::new();
+        for (position, variant) in variants.iter().enumerate() {
+            let ty = self.nested(variant, &format!("{name}Variant{}", position + 1))?;
+            let base = variant_label(&ty, name);
+            let mut label = base.clone();
+            let mut counter = 2;
+            while !names.insert(label.clone()) {
+                label = format!("{base}{counter}");
+                counter += 1;
+            }
+            lowered.push(Variant { name: label, ty });
+        }
+        if let Some(Def::Union { variants, .. }) = self.out.items.get_mut(index) {
+            *variants = lowered;
+        }
+        Ok(Ty::Named(name.to_owned()))
+    }
+}
+
+/// Follows `Ref`s so tagged unions can be detected through definitions.
+fn resolve_shallow<'s>(
+    schema: &'s SchemaIr,
+    definitions: Option<&'s SchemaDefinitions>,
+) -> &'s SchemaIr {
+    match (schema, definitions) {
+        (SchemaIr::Ref { id }, Some(defs)) => defs.resolve(id).unwrap_or(schema),
+        _ => schema,
+    }
+}
+
+fn needs_name(schema: &SchemaIr) -> bool {
+    match schema {
+        SchemaIr::Object {
+            properties,
+            additional,
+            ..
+        } => !properties.is_empty() || *additional == AdditionalProperties::Forbid,
+        SchemaIr::Enum { values } => {
+            !values.is_empty()
+                && values
+                    .iter()
+                    .all(|value| matches!(value, ciborium::value::Value::Text(_)))
+        }
+        SchemaIr::OneOf { variants } => {
+            variants
+                .iter()
+                .filter(|variant| **variant != SchemaIr::Null)
+                .count()
+                > 1
+                || variants.iter().any(needs_name)
+        }
+        _ => false,
+    }
+}
+
+/// Finds a required property holding one distinct string constant in every object variant.
+fn discriminator(variants: &[&SchemaIr]) -> Option<String> {
+    let SchemaIr::Object { properties, .. } = variants.first()? else {
+        return None;
+    };
+    properties.keys().find_map(|candidate| {
+        let mut seen = BTreeSet::new();
+        variants
+            .iter()
+            .all(|variant| tag_value(variant, candidate).is_some_and(|value| seen.insert(value)))
+            .then(|| candidate.clone())
+    })
+}
+
+fn tag_value(variant: &SchemaIr, property: &str) -> Option<String> {
+    let SchemaIr::Object {
+        properties,
+        required,
+        ..
+    } = variant
+    else {
+        return None;
+    };
+    if !required.iter().any(|name| name == property) {
+        return None;
+    }
+    match properties.get(property)? {
+        SchemaIr::Enum { values } => match values.as_slice() {
+            [ciborium::value::Value::Text(text)] => Some(text.clone()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Variant name for an untagged union member; named members drop the union's own prefix.
+fn variant_label(ty: &Ty, union: &str) -> String {
+    match ty {
+        Ty::String => "String".into(),
+        Ty::Int => "Integer".into(),
+        Ty::Float => "Number".into(),
+        Ty::Bool => "Bool".into(),
+        Ty::Null => "Null".into(),
+        Ty::Bytes => "Bytes".into(),
+        Ty::Any | Ty::Literals(_) => "Value".into(),
+        Ty::Array(_) => "List".into(),
+        Ty::Map(_) => "Map".into(),
+        Ty::Named(name) => name
+            .strip_prefix(union)
+            .filter(|rest| !rest.is_empty())
+            .unwrap_or(name)
+            .to_owned(),
+        Ty::Optional(inner) => variant_label(inner, union),
+    }
+}
+
+/// Converts `api_key`, `api-key` or `apiKey` to `ApiKey`.
+fn pascal_case(raw: &str) -> String {
+    let mut out = String::new();
+    for word in raw.split(|ch: char| !ch.is_ascii_alphanumeric()) {
+        let mut chars = word.chars();
+        if let Some(first) = chars.next() {
+            out.push(first.to_ascii_uppercase());
+            out.extend(chars);
+        }
+    }
+    if out.is_empty() {
+        out.push_str("Value");
+    }
+    if out.starts_with(|ch: char| ch.is_ascii_digit()) {
+        out.insert(0, 'T');
+    }
+    out
+}
+
+/// Converts `apiKey`, `api-key` or `API Key` to a valid Rust field identifier.
+fn snake_case(raw: &str) -> String {
+    let mut out = String::new();
+    let mut previous_lower = false;
+    for ch in raw.chars() {
+        if ch.is_ascii_alphanumeric() {
+            if ch.is_ascii_uppercase() && previous_lower {
+                out.push('_');
+            }
+            previous_lower = ch.is_ascii_lowercase() || ch.is_ascii_digit();
+            out.push(ch.to_ascii_lowercase());
+        } else {
+            if !out.is_empty() && !out.ends_with('_') {
+                out.push('_');
+            }
+            previous_lower = false;
+        }
+    }
+    let mut out = out.trim_end_matches('_').to_owned();
+    if out.is_empty() || out.starts_with(|ch: char| ch.is_ascii_digit()) {
+        out.insert(0, '_');
+    }
+    match out.as_str() {
+        "self" | "super" | "crate" | "Self" => format!("{out}_"),
+        word if RUST_KEYWORDS.contains(&word) => format!("r#{out}"),
+        _ => out,
+    }
+}
+
+/// Suffixes `ident` with `_2`, `_3`, ... until it is not yet in `taken`.
+fn unique_ident(ident: String, taken: &mut BTreeSet<String>) -> String {
+    let base = ident.trim_start_matches("r#").to_owned();
+    let mut candidate = ident;
+    let mut counter = 2;
+    while !taken.insert(candidate.trim_start_matches("r#").to_owned()) {
+        candidate = format!("{base}_{counter}");
+        counter += 1;
+    }
+    candidate
+}
+
+const RUST_KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "do", "dyn",
+    "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl", "in", "let",
+    "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref", "return",
+    "static", "struct", "trait", "true", "try", "type", "typeof", "unsafe", "unsized", "use",
+    "virtual", "where", "while", "yield",
+];
+
+fn ts_key(name: &str) -> String {
+    let mut chars = name.chars();
+    let valid = chars
+        .next()
+        .is_some_and(|ch| ch.is_ascii_alphabetic() || ch == '_' || ch == '$')
+        && chars.all(|ch| ch.is_ascii_alphanumeric() || ch == '_' || ch == '$');
+    if valid {
+        name.to_owned()
+    } else {
+        JsonValue::String(name.to_owned()).to_string()
+    }
+}
+
+fn ts_type(ty: &Ty) -> String {
+    match ty {
+        Ty::String => "string".into(),
+        Ty::Int | Ty::Float => "number".into(),
+        Ty::Bool => "boolean".into(),
+        Ty::Null => "null".into(),
+        Ty::Bytes => "Uint8Array".into(),
+        Ty::Any => "unknown".into(),
+        Ty::Literals(values) => values
+            .iter()
+            .map(JsonValue::to_string)
+            .collect::<Vec<_>>()
+            .join(" | "),
+        Ty::Array(inner) => {
+            let inner = ts_type(inner);
+            if inner.contains(' ') {
+                format!("({inner})[]")
+            } else {
+                format!("{inner}[]")
+            }
+        }
+        Ty::Map(inner) => format!("Record<string, {}>", ts_type(inner)),
+        Ty::Named(name) => name.clone(),
+        Ty::Optional(inner) => format!("{} | null", ts_type(inner)),
+    }
+}
+
+fn render_ts(def: &Def, out: &mut String) {
+    match def {
+        Def::Alias { name, ty } => {
+            let _ = writeln!(out, "export type {name} = {};", ts_type(ty));
+        }
+        Def::Struct {
+            name,
+            fields,
+            extra,
+            tag,
+            ..
+        } => {
+            let _ = writeln!(out, "export interface {name} {{");
+            if let Some((property, value)) = tag {
+                let _ = writeln!(
+                    out,
+                    "  {}: {};",
+                    ts_key(property),
+                    JsonValue::String(value.clone())
+                );
+            }
+            for field in fields {
+                let optional = if field.required { "" } else { "?" };
+                let _ = writeln!(
+                    out,
+                    "  {}{optional}: {};",
+                    ts_key(&field.name),
+                    ts_type(&field.ty)
+                );
+            }
+            if let Some(extra) = extra {
+                // Index signatures must cover declared fields, so mixed objects use `unknown`.
+                let extra = if fields.is_empty() && tag.is_none() {
+                    ts_type(extra)
+                } else {
+                    "unknown".into()
+                };
+                let _ = writeln!(out, "  [key: string]: {extra};");
+            }
+            out.push_str("}\n");
+        }
+        Def::StringEnum { name, values } => {
+            let values: Vec<String> = values
+                .iter()
+                .map(|value| JsonValue::String(value.clone()).to_string())
+                .collect();
+            let _ = writeln!(out, "export type {name} = {};", values.join(" | "));
+        }
+        Def::Union { name, variants, .. } => {
+            let variants: Vec<String> = variants
+                .iter()
+                .map(|variant| ts_type(&variant.ty))
+                .collect();
+            let _ = writeln!(out, "export type {name} = {};", variants.join(" | "));
+        }
+    }
+}
+
+fn rust_type(ty: &Ty, boxed: &BTreeSet<String>, boxing: bool) -> String {
+    match ty {
+        Ty::String => "String".into(),
+        Ty::Int => "i64".into(),
+        Ty::Float => "f64".into(),
+        Ty::Bool => "bool".into(),
+        Ty::Null => "()".into(),
+        Ty::Bytes => RUST_BYTES_TYPE.into(),
+        Ty::Any | Ty::Literals(_) => "serde_json::Value".into(),
+        Ty::Array(inner) => format!("Vec<{}>", rust_type(inner, boxed, false)),
+        Ty::Map(inner) => format!("BTreeMap<String, {}>", rust_type(inner, boxed, false)),
+        Ty::Named(name) if boxing && boxed.contains(name) => format!("Box<{name}>"),
+        Ty::Named(name) => name.clone(),
+        Ty::Optional(inner) => format!("Option<{}>", rust_type(inner, boxed, boxing)),
+    }
+}
+
+fn render_rust(def: &Def, boxed: &BTreeSet<String>, out: &mut String) {
+    match def {
+        Def::Alias { name, ty } => {
+            let _ = writeln!(out, "pub type {name} = {};", rust_type(ty, boxed, false));
+        }
+        Def::Struct {
+            name,
+            fields,
+            extra,
+            deny_unknown,
+            ..
+        } => {
+            out.push_str("#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]\n");
+            if *deny_unknown {
+                out.push_str("#[serde(deny_unknown_fields)]\n");
+            }
+            let _ = writeln!(out, "pub struct {name} {{");
+            let mut taken = BTreeSet::new();
+            for field in fields {
+                let ident = unique_ident(snake_case(&field.name), &mut taken);
+                let mut attrs = Vec::new();
+                if ident.trim_start_matches("r#") != field.name {
+                    attrs.push(format!(
+                        "rename = {}",
+                        JsonValue::String(field.name.clone())
+                    ));
+                }
+                let mut ty = rust_type(&field.ty, boxed, true);
+                if !field.required {
+                    if !matches!(field.ty, Ty::Optional(_)) {
+                        ty = format!("Option<{ty}>");
+                    }
+                    attrs.push("default".into());
+                    attrs.push("skip_serializing_if = \"Option::is_none\"".into());
+                }
+                if !attrs.is_empty() {
+                    let _ = writeln!(out, "    #[serde({})]", attrs.join(", "));
+                }
+                let _ = writeln!(out, "    pub {ident}: {ty},");
+            }
+            if let Some(extra) = extra {
+                out.push_str("    #[serde(flatten)]\n");
+                let _ = writeln!(
+                    out,
+                    "    pub {}: BTreeMap<String, {}>,",
+                    unique_ident("extra".into(), &mut taken),
+                    rust_type(extra, boxed, false)
+                );
+            }
+            out.push_str("}\n");
+        }
+        Def::StringEnum { name, values } => {
+            out.push_str(
+                "#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]\n",
+            );
+            let _ = writeln!(out, "pub enum {name} {{");
+            let mut taken = BTreeSet::new();
+            for value in values {
+                let base = pascal_case(value);
+                let mut variant = base.clone();
+                let mut counter = 2;
+                while !taken.insert(variant.clone()) {
+                    variant = format!("{base}{counter}");
+                    counter += 1;
+                }
+                let _ = writeln!(
+                    out,
+                    "    #[serde(rename = {})]",
+                    JsonValue::String(value.clone())
+                );
+                let _ = writeln!(out, "    {variant},");
+            }
+            out.push_str("}\n");
+        }
+        Def::Union {
+            name,
+            tag,
+            variants,
+        } => {
+            out.push_str("#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]\n");
+            match tag {
+                Some(tag) => {
+                    let _ = writeln!(out, "#[serde(tag = {})]", JsonValue::String(tag.clone()));
+                }
+                None => out.push_str("#[serde(untagged)]\n"),
+            }
+            let _ = writeln!(out, "pub enum {name} {{");
+            for variant in variants {
+                let label = pascal_case(&variant.name);
+                if tag.is_some() {
+                    let _ = writeln!(
+                        out,
+                        "    #[serde(rename = {})]",
+                        JsonValue::String(variant.name.clone())
+                    );
+                }
+                match &variant.ty {
+                    Ty::Null => {
+                        let _ = writeln!(out, "    {label},");
+                    }
+                    ty => {
+                        let _ = writeln!(out, "    {label}({}),", rust_type(ty, boxed, true));
+                    }
+                }
+            }
+            out.push_str("}\n");
+        }
+    }
+}
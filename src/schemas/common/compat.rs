@@ -0,0 +1,914 @@
+//! Backward/forward compatibility checks between two [`SchemaIr`] revisions.
+//!
+//! Compatibility is about data:
+//!
+//! * **backward compatible**: every value valid under the old schema is valid under the new one
+//!   (existing answers and configs keep working);
+//! * **forward compatible**: every value valid under the new schema is valid under the old one
+//!   (old readers accept data produced against the new schema).
+//!
+//! Findings are reported per path. Paths are JSON pointers into the validated value; `*` stands
+//! for any array item or additional property.
+use alloc::{
+    borrow::ToOwned,
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use ciborium::value::Value;
+use semver::Version;
+
+use super::definitions::SchemaDefinitions;
+use super::schema_ir::{AdditionalProperties, SchemaIr};
+use super::validation::pointer_child;
+use crate::schemas::component::v0_6_0::ComponentDescribe;
+use crate::validate::{Diagnostic, Severity};
+
+/// Overall compatibility classification.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Compatibility {
+    /// Backward and forward compatible.
+    Full,
+    /// Old values stay valid; new values may be rejected by old readers.
+    Backward,
+    /// New values are valid for old readers; old values may be rejected.
+    Forward,
+    /// Neither direction holds.
+    Breaking,
+}
+
+/// Direction in which a single change breaks compatibility.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CompatImpact {
+    /// The new schema accepts more values (breaks forward compatibility).
+    Widening,
+    /// The new schema accepts fewer values (breaks backward compatibility).
+    Narrowing,
+    /// The accepted sets are unrelated (breaks both directions).
+    Incompatible,
+}
+
+impl CompatImpact {
+    fn inverted(self) -> Self {
+        match self {
+            Self::Widening => Self::Narrowing,
+            Self::Narrowing => Self::Widening,
+            Self::Incompatible => Self::Incompatible,
+        }
+    }
+}
+
+/// Single schema change with its impact.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CompatFinding {
+    /// Location of the change (JSON pointer into the value; `*` for items/additional properties).
+    pub path: String,
+    /// Stable machine-readable code (for example `COMPAT_REQUIRED_ADDED`).
+    pub code: String,
+    /// Human-readable explanation.
+    pub message: String,
+    /// Compatibility impact.
+    pub impact: CompatImpact,
+}
+
+/// Minimum semver bump a schema change calls for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum VersionBump {
+    /// Fully compatible change.
+    Patch,
+    /// Backward-compatible change (values only widened).
+    Minor,
+    /// Any change that can reject existing values.
+    Major,
+}
+
+/// Result of [`compat`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CompatReport {
+    /// Overall classification.
+    pub compatibility: Compatibility,
+    /// Individual changes in path order.
+    pub findings: Vec<CompatFinding>,
+}
+
+impl CompatReport {
+    fn from_findings(mut findings: Vec<CompatFinding>) -> Self {
+        findings.sort_by(|a, b| a.path.cmp(&b.path).then_with(|| a.code.cmp(&b.code)));
+        findings.dedup();
+        let narrows = findings.iter().any(|f| f.impact != CompatImpact::Widening);
+        let widens = findings.iter().any(|f| f.impact != CompatImpact::Narrowing);
+        let compatibility = match (narrows, widens) {
+            (false, false) => Compatibility::Full,
+            (false, true) => Compatibility::Backward,
+            (true, false) => Compatibility::Forward,
+            (true, true) => Compatibility::Breaking,
+        };
+        Self {
+            compatibility,
+            findings,
+        }
+    }
+
+    /// Returns `true` when old values remain valid.
+    pub fn is_backward_compatible(&self) -> bool {
+        matches!(
+            self.compatibility,
+            Compatibility::Full | Compatibility::Backward
+        )
+    }
+
+    /// Returns `true` when new values are valid for old readers.
+    pub fn is_forward_compatible(&self) -> bool {
+        matches!(
+            self.compatibility,
+            Compatibility::Full | Compatibility::Forward
+        )
+    }
+
+    /// Minimum version bump for the change.
+    pub fn required_bump(&self) -> VersionBump {
+        match self.compatibility {
+            Compatibility::Full => VersionBump::Patch,
+            Compatibility::Backward => VersionBump::Minor,
+            Compatibility::Forward | Compatibility::Breaking => VersionBump::Major,
+        }
+    }
+
+    /// Checks that going from `old` to `new` is a large enough version bump.
+    ///
+    /// Follows semver's `0.x` convention: below `1.0.0` a minor bump counts as major. Every
+    /// finding that the bump does not cover becomes an error diagnostic (`SCHEMA_COMPAT_*`);
+    /// covered findings are reported as info.
+    pub fn check_version_bump(&self, old: &Version, new: &Version) -> Vec<Diagnostic> {
+        let actual = actual_bump(old, new);
+        self.findings
+            .iter()
+            .map(|finding| {
+                let needed = match finding.impact {
+                    CompatImpact::Widening => VersionBump::Minor,
+                    _ => VersionBump::Major,
+                };
+                let covered = actual.is_some_and(|actual| actual >= needed);
+                Diagnostic {
+                    severity: if covered {
+                        Severity::Info
+                    } else {
+                        Severity::Error
+                    },
+                    code: format!("SCHEMA_{}", finding.code),
+                    message: finding.message.clone(),
+                    path: Some(finding.path.clone()),
+                    hint: (!covered).then(|| {
+                        format!(
+                            "Bump the version from {old} with at least a {} change.",
+                            match needed {
+                                VersionBump::Major => "major",
+                                VersionBump::Minor => "minor",
+                                VersionBump::Patch => "patch",
+                            }
+                        )
+                    }),
+                    data: serde_json::Value::Null,
+                }
+            })
+            .collect()
+    }
+}
+
+fn actual_bump(old: &Version, new: &Version) -> Option<VersionBump> {
+    if new <= old {
+        return None;
+    }
+    Some(if new.major != old.major {
+        VersionBump::Major
+    } else if new.minor != old.minor {
+        if old.major == 0 {
+            VersionBump::Major
+        } else {
+            VersionBump::Minor
+        }
+    } else if old.major == 0 && old.minor == 0 {
+        VersionBump::Major
+    } else {
+        VersionBump::Patch
+    })
+}
+
+/// Compares two schema revisions.
+///
+/// `Ref`s are compared by id; use [`compat_with`] to resolve them.
+pub fn compat(old: &SchemaIr, new: &SchemaIr) -> CompatReport {
+    Comparator::new(None, None).run(old, new)
+}
+
+/// Compares two schema revisions, resolving `Ref`s through each side's definitions.
+pub fn compat_with(
+    old: &SchemaIr,
+    old_definitions: &SchemaDefinitions,
+    new: &SchemaIr,
+    new_definitions: &SchemaDefinitions,
+) -> CompatReport {
+    Comparator::new(Some(old_definitions), Some(new_definitions)).run(old, new)
+}
+
+/// Compares the config schema and every operation of two component descriptions.
+///
+/// Inputs and config must stay backward compatible; outputs are compared the other way round, so
+/// a narrowed output is fine while a widened one breaks existing consumers. Paths are prefixed
+/// with `/config`, `/operations/<id>/input` or `/operations/<id>/output`.
+pub fn compat_describe(old: &ComponentDescribe, new: &ComponentDescribe) -> CompatReport {
+    let mut findings = Vec::new();
+    let collect = |findings: &mut Vec<CompatFinding>,
+                   prefix: String,
+                   old: &SchemaIr,
+                   new: &SchemaIr,
+                   invert: bool| {
+        for mut finding in compat(old, new).findings {
+            finding.path = format!("{prefix}{}", finding.path);
+            if invert {
+                finding.impact = finding.impact.inverted();
+            }
+            findings.push(finding);
+        }
+    };
+    collect(
+        &mut findings,
+        "/config".into(),
+        &old.config_schema,
+        &new.config_schema,
+        false,
+    );
+    for old_op in &old.operations {
+        let prefix = pointer_child("/operations", &old_op.id);
+        match new.operations.iter().find(|op| op.id == old_op.id) {
+            Some(new_op) => {
+                collect(
+                    &mut findings,
+                    format!("{prefix}/input"),
+                    &old_op.input.schema,
+                    &new_op.input.schema,
+                    false,
+                );
+                collect(
+                    &mut findings,
+                    format!("{prefix}/output"),
+                    &old_op.output.schema,
+                    &new_op.output.schema,
+                    true,
+                );
+            }
+            None => findings.push(CompatFinding {
+                path: prefix,
+                code: "COMPAT_OPERATION_REMOVED".into(),
+                message: format!("Operation `{}` was removed.", old_op.id),
+                impact: CompatImpact::Narrowing,
+            }),
+        }
+    }
+    for new_op in &new.operations {
+        if !old.operations.iter().any(|op| op.id == new_op.id) {
+            findings.push(CompatFinding {
+                path: pointer_child("/operations", &new_op.id),
+                code: "COMPAT_OPERATION_ADDED".into(),
+                message: format!("Operation `{}` was added.", new_op.id),
+                impact: CompatImpact::Widening,
+            });
+        }
+    }
+    CompatReport::from_findings(findings)
+}
+
+struct Comparator<'a> {
+    old_defs: Option<&'a SchemaDefinitions>,
+    new_defs: Option<&'a SchemaDefinitions>,
+    /// Reference pairs currently being compared; re-entering one assumes compatibility.
+    active: Vec<(String, String)>,
+}
+
+impl<'a> Comparator<'a> {
+    fn new(
+        old_defs: Option<&'a SchemaDefinitions>,
+        new_defs: Option<&'a SchemaDefinitions>,
+    ) -> Self {
+        Self {
+            old_defs,
+            new_defs,
+            active: Vec::new(),
+        }
+    }
+
+    fn run(mut self, old: &SchemaIr, new: &SchemaIr) -> CompatReport {
+        let mut findings = Vec::new();
+        self.compare(old, new, "", &mut findings);
+        CompatReport::from_findings(findings)
+    }
+
+    fn compare(
+        &mut self,
+        old: &SchemaIr,
+        new: &SchemaIr,
+        path: &str,
+        out: &mut Vec<CompatFinding>,
+    ) {
+        // Equal refs may still resolve differently when each side has its own definitions.
+        if old == new && self.old_defs.is_none() && self.new_defs.is_none() {
+            return;
+        }
+        let SchemaIr::Ref { id: old_id } = old else {
+            return self.compare_resolved(old, new, path, out);
+        };
+        let SchemaIr::Ref { id: new_id } = new else {
+            return self.compare_resolved(old, new, path, out);
+        };
+        let pair = (old_id.clone(), new_id.clone());
+        if self.active.contains(&pair) {
+            return;
+        }
+        self.active.push(pair);
+        self.compare_resolved(old, new, path, out);
+        self.active.pop();
+    }
+
+    fn compare_resolved(
+        &mut self,
+        old: &SchemaIr,
+        new: &SchemaIr,
+        path: &str,
+        out: &mut Vec<CompatFinding>,
+    ) {
+        let old = match self.deref(old, self.old_defs, path, out) {
+            Some(schema) => schema,
+            None => return,
+        };
+        let new = match self.deref(new, self.new_defs, path, out) {
+            Some(schema) => schema,
+            None => return,
+        };
+        if let (SchemaIr::Ref { id: old_id }, SchemaIr::Ref { id: new_id }) = (&old, &new) {
+            if old_id != new_id {
+                push(
+                    out,
+                    path,
+                    "COMPAT_REF_CHANGED",
+                    format!("Reference changed from `{old_id}` to `{new_id}`."),
+                    CompatImpact::Incompatible,
+                );
+            }
+            return;
+        }
+
+        match (&old, &new) {
+            (
+                SchemaIr::Object {
+                    properties: old_props,
+                    required: old_required,
+                    additional: old_additional,
+                },
+                SchemaIr::Object {
+                    properties: new_props,
+                    required: new_required,
+                    additional: new_additional,
+                },
+            ) => {
+                for name in new_required {
+                    if !old_required.contains(name) {
+                        push(
+                            out,
+                            &pointer_child(path, name),
+                            "COMPAT_REQUIRED_ADDED",
+                            format!("Field `{name}` is now required."),
+                            CompatImpact::Narrowing,
+                        );
+                    }
+                }
+                for name in old_required {
+                    if !new_required.contains(name) {
+                        push(
+                            out,
+                            &pointer_child(path, name),
+                            "COMPAT_REQUIRED_REMOVED",
+                            format!("Field `{name}` is no longer required."),
+                            CompatImpact::Widening,
+                        );
+                    }
+                }
+                for (name, old_prop) in old_props {
+                    let child = pointer_child(path, name);
+                    match new_props.get(name) {
+                        Some(new_prop) => self.compare(old_prop, new_prop, &child, out),
+                        None => match new_additional {
+                            AdditionalProperties::Forbid => push(
+                                out,
+                                &child,
+                                "COMPAT_PROPERTY_REMOVED",
+                                format!(
+                                    "Field `{name}` was removed and extra fields are forbidden."
+                                ),
+                                CompatImpact::Narrowing,
+                            ),
+                            AdditionalProperties::Allow => push(
+                                out,
+                                &child,
+                                "COMPAT_PROPERTY_REMOVED",
+                                format!("Field `{name}` was removed; any value is now accepted."),
+                                CompatImpact::Widening,
+                            ),
+                            AdditionalProperties::Schema(extra) => {
+                                self.compare(old_prop, extra, &child, out)
+                            }
+                        },
+                    }
+                }
+                for (name, new_prop) in new_props {
+                    if old_props.contains_key(name) {
+                        continue;
+                    }
+                    let child = pointer_child(path, name);
+                    match old_additional {
+                        // Open objects reserve unknown keys for future fields.
+                        AdditionalProperties::Allow => {}
+                        AdditionalProperties::Forbid => push(
+                            out,
+                            &child,
+                            "COMPAT_PROPERTY_ADDED",
+                            format!("Field `{name}` was added; old readers forbid it."),
+                            CompatImpact::Widening,
+                        ),
+                        AdditionalProperties::Schema(extra) => {
+                            self.compare(extra, new_prop, &child, out)
+                        }
+                    }
+                }
+                self.compare_additional(old_additional, new_additional, path, out);
+            }
+            (
+                SchemaIr::Array {
+                    items: old_items,
+                    min_items: old_min,
+                    max_items: old_max,
+                },
+                SchemaIr::Array {
+                    items: new_items,
+                    min_items: new_min,
+                    max_items: new_max,
+                },
+            ) => {
+                bounds(
+                    out,
+                    path,
+                    "item count",
+                    *old_min,
+                    *old_max,
+                    *new_min,
+                    *new_max,
+                );
+                self.compare(old_items, new_items, &pointer_child(path, "*"), out);
+            }
+            (
+                SchemaIr::String {
+                    min_len: old_min,
+                    max_len: old_max,
+                    regex: old_regex,
+                    format: old_format,
+                },
+                SchemaIr::String {
+                    min_len: new_min,
+                    max_len: new_max,
+                    regex: new_regex,
+                    format: new_format,
+                },
+            ) => {
+                bounds(out, path, "length", *old_min, *old_max, *new_min, *new_max);
+                constraint(
+                    out,
+                    path,
+                    "COMPAT_PATTERN_CHANGED",
+                    "pattern",
+                    old_regex,
+                    new_regex,
+                );
+                constraint(
+                    out,
+                    path,
+                    "COMPAT_FORMAT_CHANGED",
+                    "format",
+                    old_format,
+                    new_format,
+                );
+            }
+            (
+                SchemaIr::Int {
+                    min: old_min,
+                    max: old_max,
+                },
+                SchemaIr::Int {
+                    min: new_min,
+                    max: new_max,
+                },
+            ) => {
+                bounds(out, path, "range", *old_min, *old_max, *new_min, *new_max);
+            }
+            (
+                SchemaIr::Float {
+                    min: old_min,
+                    max: old_max,
+                },
+                SchemaIr::Float {
+                    min: new_min,
+                    max: new_max,
+                },
+            ) => {
+                bounds(out, path, "range", *old_min, *old_max, *new_min, *new_max);
+            }
+            (
+                SchemaIr::Int {
+                    min: old_min,
+                    max: old_max,
+                },
+                SchemaIr::Float {
+                    min: new_min,
+                    max: new_max,
+                },
+            ) => {
+                push(
+                    out,
+                    path,
+                    "COMPAT_TYPE_WIDENED",
+                    "Type widened from integer to number.".into(),
+                    CompatImpact::Widening,
+                );
+                let old_min = old_min.map(|v| v as f64);
+                let old_max = old_max.map(|v| v as f64);
+                bounds(out, path, "range", old_min, old_max, *new_min, *new_max);
+            }
+            (
+                SchemaIr::Float {
+                    min: old_min,
+                    max: old_max,
+                },
+                SchemaIr::Int {
+                    min: new_min,
+                    max: new_max,
+                },
+            ) => {
+                push(
+                    out,
+                    path,
+                    "COMPAT_TYPE_NARROWED",
+                    "Type narrowed from number to integer.".into(),
+                    CompatImpact::Narrowing,
+                );
+                let new_min = new_min.map(|v| v as f64);
+                let new_max = new_max.map(|v| v as f64);
+                bounds(out, path, "range", *old_min, *old_max, new_min, new_max);
+            }
+            (SchemaIr::Enum { values: old_values }, SchemaIr::Enum { values: new_values }) => {
+                let removed: Vec<&Value> = old_values
+                    .iter()
+                    .filter(|v| !new_values.contains(v))
+                    .collect();
+                let added: Vec<&Value> = new_values
+                    .iter()
+                    .filter(|v| !old_values.contains(v))
+                    .collect();
+                if !removed.is_empty() {
+                    push(
+                        out,
+                        path,
+                        "COMPAT_ENUM_VALUES_REMOVED",
+                        format!("Enum values removed: {}.", describe_values(&removed)),
+                        CompatImpact::Narrowing,
+                    );
+                }
+                if !added.is_empty() {
+                    push(
+                        out,
+                        path,
+                        "COMPAT_ENUM_VALUES_ADDED",
+                        format!("Enum values added: {}.", describe_values(&added)),
+                        CompatImpact::Widening,
+                    );
+                }
+            }
+            (SchemaIr::Enum { values }, other) => {
+                if values
+                    .iter()
+                    .all(|value| self.accepts(other, value, self.new_defs))
+                {
+                    push(
+                        out,
+                        path,
+                        "COMPAT_TYPE_WIDENED",
+                        "Enum replaced by a schema accepting all previous values.".into(),
+                        CompatImpact::Widening,
+                    );
+                } else {
+                    type_changed(out, path, &old, &new);
+                }
+            }
+            (other, SchemaIr::Enum { values }) => {
+                if values
+                    .iter()
+                    .all(|value| self.accepts(other, value, self.old_defs))
+                {
+                    push(
+                        out,
+                        path,
+                        "COMPAT_TYPE_NARROWED",
+                        "Schema replaced by an enum of previously valid values.".into(),
+                        CompatImpact::Narrowing,
+                    );
+                } else {
+                    type_changed(out, path, &old, &new);
+                }
+            }
+            (SchemaIr::OneOf { .. }, _) | (_, SchemaIr::OneOf { .. }) => {
+                self.compare_variants(&variants_of(&old), &variants_of(&new), path, out);
+            }
+            _ => type_changed(out, path, &old, &new),
+        }
+    }
+
+    fn compare_additional(
+        &mut self,
+        old: &AdditionalProperties,
+        new: &AdditionalProperties,
+        path: &str,
+        out: &mut Vec<CompatFinding>,
+    ) {
+        use AdditionalProperties::{Allow, Forbid, Schema};
+        let impact = match (old, new) {
+            (Allow, Allow) | (Forbid, Forbid) => return,
+            (Schema(old), Schema(new)) => {
+                return self.compare(old, new, &pointer_child(path, "*"), out);
+            }
+            (Allow, _) | (Schema(_), Forbid) => CompatImpact::Narrowing,
+            (_, Allow) | (Forbid, Schema(_)) => CompatImpact::Widening,
+        };
+        let label = |policy: &AdditionalProperties| match policy {
+            Allow => "allow",
+            Forbid => "forbid",
+            Schema(_) => "schema",
+        };
+        push(
+            out,
+            path,
+            match impact {
+                CompatImpact::Narrowing => "COMPAT_ADDITIONAL_NARROWED",
+                _ => "COMPAT_ADDITIONAL_WIDENED",
+            },
+            format!(
+                "Additional properties changed from `{}` to `{}`.",
+                label(old),
+                label(new)
+            ),
+            impact,
+        );
+    }
+
+    fn compare_variants(
+        &mut self,
+        old: &[SchemaIr],
+        new: &[SchemaIr],
+        path: &str,
+        out: &mut Vec<CompatFinding>,
+    ) {
+        for (index, old_variant) in old.iter().enumerate() {
+            let covered = new.iter().any(|new_variant| {
+                self.probe(old_variant, new_variant, path)
+                    .iter()
+                    .all(|f| f.impact == CompatImpact::Widening)
+            });
+            if covered {
+                continue;
+            }
+            match new.iter().find(|v| same_kind(v, old_variant)) {
+                Some(new_variant) => self.compare(old_variant, new_variant, path, out),
+                None => push(
+                    out,
+                    path,
+                    "COMPAT_VARIANT_REMOVED",
+                    format!(
+                        "Variant {index} ({}) is no longer accepted.",
+                        kind(old_variant)
+                    ),
+                    CompatImpact::Narrowing,
+                ),
+            }
+        }
+        for (index, new_variant) in new.iter().enumerate() {
+            let covered = old.iter().any(|old_variant| {
+                self.probe(old_variant, new_variant, path)
+                    .iter()
+                    .all(|f| f.impact == CompatImpact::Narrowing)
+            });
+            if covered {
+                continue;
+            }
+            match old.iter().find(|v| same_kind(v, new_variant)) {
+                Some(old_variant) => self.compare(old_variant, new_variant, path, out),
+                None => push(
+                    out,
+                    path,
+                    "COMPAT_VARIANT_ADDED",
+                    format!("Variant {index} ({}) was added.", kind(new_variant)),
+                    CompatImpact::Widening,
+                ),
+            }
+        }
+    }
+
+    /// Compares without recording findings.
+    fn probe(&mut self, old: &SchemaIr, new: &SchemaIr, path: &str) -> Vec<CompatFinding> {
+        let mut scratch = Vec::new();
+        self.compare(old, new, path, &mut scratch);
+        scratch
+    }
+
+    fn deref(
+        &self,
+        schema: &SchemaIr,
+        defs: Option<&SchemaDefinitions>,
+        path: &str,
+        out: &mut Vec<CompatFinding>,
+    ) -> Option<SchemaIr> {
+        let SchemaIr::Ref { id } = schema else {
+            return Some(schema.clone());
+        };
+        let Some(defs) = defs else {
+            return Some(schema.clone());
+        };
+        match defs.resolve(id) {
+            Ok(resolved) => Some(resolved.clone()),
+            Err(err) => {
+                push(
+                    out,
+                    path,
+                    "COMPAT_UNRESOLVED_REF",
+                    format!("{err}."),
+                    CompatImpact::Incompatible,
+                );
+                None
+            }
+        }
+    }
+
+    fn accepts(&self, schema: &SchemaIr, value: &Value, defs: Option<&SchemaDefinitions>) -> bool {
+        match defs {
+            Some(defs) => schema.validate_with(value, defs).is_ok(),
+            None => schema.validate(value).is_ok(),
+        }
+    }
+}
+
+fn push(
+    out: &mut Vec<CompatFinding>,
+    path: &str,
+    code: &str,
+    message: String,
+    impact: CompatImpact,
+) {
+    out.push(CompatFinding {
+        path: path.to_owned(),
+        code: code.to_owned(),
+        message,
+        impact,
+    });
+}
+
+fn bounds<T: PartialOrd + Copy + core::fmt::Display>(
+    out: &mut Vec<CompatFinding>,
+    path: &str,
+    what: &str,
+    old_min: Option<T>,
+    old_max: Option<T>,
+    new_min: Option<T>,
+    new_max: Option<T>,
+) {
+    let show = |bound: Option<T>| bound.map_or_else(|| "none".to_owned(), |b| b.to_string());
+    // A lower bound narrows when it appears or rises; an upper bound when it appears or drops.
+    let lower = match (old_min, new_min) {
+        (None, Some(_)) => Some(CompatImpact::Narrowing),
+        (Some(_), None) => Some(CompatImpact::Widening),
+        (Some(old), Some(new)) if new > old => Some(CompatImpact::Narrowing),
+        (Some(old), Some(new)) if new < old => Some(CompatImpact::Widening),
+        _ => None,
+    };
+    let upper = match (old_max, new_max) {
+        (None, Some(_)) => Some(CompatImpact::Narrowing),
+        (Some(_), None) => Some(CompatImpact::Widening),
+        (Some(old), Some(new)) if new < old => Some(CompatImpact::Narrowing),
+        (Some(old), Some(new)) if new > old => Some(CompatImpact::Widening),
+        _ => None,
+    };
+    for (impact, bound, old, new) in [
+        (lower, "minimum", old_min, new_min),
+        (upper, "maximum", old_max, new_max),
+    ] {
+        if let Some(impact) = impact {
+            push(
+                out,
+                path,
+                match impact {
+                    CompatImpact::Narrowing => "COMPAT_RANGE_NARROWED",
+                    _ => "COMPAT_RANGE_WIDENED",
+                },
+                format!(
+                    "{} {what} changed from {} to {}.",
+                    capitalize(bound),
+                    show(old),
+                    show(new)
+                ),
+                impact,
+            );
+        }
+    }
+}
+
+fn constraint(
+    out: &mut Vec<CompatFinding>,
+    path: &str,
+    code: &str,
+    what: &str,
+    old: &Option<String>,
+    new: &Option<String>,
+) {
+    let impact = match (old, new) {
+        (None, Some(_)) => CompatImpact::Narrowing,
+        (Some(_), None) => CompatImpact::Widening,
+        (Some(old), Some(new)) if old != new => CompatImpact::Incompatible,
+        _ => return,
+    };
+    let show = |value: &Option<String>| {
+        value
+            .as_deref()
+            .map_or("none".to_owned(), |v| format!("`{v}`"))
+    };
+    push(
+        out,
+        path,
+        code,
+        format!("String {what} changed from {} to {}.", show(old), show(new)),
+        impact,
+    );
+}
+
+fn type_changed(out: &mut Vec<CompatFinding>, path: &str, old: &SchemaIr, new: &SchemaIr) {
+    push(
+        out,
+        path,
+        "COMPAT_TYPE_CHANGED",
+        format!("Type changed from {} to {}.", kind(old), kind(new)),
+        CompatImpact::Incompatible,
+    );
+}
+
+fn variants_of(schema: &SchemaIr) -> Vec<SchemaIr> {
+    match schema {
+        SchemaIr::OneOf { variants } => variants.clone(),
+        other => alloc::vec![other.clone()],
+    }
+}
+
+fn same_kind(a: &SchemaIr, b: &SchemaIr) -> bool {
+    core::mem::discriminant(a) == core::mem::discriminant(b)
+}
+
+fn kind(schema: &SchemaIr) -> &'static str {
+    match schema {
+        SchemaIr::Object { .. } => "object",
+        SchemaIr::Array { .. } => "array",
+        SchemaIr::String { .. } => "string",
+        SchemaIr::Int { .. } => "integer",
+        SchemaIr::Float { .. } => "number",
+        SchemaIr::Bool => "boolean",
+        SchemaIr::Null => "null",
+        SchemaIr::Bytes => "bytes",
+        SchemaIr::Enum { .. } => "enum",
+        SchemaIr::OneOf { .. } => "oneOf",
+        SchemaIr::Ref { .. } => "ref",
+    }
+}
+
+fn describe_values(values: &[&Value]) -> String {
+    values
+        .iter()
+        .map(|value| match value {
+            Value::Text(text) => format!("`{text}`"),
+            Value::Integer(int) => i128::from(*int).to_string(),
+            Value::Bool(flag) => flag.to_string(),
+            Value::Null => "null".to_owned(),
+            other => format!("{other:?}"),
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
@@ -0,0 +1,348 @@
+//! Default application and loose-value coercion driven by [`SchemaIr`].
+//!
+//! CLI flags, environment variables and QA answers arrive as loosely typed values (often strings).
+//! [`DefaultsApplier`] walks a value alongside its schema, fills missing object fields from a
+//! defaults map and coerces scalars into the types the schema expects. Every change is reported,
+//! so the CLI, runner and console build the same config from the same answers and can explain it.
+//! The result is not validated; run [`SchemaIr::validate`] afterwards.
+use alloc::{
+    borrow::ToOwned,
+    collections::BTreeMap,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use ciborium::value::Value;
+
+use super::definitions::SchemaDefinitions;
+use super::json_schema::cbor_to_json;
+use super::schema_ir::{AdditionalProperties, SchemaIr};
+use super::validation::{json_to_cbor, pointer_child, value_kind};
+
+/// How aggressively scalars are converted.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Coercion {
+    /// Values are never converted; only defaults are filled.
+    Strict,
+    /// Strings are parsed into numbers, booleans, nulls and comma-separated arrays; numbers and
+    /// booleans are stringified where the schema wants a string.
+    #[default]
+    Lenient,
+}
+
+/// What happened at a given location.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ValueChangeKind {
+    /// A missing field was filled from the defaults map.
+    DefaultFilled,
+    /// A value was converted to the expected type.
+    Coerced {
+        /// Original value kind (for example `string`).
+        from: String,
+        /// Resulting value kind (for example `integer`).
+        to: String,
+    },
+}
+
+/// Single change made while applying defaults.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ValueChange {
+    /// JSON pointer of the changed value.
+    pub pointer: String,
+    /// What changed.
+    pub kind: ValueChangeKind,
+}
+
+/// Value with defaults applied, plus the list of changes in walk order.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Applied<T> {
+    /// Resulting value.
+    pub value: T,
+    /// Changes made, in the order they were applied.
+    pub changes: Vec<ValueChange>,
+}
+
+/// Fills defaults and coerces values according to a schema.
+#[derive(Clone, Debug)]
+pub struct DefaultsApplier<'a> {
+    schema: &'a SchemaIr,
+    defaults: BTreeMap<String, Value>,
+    definitions: Option<&'a SchemaDefinitions>,
+    coercion: Coercion,
+}
+
+impl<'a> DefaultsApplier<'a> {
+    /// Creates an applier with no defaults and lenient coercion.
+    pub fn new(schema: &'a SchemaIr) -> Self {
+        Self {
+            schema,
+            defaults: BTreeMap::new(),
+            definitions: None,
+            coercion: Coercion::default(),
+        }
+    }
+
+    /// Adds defaults keyed by top-level field name or by JSON pointer (`/db/port`).
+    ///
+    /// This accepts `ComponentOperation::defaults` and the `answer_defaults()` of QA specs
+    /// directly. Later calls override earlier ones for the same location.
+    pub fn with_defaults(mut self, defaults: &BTreeMap<String, Value>) -> Self {
+        for (key, value) in defaults {
+            let pointer = if key.starts_with('/') {
+                key.clone()
+            } else {
+                pointer_child("", key)
+            };
+            self.defaults.insert(pointer, value.clone());
+        }
+        self
+    }
+
+    /// Resolves `Ref` schemas through `definitions`.
+    pub fn with_definitions(mut self, definitions: &'a SchemaDefinitions) -> Self {
+        self.definitions = Some(definitions);
+        self
+    }
+
+    /// Sets the coercion policy.
+    pub fn with_coercion(mut self, coercion: Coercion) -> Self {
+        self.coercion = coercion;
+        self
+    }
+
+    /// Applies defaults and coercions to a CBOR value.
+    pub fn apply(&self, value: &Value) -> Applied<Value> {
+        let mut value = value.clone();
+        let mut changes = Vec::new();
+        self.walk(self.schema, &mut value, "", &mut Vec::new(), &mut changes);
+        Applied { value, changes }
+    }
+
+    /// Applies defaults and coercions to a JSON value.
+    ///
+    /// Defaults that have no JSON form (bytes, tags, non-finite floats) come out as `null`.
+    pub fn apply_json(&self, value: &serde_json::Value) -> Applied<serde_json::Value> {
+        let Applied { value, changes } = self.apply(&json_to_cbor(value));
+        Applied {
+            value: cbor_to_json(&value).unwrap_or(serde_json::Value::Null),
+            changes,
+        }
+    }
+
+    fn walk(
+        &self,
+        schema: &SchemaIr,
+        value: &mut Value,
+        pointer: &str,
+        active: &mut Vec<String>,
+        changes: &mut Vec<ValueChange>,
+    ) {
+        match schema {
+            SchemaIr::Ref { id } => {
+                let Some(target) = self.definitions.and_then(|defs| defs.get(id)) else {
+                    return;
+                };
+                // Refs that loop back without consuming the value would never terminate.
+                if active.contains(id) {
+                    return;
+                }
+                active.push(id.clone());
+                self.walk(target, value, pointer, active, changes);
+                active.pop();
+            }
+            SchemaIr::Object {
+                properties,
+                additional,
+                ..
+            } => {
+                let Value::Map(entries) = value else {
+                    return;
+                };
+                for (key, child) in entries.iter_mut() {
+                    let Value::Text(key) = key else { continue };
+                    let child_schema = match (properties.get(key.as_str()), additional) {
+                        (Some(schema), _) => schema,
+                        (None, AdditionalProperties::Schema(schema)) => schema,
+                        _ => continue,
+                    };
+                    let child_pointer = pointer_child(pointer, key);
+                    self.walk(
+                        child_schema,
+                        child,
+                        &child_pointer,
+                        &mut Vec::new(),
+                        changes,
+                    );
+                }
+                for (name, child_schema) in properties {
+                    let present = entries
+                        .iter()
+                        .any(|(key, _)| matches!(key, Value::Text(key) if key == name));
+                    if present {
+                        continue;
+                    }
+                    let child_pointer = pointer_child(pointer, name);
+                    let mut child = if let Some(default) = self.defaults.get(&child_pointer) {
+                        changes.push(ValueChange {
+                            pointer: child_pointer.clone(),
+                            kind: ValueChangeKind::DefaultFilled,
+                        });
+                        default.clone()
+                    } else if self.has_nested_defaults(&child_pointer) {
+                        Value::Map(Vec::new())
+                    } else {
+                        continue;
+                    };
+                    self.walk(
+                        child_schema,
+                        &mut child,
+                        &child_pointer,
+                        &mut Vec::new(),
+                        changes,
+                    );
+                    entries.push((Value::Text(name.clone()), child));
+                }
+            }
+            SchemaIr::Array { items, .. } => {
+                if self.coercion == Coercion::Lenient
+                    && let Value::Text(text) = value
+                {
+                    let parts = text
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|part| !part.is_empty())
+                        .map(|part| Value::Text(part.to_owned()))
+                        .collect();
+                    self.replace(value, Value::Array(parts), pointer, changes);
+                }
+                let Value::Array(entries) = value else {
+                    return;
+                };
+                for (index, item) in entries.iter_mut().enumerate() {
+                    let item_pointer = pointer_child(pointer, &index.to_string());
+                    self.walk(items, item, &item_pointer, &mut Vec::new(), changes);
+                }
+            }
+            SchemaIr::OneOf { variants } => {
+                // Prefer a variant that already accepts the value, then one that accepts it after
+                // coercion; otherwise leave the value for validation to report.
+                let accepts = |variant: &SchemaIr, value: &Value| match self.definitions {
+                    Some(defs) => variant.validate_with(value, defs).is_ok(),
+                    None => variant.validate(value).is_ok(),
+                };
+                if let Some(variant) = variants.iter().find(|variant| accepts(variant, value)) {
+                    return self.walk(variant, value, pointer, active, changes);
+                }
+                for variant in variants {
+                    let mut trial = value.clone();
+                    let mut trial_changes = Vec::new();
+                    self.walk(variant, &mut trial, pointer, active, &mut trial_changes);
+                    if accepts(variant, &trial) {
+                        *value = trial;
+                        changes.extend(trial_changes);
+                        return;
+                    }
+                }
+            }
+            _ => {
+                if self.coercion == Coercion::Lenient
+                    && let Some(coerced) = coerce_scalar(schema, value)
+                {
+                    self.replace(value, coerced, pointer, changes);
+                }
+            }
+        }
+    }
+
+    fn has_nested_defaults(&self, pointer: &str) -> bool {
+        let prefix = alloc::format!("{pointer}/");
+        self.defaults
+            .range(prefix.clone()..)
+            .next()
+            .is_some_and(|(key, _)| key.starts_with(&prefix))
+    }
+
+    fn replace(
+        &self,
+        value: &mut Value,
+        coerced: Value,
+        pointer: &str,
+        changes: &mut Vec<ValueChange>,
+    ) {
+        changes.push(ValueChange {
+            pointer: pointer.to_owned(),
+            kind: ValueChangeKind::Coerced {
+                from: value_kind(value).into(),
+                to: value_kind(&coerced).into(),
+            },
+        });
+        *value = coerced;
+    }
+}
+
+/// Converts a scalar into the type `schema` expects; `None` when no conversion applies.
+fn coerce_scalar(schema: &SchemaIr, value: &Value) -> Option<Value> {
+    match (schema, value) {
+        (SchemaIr::Int { .. }, Value::Text(text)) => text
+            .trim()
+            .parse::<i64>()
+            .ok()
+            .map(|int| Value::Integer(int.into())),
+        (SchemaIr::Int { .. }, Value::Float(float))
+            if float.fract() == 0.0 && float.abs() < i64::MAX as f64 =>
+        {
+            Some(Value::Integer((*float as i64).into()))
+        }
+        (SchemaIr::Float { .. }, Value::Text(text)) => text
+            .trim()
+            .parse::<f64>()
+            .ok()
+            .filter(|float| float.is_finite())
+            .map(Value::Float),
+        (SchemaIr::Bool, Value::Text(text)) => parse_bool(text).map(Value::Bool),
+        (SchemaIr::Bool, Value::Integer(int)) => match i128::from(*int) {
+            0 => Some(Value::Bool(false)),
+            1 => Some(Value::Bool(true)),
+            _ => None,
+        },
+        (SchemaIr::Null, Value::Text(text)) if text.trim().is_empty() || text.trim() == "null" => {
+            Some(Value::Null)
+        }
+        (SchemaIr::String { .. }, Value::Integer(_) | Value::Float(_) | Value::Bool(_)) => {
+            scalar_text(value).map(Value::Text)
+        }
+        (SchemaIr::Enum { values }, _) if !values.contains(value) => {
+            let text = match value {
+                Value::Text(text) => text.trim().to_owned(),
+                other => scalar_text(other)?,
+            };
+            values
+                .iter()
+                .find(|allowed| match allowed {
+                    Value::Text(allowed) => allowed == &text,
+                    other => scalar_text(other).as_deref() == Some(text.as_str()),
+                })
+                .cloned()
+        }
+        _ => None,
+    }
+}
+
+/// Boolean spellings accepted from text input (config values, QA answers, chat replies).
+pub(crate) fn parse_bool(text: &str) -> Option<bool> {
+    match text.trim().to_ascii_lowercase().as_str() {
+        "true" | "yes" | "y" | "on" | "1" => Some(true),
+        "false" | "no" | "n" | "off" | "0" => Some(false),
+        _ => None,
+    }
+}
+
+fn scalar_text(value: &Value) -> Option<String> {
+    match value {
+        Value::Integer(int) => Some(i128::from(*int).to_string()),
+        Value::Float(float) => Some(float.to_string()),
+        Value::Bool(flag) => Some(flag.to_string()),
+        Value::Null => Some("null".into()),
+        _ => None,
+    }
+}
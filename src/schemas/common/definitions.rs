@@ -0,0 +1,275 @@
+//! Named schema definitions and [`SchemaIr::Ref`] resolution.
+//!
+//! A [`SchemaDefinitions`] registry maps local names or `SchemaId` strings to schemas. References
+//! may be recursive as long as every cycle passes through an object property, array item or
+//! additional-properties schema (that is, it consumes part of the value). Cycles made only of
+//! references and `OneOf` variants can never terminate and are rejected by
+//! [`SchemaDefinitions::check`].
+use alloc::{
+    borrow::ToOwned,
+    boxed::Box,
+    collections::{BTreeMap, BTreeSet},
+    string::String,
+    vec::Vec,
+};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use super::schema_ir::{AdditionalProperties, SchemaIr};
+use crate::SchemaId;
+
+/// Errors raised while resolving schema references.
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+pub enum SchemaRefError {
+    /// No definition exists for the referenced id.
+    #[error("schema reference `{0}` cannot be resolved")]
+    Unresolved(String),
+    /// References loop back without consuming any part of the value.
+    #[error("schema references form a cycle: {}", .0.join(" -> "))]
+    Cycle(Vec<String>),
+    /// A recursive definition cannot be inlined.
+    #[error("schema `{0}` is recursive and cannot be dereferenced")]
+    Recursive(String),
+}
+
+/// Registry of named schemas addressed by [`SchemaIr::Ref`].
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SchemaDefinitions {
+    definitions: BTreeMap<String, SchemaIr>,
+}
+
+impl SchemaDefinitions {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a definition, returning the previous schema registered under `id`.
+    pub fn insert(&mut self, id: impl Into<String>, schema: SchemaIr) -> Option<SchemaIr> {
+        self.definitions.insert(id.into(), schema)
+    }
+
+    /// Builder-style variant of [`SchemaDefinitions::insert`].
+    pub fn with(mut self, id: impl Into<String>, schema: SchemaIr) -> Self {
+        self.insert(id, schema);
+        self
+    }
+
+    /// Adds a definition keyed by its `SchemaId`.
+    pub fn insert_schema_id(&mut self, id: &SchemaId, schema: SchemaIr) -> Option<SchemaIr> {
+        self.insert(id.as_str(), schema)
+    }
+
+    /// Returns the definition registered under `id`, without following references.
+    pub fn get(&self, id: &str) -> Option<&SchemaIr> {
+        self.definitions.get(id)
+    }
+
+    /// Iterates over definitions in id order.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &SchemaIr)> {
+        self.definitions
+            .iter()
+            .map(|(id, schema)| (id.as_str(), schema))
+    }
+
+    /// Number of definitions.
+    pub fn len(&self) -> usize {
+        self.definitions.len()
+    }
+
+    /// Returns `true` when no definitions are registered.
+    pub fn is_empty(&self) -> bool {
+        self.definitions.is_empty()
+    }
+
+    /// Resolves `id` to a concrete (non-`Ref`) schema, following alias chains.
+    pub fn resolve(&self, id: &str) -> Result<&SchemaIr, SchemaRefError> {
+        let mut chain: Vec<String> = Vec::new();
+        let mut current = id;
+        loop {
+            if chain.iter().any(|seen| seen == current) {
+                chain.push(current.to_owned());
+                return Err(SchemaRefError::Cycle(chain));
+            }
+            chain.push(current.to_owned());
+            match self.get(current) {
+                Some(SchemaIr::Ref { id }) => current = id,
+                Some(schema) => return Ok(schema),
+                None => return Err(SchemaRefError::Unresolved(current.to_owned())),
+            }
+        }
+    }
+
+    /// Checks that every reference in the registry resolves and that no cycle is unguarded.
+    pub fn check(&self) -> Result<(), SchemaRefError> {
+        for (id, schema) in &self.definitions {
+            self.check_schema(schema)?;
+            self.unguarded_cycle(id, &mut Vec::new())?;
+        }
+        Ok(())
+    }
+
+    /// Checks that every reference reachable from `schema` resolves without unguarded cycles.
+    pub fn check_schema(&self, schema: &SchemaIr) -> Result<(), SchemaRefError> {
+        let reachable = self.reachable([schema])?;
+        for id in reachable.keys() {
+            self.unguarded_cycle(id, &mut Vec::new())?;
+        }
+        Ok(())
+    }
+
+    /// Collects every definition reachable from `roots`, keyed by id.
+    pub fn reachable<'a>(
+        &'a self,
+        roots: impl IntoIterator<Item = &'a SchemaIr>,
+    ) -> Result<BTreeMap<&'a str, &'a SchemaIr>, SchemaRefError> {
+        let mut found: BTreeMap<&str, &SchemaIr> = BTreeMap::new();
+        let mut pending: Vec<&SchemaIr> = roots.into_iter().collect();
+        while let Some(schema) = pending.pop() {
+            for id in direct_refs(schema) {
+                if found.contains_key(id) {
+                    continue;
+                }
+                let (key, target) = self
+                    .definitions
+                    .get_key_value(id)
+                    .ok_or_else(|| SchemaRefError::Unresolved(id.to_owned()))?;
+                found.insert(key.as_str(), target);
+                pending.push(target);
+            }
+        }
+        Ok(found)
+    }
+
+    /// Inlines every reference in `schema`, failing on recursive definitions.
+    pub fn dereference(&self, schema: &SchemaIr) -> Result<SchemaIr, SchemaRefError> {
+        self.inline(schema, &mut Vec::new())
+    }
+
+    fn inline(
+        &self,
+        schema: &SchemaIr,
+        active: &mut Vec<String>,
+    ) -> Result<SchemaIr, SchemaRefError> {
+        Ok(match schema {
+            SchemaIr::Object {
+                properties,
+                required,
+                additional,
+            } => SchemaIr::Object {
+                properties: properties
+                    .iter()
+                    .map(|(name, property)| Ok((name.clone(), self.inline(property, active)?)))
+                    .collect::<Result<_, SchemaRefError>>()?,
+                required: required.clone(),
+                additional: match additional {
+                    AdditionalProperties::Schema(extra) => {
+                        AdditionalProperties::Schema(Box::new(self.inline(extra, active)?))
+                    }
+                    other => other.clone(),
+                },
+            },
+            SchemaIr::Array {
+                items,
+                min_items,
+                max_items,
+            } => SchemaIr::Array {
+                items: Box::new(self.inline(items, active)?),
+                min_items: *min_items,
+                max_items: *max_items,
+            },
+            SchemaIr::OneOf { variants } => SchemaIr::OneOf {
+                variants: variants
+                    .iter()
+                    .map(|variant| self.inline(variant, active))
+                    .collect::<Result<_, _>>()?,
+            },
+            SchemaIr::Ref { id } => {
+                if active.contains(id) {
+                    return Err(SchemaRefError::Recursive(id.clone()));
+                }
+                let target = self
+                    .get(id)
+                    .ok_or_else(|| SchemaRefError::Unresolved(id.clone()))?;
+                active.push(id.clone());
+                let inlined = self.inline(target, active)?;
+                active.pop();
+                inlined
+            }
+            other => other.clone(),
+        })
+    }
+
+    /// Detects cycles that reach `id` again through references and `OneOf` variants only.
+    fn unguarded_cycle(&self, id: &str, path: &mut Vec<String>) -> Result<(), SchemaRefError> {
+        if path.iter().any(|seen| seen == id) {
+            let mut cycle = path.clone();
+            cycle.push(id.to_owned());
+            return Err(SchemaRefError::Cycle(cycle));
+        }
+        let Some(schema) = self.get(id) else {
+            return Err(SchemaRefError::Unresolved(id.to_owned()));
+        };
+        path.push(id.to_owned());
+        let mut heads = BTreeSet::new();
+        unguarded_refs(schema, &mut heads);
+        for next in heads {
+            self.unguarded_cycle(next, path)?;
+        }
+        path.pop();
+        Ok(())
+    }
+}
+
+impl SchemaIr {
+    /// Returns a copy of the schema with every `Ref` inlined from `definitions`.
+    ///
+    /// Recursive schemas cannot be flattened and yield [`SchemaRefError::Recursive`].
+    pub fn dereference(&self, definitions: &SchemaDefinitions) -> Result<SchemaIr, SchemaRefError> {
+        definitions.dereference(self)
+    }
+}
+
+/// References appearing anywhere in `schema` (not following them).
+fn direct_refs(schema: &SchemaIr) -> Vec<&str> {
+    let mut out = Vec::new();
+    let mut pending = alloc::vec![schema];
+    while let Some(schema) = pending.pop() {
+        match schema {
+            SchemaIr::Object {
+                properties,
+                additional,
+                ..
+            } => {
+                pending.extend(properties.values());
+                if let AdditionalProperties::Schema(extra) = additional {
+                    pending.push(extra);
+                }
+            }
+            SchemaIr::Array { items, .. } => pending.push(items),
+            SchemaIr::OneOf { variants } => pending.extend(variants),
+            SchemaIr::Ref { id } => out.push(id.as_str()),
+            _ => {}
+        }
+    }
+    out
+}
+
+/// References reachable from `schema` without descending into part of the value.
+fn unguarded_refs<'a>(schema: &'a SchemaIr, out: &mut BTreeSet<&'a str>) {
+    match schema {
+        SchemaIr::Ref { id } => {
+            out.insert(id);
+        }
+        SchemaIr::OneOf { variants } => {
+            for variant in variants {
+                unguarded_refs(variant, out);
+            }
+        }
+        _ => {}
+    }
+}
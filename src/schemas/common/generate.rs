@@ -0,0 +1,496 @@
+//! Example values generated from [`SchemaIr`].
+//!
+//! [`SampleGenerator`] produces a minimal instance (only required fields, lower bounds), a maximal
+//! instance (every field, upper bounds) and seeded random instances. Every generated value is
+//! validated against the schema before it is returned, so callers can rely on it being accepted.
+//! Strings with a `regex` are drawn from a small candidate set and fail with
+//! [`GenerateError::Unsatisfiable`] when none matches. With the `proptest` feature,
+//! [`SampleGenerator::strategy`] wraps the random generator as a proptest strategy.
+use alloc::{
+    borrow::ToOwned,
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+
+use ciborium::value::Value;
+use thiserror::Error;
+
+use super::definitions::SchemaDefinitions;
+use super::schema_ir::{AdditionalProperties, SchemaIr};
+use super::validation::pointer_child;
+
+/// Ref expansions after which generation falls back to minimal values.
+const MAX_REF_DEPTH: usize = 4;
+/// Hard limit on ref expansions, even for minimal values.
+const HARD_REF_DEPTH: usize = 32;
+/// Default spread used when a bound is missing.
+const UNBOUNDED_SPREAD: i64 = 1000;
+
+/// Errors raised while generating samples.
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+pub enum GenerateError {
+    /// No value satisfying the schema could be produced.
+    #[error("cannot generate a value at `{pointer}`: {reason}")]
+    Unsatisfiable {
+        /// JSON pointer of the failing location.
+        pointer: String,
+        /// Why generation failed.
+        reason: String,
+    },
+    /// Encoding the sample as canonical CBOR failed.
+    #[error("failed to encode sample: {0}")]
+    Encode(String),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Mode {
+    Minimal,
+    Maximal,
+    Random,
+}
+
+/// Generates example values for a schema.
+#[derive(Clone, Debug)]
+pub struct SampleGenerator<'a> {
+    schema: &'a SchemaIr,
+    definitions: Option<&'a SchemaDefinitions>,
+}
+
+impl<'a> SampleGenerator<'a> {
+    /// Creates a generator for `schema`.
+    pub fn new(schema: &'a SchemaIr) -> Self {
+        Self {
+            schema,
+            definitions: None,
+        }
+    }
+
+    /// Resolves `Ref` schemas through `definitions`.
+    pub fn with_definitions(mut self, definitions: &'a SchemaDefinitions) -> Self {
+        self.definitions = Some(definitions);
+        self
+    }
+
+    /// Smallest valid value: required fields only, lower bounds, first enum value/variant.
+    pub fn minimal(&self) -> Result<Value, GenerateError> {
+        self.generate(Mode::Minimal, 0)
+    }
+
+    /// Largest valid value: every field, upper bounds, last enum value/variant.
+    pub fn maximal(&self) -> Result<Value, GenerateError> {
+        self.generate(Mode::Maximal, 0)
+    }
+
+    /// Random valid value; the same seed always yields the same value.
+    pub fn random(&self, seed: u64) -> Result<Value, GenerateError> {
+        self.generate(Mode::Random, seed)
+    }
+
+    /// Builds minimal and maximal `OpExample`s with canonical CBOR inputs.
+    #[cfg(feature = "std")]
+    pub fn op_examples(&self) -> Result<Vec<crate::OpExample>, GenerateError> {
+        let minimal = self.minimal()?;
+        let maximal = self.maximal()?;
+        let mut examples = Vec::new();
+        for (title, value) in [("Minimal input", &minimal), ("Maximal input", &maximal)] {
+            if title == "Maximal input" && maximal == minimal {
+                continue;
+            }
+            let bytes = crate::cbor::canonical::to_canonical_cbor_allow_floats(value)
+                .map_err(|err| GenerateError::Encode(err.to_string()))?;
+            examples.push(crate::OpExample {
+                title: title.into(),
+                input_cbor: crate::CborBytes::new(bytes),
+                output_cbor: None,
+                notes: Some("Generated from the input schema.".into()),
+            });
+        }
+        Ok(examples)
+    }
+
+    fn generate(&self, mode: Mode, seed: u64) -> Result<Value, GenerateError> {
+        let mut state = Walk {
+            rng: SplitMix64(seed),
+            depth: 0,
+        };
+        let value = self.value(self.schema, mode, "", &mut state)?;
+        self.check(&value)?;
+        Ok(value)
+    }
+
+    fn accepts(&self, schema: &SchemaIr, value: &Value) -> bool {
+        match self.definitions {
+            Some(defs) => schema.validate_with(value, defs).is_ok(),
+            None => schema.validate(value).is_ok(),
+        }
+    }
+
+    fn check(&self, value: &Value) -> Result<(), GenerateError> {
+        let result = match self.definitions {
+            Some(defs) => self.schema.validate_with(value, defs),
+            None => self.schema.validate(value),
+        };
+        match result {
+            Ok(()) => Ok(()),
+            Err(violations) => Err(unsatisfiable(
+                &violations[0].pointer,
+                violations[0].message.clone(),
+            )),
+        }
+    }
+
+    fn value(
+        &self,
+        schema: &SchemaIr,
+        mode: Mode,
+        pointer: &str,
+        state: &mut Walk,
+    ) -> Result<Value, GenerateError> {
+        // Deep recursive schemas degrade to minimal values so generation terminates.
+        let mode = if state.depth > MAX_REF_DEPTH {
+            Mode::Minimal
+        } else {
+            mode
+        };
+        match schema {
+            SchemaIr::Ref { id } => {
+                let target = self
+                    .definitions
+                    .and_then(|defs| defs.get(id))
+                    .ok_or_else(|| {
+                        unsatisfiable(pointer, format!("reference `{id}` cannot be resolved"))
+                    })?;
+                if state.depth >= HARD_REF_DEPTH {
+                    return Err(unsatisfiable(
+                        pointer,
+                        format!("reference `{id}` recurses without a finite instance"),
+                    ));
+                }
+                state.depth += 1;
+                let value = self.value(target, mode, pointer, state);
+                state.depth -= 1;
+                value
+            }
+            SchemaIr::Object {
+                properties,
+                required,
+                additional,
+            } => {
+                let mut entries = Vec::new();
+                for (name, property) in properties {
+                    let include = required.contains(name)
+                        || match mode {
+                            Mode::Minimal => false,
+                            Mode::Maximal => true,
+                            Mode::Random => state.rng.next().is_multiple_of(2),
+                        };
+                    if include {
+                        let child = pointer_child(pointer, name);
+                        entries.push((
+                            Value::Text(name.clone()),
+                            self.value(property, mode, &child, state)?,
+                        ));
+                    }
+                }
+                for name in required {
+                    if properties.contains_key(name) {
+                        continue;
+                    }
+                    let child = pointer_child(pointer, name);
+                    let value = match additional {
+                        AdditionalProperties::Allow => Value::Null,
+                        AdditionalProperties::Schema(extra) => {
+                            self.value(extra, mode, &child, state)?
+                        }
+                        AdditionalProperties::Forbid => {
+                            return Err(unsatisfiable(
+                                &child,
+                                "field is required but not declared while extra fields are forbidden"
+                                    .into(),
+                            ));
+                        }
+                    };
+                    entries.push((Value::Text(name.clone()), value));
+                }
+                Ok(Value::Map(entries))
+            }
+            SchemaIr::Array {
+                items,
+                min_items,
+                max_items,
+            } => {
+                let min = min_items.unwrap_or(0);
+                let len = match mode {
+                    Mode::Minimal => min,
+                    Mode::Maximal => max_items.unwrap_or(min.max(2)),
+                    Mode::Random => state.rng.between_u64(min, max_items.unwrap_or(min + 4)),
+                };
+                if max_items.is_some_and(|max| max < min) {
+                    return Err(unsatisfiable(pointer, "min_items exceeds max_items".into()));
+                }
+                (0..len)
+                    .map(|index| {
+                        let child = pointer_child(pointer, &index.to_string());
+                        self.value(items, mode, &child, state)
+                    })
+                    .collect::<Result<_, _>>()
+                    .map(Value::Array)
+            }
+            SchemaIr::String {
+                min_len,
+                max_len,
+                regex,
+                format,
+            } => self.string(
+                *min_len,
+                *max_len,
+                regex.as_deref(),
+                format.as_deref(),
+                mode,
+                pointer,
+                state,
+            ),
+            SchemaIr::Int { min, max } => {
+                let (low, high) = int_range(*min, *max);
+                if low > high {
+                    return Err(unsatisfiable(pointer, "minimum exceeds maximum".into()));
+                }
+                let value = match mode {
+                    Mode::Minimal => 0.clamp(low, high),
+                    Mode::Maximal => max.unwrap_or(i64::MAX),
+                    Mode::Random => state.rng.between_i64(low, high),
+                };
+                Ok(Value::Integer(value.into()))
+            }
+            SchemaIr::Float { min, max } => {
+                let low = min.unwrap_or(max.map_or(-(UNBOUNDED_SPREAD as f64), |max| {
+                    max - UNBOUNDED_SPREAD as f64
+                }));
+                let high = max.unwrap_or(low + 2.0 * UNBOUNDED_SPREAD as f64);
+                if low > high {
+                    return Err(unsatisfiable(pointer, "minimum exceeds maximum".into()));
+                }
+                let value = match mode {
+                    Mode::Minimal => 0.0f64.clamp(low, high),
+                    Mode::Maximal => max.unwrap_or(f64::MAX),
+                    Mode::Random => low + (high - low) * state.rng.unit(),
+                };
+                Ok(Value::Float(value))
+            }
+            SchemaIr::Bool => Ok(Value::Bool(match mode {
+                Mode::Minimal => false,
+                Mode::Maximal => true,
+                Mode::Random => state.rng.next().is_multiple_of(2),
+            })),
+            SchemaIr::Null => Ok(Value::Null),
+            SchemaIr::Bytes => Ok(Value::Bytes(match mode {
+                Mode::Minimal => Vec::new(),
+                Mode::Maximal => vec![0xff; 16],
+                Mode::Random => {
+                    let len = state.rng.between_u64(0, 16);
+                    (0..len).map(|_| state.rng.next() as u8).collect()
+                }
+            })),
+            SchemaIr::Enum { values } => {
+                if values.is_empty() {
+                    return Err(unsatisfiable(pointer, "enum has no values".into()));
+                }
+                let index = match mode {
+                    Mode::Minimal => 0,
+                    Mode::Maximal => values.len() - 1,
+                    Mode::Random => state.rng.between_u64(0, values.len() as u64 - 1) as usize,
+                };
+                Ok(values[index].clone())
+            }
+            SchemaIr::OneOf { variants } => {
+                let mut order: Vec<usize> = (0..variants.len()).collect();
+                match mode {
+                    Mode::Minimal => {}
+                    Mode::Maximal => order.reverse(),
+                    Mode::Random => {
+                        for i in (1..order.len()).rev() {
+                            let j = state.rng.between_u64(0, i as u64) as usize;
+                            order.swap(i, j);
+                        }
+                    }
+                }
+                // A value must match exactly one variant, so check candidates against the union.
+                for index in order {
+                    if let Ok(value) = self.value(&variants[index], mode, pointer, state)
+                        && self.accepts(schema, &value)
+                    {
+                        return Ok(value);
+                    }
+                }
+                Err(unsatisfiable(
+                    pointer,
+                    "no variant yields a value matching exactly one variant".into(),
+                ))
+            }
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn string(
+        &self,
+        min_len: Option<u64>,
+        max_len: Option<u64>,
+        regex: Option<&str>,
+        format: Option<&str>,
+        mode: Mode,
+        pointer: &str,
+        state: &mut Walk,
+    ) -> Result<Value, GenerateError> {
+        let min = min_len.unwrap_or(0);
+        if max_len.is_some_and(|max| max < min) {
+            return Err(unsatisfiable(pointer, "min_len exceeds max_len".into()));
+        }
+        let len = match mode {
+            Mode::Minimal => min,
+            Mode::Maximal => max_len.unwrap_or(min.max(16)),
+            Mode::Random => state.rng.between_u64(min, max_len.unwrap_or(min + 12)),
+        } as usize;
+        let filler = |ch: char, len: usize| core::iter::repeat_n(ch, len).collect::<String>();
+        let mut candidates: Vec<String> = Vec::new();
+        if let Some(sample) = format.and_then(format_sample) {
+            candidates.push(sample.to_owned());
+        }
+        candidates.push(match mode {
+            Mode::Minimal => filler('a', len),
+            Mode::Maximal => filler('z', len),
+            Mode::Random => (0..len)
+                .map(|_| {
+                    const ALPHABET: &[u8] = b"abcdefghijklmnopqrstuvwxyz0123456789";
+                    ALPHABET[state.rng.between_u64(0, ALPHABET.len() as u64 - 1) as usize] as char
+                })
+                .collect(),
+        });
+        if regex.is_some() {
+            // Patterns usually reject the empty string, so try at least one character.
+            for ch in ['a', 'A', '0', 'x'] {
+                candidates.push(filler(ch, len.max(1)));
+            }
+            candidates.extend(
+                ["example", "a-1", "A1", "a_b", "x.y", "abc123"]
+                    .into_iter()
+                    .map(String::from),
+            );
+        }
+        let schema = SchemaIr::String {
+            min_len,
+            max_len,
+            regex: regex.map(String::from),
+            format: format.map(String::from),
+        };
+        candidates
+            .into_iter()
+            .map(Value::Text)
+            .find(|candidate| schema.validate(candidate).is_ok())
+            .ok_or_else(|| {
+                unsatisfiable(
+                    pointer,
+                    format!(
+                        "no candidate string satisfies pattern {:?} and format {:?}",
+                        regex, format
+                    ),
+                )
+            })
+    }
+
+    /// Proptest strategy producing random valid values.
+    ///
+    /// Seeds whose generation fails are filtered out, so unsatisfiable schemas make the strategy
+    /// reject every case.
+    #[cfg(feature = "proptest")]
+    pub fn strategy(&self) -> proptest::strategy::BoxedStrategy<Value> {
+        use proptest::prelude::*;
+
+        let schema = self.schema.clone();
+        let definitions = self.definitions.cloned();
+        any::<u64>()
+            .prop_filter_map("schema admits no value for this seed", move |seed| {
+                let generator = SampleGenerator::new(&schema);
+                let generator = match &definitions {
+                    Some(defs) => generator.with_definitions(defs),
+                    None => generator,
+                };
+                generator.random(seed).ok()
+            })
+            .boxed()
+    }
+}
+
+struct Walk {
+    rng: SplitMix64,
+    depth: usize,
+}
+
+/// Small deterministic PRNG (SplitMix64); stable across platforms and releases.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    fn between_u64(&mut self, low: u64, high: u64) -> u64 {
+        if high <= low {
+            return low;
+        }
+        let span = high - low;
+        match span.checked_add(1) {
+            Some(size) => low + self.next() % size,
+            None => self.next(),
+        }
+    }
+
+    fn between_i64(&mut self, low: i64, high: i64) -> i64 {
+        let span = high.abs_diff(low);
+        let offset = match span.checked_add(1) {
+            Some(size) => self.next() % size,
+            None => self.next(),
+        };
+        low.wrapping_add_unsigned(offset)
+    }
+
+    fn unit(&mut self) -> f64 {
+        (self.next() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+fn int_range(min: Option<i64>, max: Option<i64>) -> (i64, i64) {
+    match (min, max) {
+        (Some(min), Some(max)) => (min, max),
+        (Some(min), None) => (min, min.saturating_add(2 * UNBOUNDED_SPREAD)),
+        (None, Some(max)) => (max.saturating_sub(2 * UNBOUNDED_SPREAD), max),
+        (None, None) => (-UNBOUNDED_SPREAD, UNBOUNDED_SPREAD),
+    }
+}
+
+fn format_sample(format: &str) -> Option<&'static str> {
+    Some(match format {
+        "email" => "user@example.com",
+        "uri" | "url" => "https://example.com/",
+        "uuid" => "00000000-0000-4000-8000-000000000000",
+        "date" => "2024-01-01",
+        "time" => "12:00:00Z",
+        "date-time" => "2024-01-01T12:00:00Z",
+        "hostname" => "example.com",
+        "ipv4" => "192.0.2.1",
+        "ipv6" => "2001:db8::1",
+        _ => return None,
+    })
+}
+
+fn unsatisfiable(pointer: &str, reason: String) -> GenerateError {
+    GenerateError::Unsatisfiable {
+        pointer: pointer.to_owned(),
+        reason,
+    }
+}
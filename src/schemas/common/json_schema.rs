@@ -0,0 +1,634 @@
+//! Conversion between [`SchemaIr`] and JSON Schema (draft 2020-12).
+//!
+//! Conversions are lossless where both models overlap. Anything that cannot be represented on the
+//! other side is approximated or dropped and recorded as a [`ConversionNote`], so callers can
+//! decide whether the result is good enough (for example when importing third-party tool schemas).
+//! Pure annotations (`title`, `description`, `examples`, `$comment`, `$id`, `$schema`) are dropped
+//! without a note.
+use alloc::{
+    borrow::ToOwned,
+    boxed::Box,
+    collections::BTreeMap,
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+
+use ciborium::value::Value;
+use serde_json::{Map, Number, Value as JsonValue, json};
+
+use super::definitions::{SchemaDefinitions, SchemaRefError};
+use super::schema_ir::{AdditionalProperties, SchemaIr};
+use super::validation::{json_to_cbor, pointer_child};
+
+/// JSON Schema dialect emitted by [`SchemaIr::to_json_schema`].
+pub const JSON_SCHEMA_DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+const ANNOTATIONS: &[&str] = &[
+    "$schema",
+    "$id",
+    "$comment",
+    "title",
+    "description",
+    "examples",
+    "deprecated",
+    "readOnly",
+    "writeOnly",
+];
+
+/// Feature that could not be represented exactly during a conversion.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConversionNote {
+    /// JSON pointer into the source schema.
+    pub pointer: String,
+    /// What was approximated or dropped.
+    pub message: String,
+}
+
+/// Conversion output plus the notes describing lossy steps.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Converted<T> {
+    /// Converted schema.
+    pub schema: T,
+    /// Lossy steps, in source order. Empty when the conversion was exact.
+    pub notes: Vec<ConversionNote>,
+}
+
+impl<T> Converted<T> {
+    /// Returns `true` when nothing was approximated or dropped.
+    pub fn is_lossless(&self) -> bool {
+        self.notes.is_empty()
+    }
+}
+
+impl SchemaIr {
+    /// Converts the schema into a JSON Schema (draft 2020-12) document.
+    ///
+    /// `Ref` schemas become `$ref`s into `$defs`, which is left empty; use
+    /// [`SchemaIr::to_json_schema_with`] to embed the referenced definitions.
+    pub fn to_json_schema(&self) -> Converted<JsonValue> {
+        let mut notes = Vec::new();
+        let mut schema = ir_to_json(self, "", &mut notes);
+        if let JsonValue::Object(map) = &mut schema {
+            map.insert("$schema".into(), JSON_SCHEMA_DIALECT.into());
+        }
+        Converted { schema, notes }
+    }
+
+    /// Converts the schema and every definition it references into a self-contained document.
+    pub fn to_json_schema_with(
+        &self,
+        definitions: &SchemaDefinitions,
+    ) -> Result<Converted<JsonValue>, SchemaRefError> {
+        let reachable = definitions.reachable([self])?;
+        let mut converted = self.to_json_schema();
+        if reachable.is_empty() {
+            return Ok(converted);
+        }
+        let mut defs = Map::new();
+        for (id, schema) in reachable {
+            let pointer = pointer_child("/$defs", id);
+            defs.insert(
+                id.into(),
+                ir_to_json(schema, &pointer, &mut converted.notes),
+            );
+        }
+        if let JsonValue::Object(map) = &mut converted.schema {
+            map.insert("$defs".into(), JsonValue::Object(defs));
+        }
+        Ok(converted)
+    }
+
+    /// Converts a JSON Schema document into `SchemaIr`.
+    ///
+    /// Root `$defs`/`definitions` are dropped with a note; use
+    /// [`SchemaIr::from_json_schema_with_definitions`] to keep them.
+    pub fn from_json_schema(schema: &JsonValue) -> Converted<SchemaIr> {
+        let Converted {
+            schema: (root, definitions),
+            mut notes,
+        } = Self::from_json_schema_with_definitions(schema);
+        if !definitions.is_empty() {
+            note(
+                &mut notes,
+                "/$defs",
+                "definitions were dropped; references to them stay unresolved".into(),
+            );
+        }
+        Converted {
+            schema: root,
+            notes,
+        }
+    }
+
+    /// Converts a JSON Schema document plus its root `$defs` (or legacy `definitions`).
+    pub fn from_json_schema_with_definitions(
+        schema: &JsonValue,
+    ) -> Converted<(SchemaIr, SchemaDefinitions)> {
+        let mut notes = Vec::new();
+        let mut definitions = SchemaDefinitions::new();
+        let root = match schema {
+            JsonValue::Object(map) => {
+                let mut map = map.clone();
+                for keyword in ["$defs", "definitions"] {
+                    let Some(JsonValue::Object(defs)) = map.remove(keyword) else {
+                        continue;
+                    };
+                    let keyword_pointer = pointer_child("", keyword);
+                    for (id, def) in &defs {
+                        let pointer = pointer_child(&keyword_pointer, id);
+                        definitions.insert(id.clone(), json_to_ir(def, &pointer, &mut notes));
+                    }
+                }
+                json_to_ir(&JsonValue::Object(map), "", &mut notes)
+            }
+            other => json_to_ir(other, "", &mut notes),
+        };
+        Converted {
+            schema: (root, definitions),
+            notes,
+        }
+    }
+}
+
+/// Builds the `$ref` target for a `SchemaIr::Ref` id.
+///
+/// Absolute URIs and fragments are kept verbatim; every other id (local names and `SchemaId`s)
+/// points into the document's `$defs`.
+pub(crate) fn ref_to_json(id: &str) -> String {
+    if id.contains("://") || id.starts_with('#') {
+        id.to_owned()
+    } else {
+        format!("#/$defs/{}", id.replace('~', "~0").replace('/', "~1"))
+    }
+}
+
+/// Inverse of [`ref_to_json`].
+pub(crate) fn ref_from_json(reference: &str) -> String {
+    match reference
+        .strip_prefix("#/$defs/")
+        .or_else(|| reference.strip_prefix("#/definitions/"))
+    {
+        Some(token) => token.replace("~1", "/").replace("~0", "~"),
+        None => reference.to_owned(),
+    }
+}
+
+fn note(notes: &mut Vec<ConversionNote>, pointer: &str, message: String) {
+    notes.push(ConversionNote {
+        pointer: pointer.into(),
+        message,
+    });
+}
+
+fn ir_to_json(schema: &SchemaIr, pointer: &str, notes: &mut Vec<ConversionNote>) -> JsonValue {
+    match schema {
+        SchemaIr::Object {
+            properties,
+            required,
+            additional,
+        } => {
+            let mut map = Map::new();
+            map.insert("type".into(), "object".into());
+            let properties_pointer = pointer_child(pointer, "properties");
+            map.insert(
+                "properties".into(),
+                JsonValue::Object(
+                    properties
+                        .iter()
+                        .map(|(name, property)| {
+                            let child = pointer_child(&properties_pointer, name);
+                            (name.clone(), ir_to_json(property, &child, notes))
+                        })
+                        .collect(),
+                ),
+            );
+            if !required.is_empty() {
+                map.insert("required".into(), json!(required));
+            }
+            match additional {
+                AdditionalProperties::Allow => {}
+                AdditionalProperties::Forbid => {
+                    map.insert("additionalProperties".into(), false.into());
+                }
+                AdditionalProperties::Schema(extra) => {
+                    let child = pointer_child(pointer, "additional");
+                    map.insert(
+                        "additionalProperties".into(),
+                        ir_to_json(extra, &child, notes),
+                    );
+                }
+            }
+            JsonValue::Object(map)
+        }
+        SchemaIr::Array {
+            items,
+            min_items,
+            max_items,
+        } => {
+            let mut map = Map::new();
+            map.insert("type".into(), "array".into());
+            map.insert(
+                "items".into(),
+                ir_to_json(items, &pointer_child(pointer, "items"), notes),
+            );
+            insert_opt(&mut map, "minItems", *min_items);
+            insert_opt(&mut map, "maxItems", *max_items);
+            JsonValue::Object(map)
+        }
+        SchemaIr::String {
+            min_len,
+            max_len,
+            regex,
+            format,
+        } => {
+            let mut map = Map::new();
+            map.insert("type".into(), "string".into());
+            insert_opt(&mut map, "minLength", *min_len);
+            insert_opt(&mut map, "maxLength", *max_len);
+            insert_opt(&mut map, "pattern", regex.clone());
+            insert_opt(&mut map, "format", format.clone());
+            JsonValue::Object(map)
+        }
+        SchemaIr::Int { min, max } => {
+            let mut map = Map::new();
+            map.insert("type".into(), "integer".into());
+            insert_opt(&mut map, "minimum", *min);
+            insert_opt(&mut map, "maximum", *max);
+            JsonValue::Object(map)
+        }
+        SchemaIr::Float { min, max } => {
+            let mut map = Map::new();
+            map.insert("type".into(), "number".into());
+            for (key, bound) in [("minimum", min), ("maximum", max)] {
+                if let Some(bound) = bound {
+                    match Number::from_f64(*bound) {
+                        Some(number) => {
+                            map.insert(key.into(), JsonValue::Number(number));
+                        }
+                        None => note(
+                            notes,
+                            pointer,
+                            format!("non-finite {key} {bound} cannot be expressed in JSON"),
+                        ),
+                    }
+                }
+            }
+            JsonValue::Object(map)
+        }
+        SchemaIr::Bool => json!({ "type": "boolean" }),
+        SchemaIr::Null => json!({ "type": "null" }),
+        SchemaIr::Bytes => {
+            note(
+                notes,
+                pointer,
+                "bytes are represented as base64-encoded strings".into(),
+            );
+            json!({ "type": "string", "contentEncoding": "base64" })
+        }
+        SchemaIr::Enum { values } => {
+            let mut converted = Vec::with_capacity(values.len());
+            for (index, value) in values.iter().enumerate() {
+                let child = pointer_child(&pointer_child(pointer, "values"), &index.to_string());
+                match cbor_to_json(value) {
+                    Some(value) => converted.push(value),
+                    None => note(
+                        notes,
+                        &child,
+                        "enum value has no JSON equivalent and was dropped".into(),
+                    ),
+                }
+            }
+            json!({ "enum": converted })
+        }
+        SchemaIr::OneOf { variants } => {
+            let variants_pointer = pointer_child(pointer, "variants");
+            let converted: Vec<JsonValue> = variants
+                .iter()
+                .enumerate()
+                .map(|(index, variant)| {
+                    ir_to_json(
+                        variant,
+                        &pointer_child(&variants_pointer, &index.to_string()),
+                        notes,
+                    )
+                })
+                .collect();
+            json!({ "oneOf": converted })
+        }
+        SchemaIr::Ref { id } => json!({ "$ref": ref_to_json(id) }),
+    }
+}
+
+fn insert_opt<T: Into<JsonValue>>(map: &mut Map<String, JsonValue>, key: &str, value: Option<T>) {
+    if let Some(value) = value {
+        map.insert(key.into(), value.into());
+    }
+}
+
+/// Converts a CBOR value to JSON; `None` for bytes, tags, non-text keys and non-finite floats.
+pub(crate) fn cbor_to_json(value: &Value) -> Option<JsonValue> {
+    Some(match value {
+        Value::Null => JsonValue::Null,
+        Value::Bool(flag) => JsonValue::Bool(*flag),
+        Value::Integer(int) => {
+            let int = i128::from(*int);
+            if let Ok(int) = i64::try_from(int) {
+                int.into()
+            } else {
+                u64::try_from(int).ok()?.into()
+            }
+        }
+        Value::Float(float) => JsonValue::Number(Number::from_f64(*float)?),
+        Value::Text(text) => JsonValue::String(text.clone()),
+        Value::Array(items) => {
+            JsonValue::Array(items.iter().map(cbor_to_json).collect::<Option<_>>()?)
+        }
+        Value::Map(entries) => JsonValue::Object(
+            entries
+                .iter()
+                .map(|(key, value)| match key {
+                    Value::Text(key) => Some((key.clone(), cbor_to_json(value)?)),
+                    _ => None,
+                })
+                .collect::<Option<_>>()?,
+        ),
+        _ => return None,
+    })
+}
+
+fn json_to_ir(schema: &JsonValue, pointer: &str, notes: &mut Vec<ConversionNote>) -> SchemaIr {
+    let map = match schema {
+        JsonValue::Object(map) => map,
+        JsonValue::Bool(true) => return open_schema(pointer, notes),
+        JsonValue::Bool(false) => {
+            note(
+                notes,
+                pointer,
+                "`false` schema has no SchemaIr equivalent; using an empty `oneOf`".into(),
+            );
+            return SchemaIr::OneOf {
+                variants: Vec::new(),
+            };
+        }
+        _ => {
+            note(notes, pointer, "schema must be an object or boolean".into());
+            return open_schema_silent();
+        }
+    };
+
+    let mut consumed: Vec<&str> = ANNOTATIONS.to_vec();
+    let schema = convert_object(map, pointer, notes, &mut consumed);
+    for key in map.keys() {
+        if !consumed.contains(&key.as_str()) {
+            note(
+                notes,
+                &pointer_child(pointer, key),
+                format!("keyword `{key}` is not supported and was dropped"),
+            );
+        }
+    }
+    schema
+}
+
+fn convert_object<'a>(
+    map: &'a Map<String, JsonValue>,
+    pointer: &str,
+    notes: &mut Vec<ConversionNote>,
+    consumed: &mut Vec<&'a str>,
+) -> SchemaIr {
+    if let Some(reference) = map.get("$ref").and_then(JsonValue::as_str) {
+        consumed.push("$ref");
+        return SchemaIr::Ref {
+            id: ref_from_json(reference),
+        };
+    }
+    if let Some(values) = map.get("enum").and_then(JsonValue::as_array) {
+        consumed.extend(["enum", "type"]);
+        return SchemaIr::Enum {
+            values: values.iter().map(json_to_cbor).collect(),
+        };
+    }
+    if let Some(value) = map.get("const") {
+        consumed.extend(["const", "type"]);
+        return SchemaIr::Enum {
+            values: vec![json_to_cbor(value)],
+        };
+    }
+    for keyword in ["oneOf", "anyOf"] {
+        if let Some(variants) = map.get(keyword).and_then(JsonValue::as_array) {
+            consumed.push(keyword);
+            if keyword == "anyOf" {
+                note(
+                    notes,
+                    &pointer_child(pointer, keyword),
+                    "`anyOf` converted to `oneOf`; values matching several variants are rejected"
+                        .into(),
+                );
+            }
+            let keyword_pointer = pointer_child(pointer, keyword);
+            return SchemaIr::OneOf {
+                variants: variants
+                    .iter()
+                    .enumerate()
+                    .map(|(index, variant)| {
+                        json_to_ir(
+                            variant,
+                            &pointer_child(&keyword_pointer, &index.to_string()),
+                            notes,
+                        )
+                    })
+                    .collect(),
+            };
+        }
+    }
+    if let Some(parts) = map.get("allOf").and_then(JsonValue::as_array)
+        && parts.len() == 1
+    {
+        consumed.push("allOf");
+        return json_to_ir(&parts[0], &pointer_child(pointer, "allOf/0"), notes);
+    }
+
+    consumed.push("type");
+    match map.get("type") {
+        Some(JsonValue::String(kind)) => convert_typed(kind, map, pointer, notes, consumed),
+        Some(JsonValue::Array(kinds)) => {
+            let kinds: Vec<&str> = kinds.iter().filter_map(JsonValue::as_str).collect();
+            let variants = kinds
+                .iter()
+                .map(|kind| convert_typed(kind, map, pointer, notes, consumed))
+                .collect();
+            SchemaIr::OneOf { variants }
+        }
+        _ => {
+            // `properties` without `type` is common in hand-written and tool schemas.
+            if map.contains_key("properties") {
+                convert_typed("object", map, pointer, notes, consumed)
+            } else {
+                open_schema(pointer, notes)
+            }
+        }
+    }
+}
+
+fn convert_typed<'a>(
+    kind: &str,
+    map: &'a Map<String, JsonValue>,
+    pointer: &str,
+    notes: &mut Vec<ConversionNote>,
+    consumed: &mut Vec<&'a str>,
+) -> SchemaIr {
+    match kind {
+        "object" => {
+            consumed.extend(["properties", "required", "additionalProperties"]);
+            let properties_pointer = pointer_child(pointer, "properties");
+            let properties: BTreeMap<String, SchemaIr> = map
+                .get("properties")
+                .and_then(JsonValue::as_object)
+                .map(|properties| {
+                    properties
+                        .iter()
+                        .map(|(name, property)| {
+                            let child = pointer_child(&properties_pointer, name);
+                            (name.clone(), json_to_ir(property, &child, notes))
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+            let required = map
+                .get("required")
+                .and_then(JsonValue::as_array)
+                .map(|names| {
+                    names
+                        .iter()
+                        .filter_map(JsonValue::as_str)
+                        .map(String::from)
+                        .collect()
+                })
+                .unwrap_or_default();
+            let additional = match map.get("additionalProperties") {
+                None | Some(JsonValue::Bool(true)) => AdditionalProperties::Allow,
+                Some(JsonValue::Bool(false)) => AdditionalProperties::Forbid,
+                Some(schema) => AdditionalProperties::Schema(Box::new(json_to_ir(
+                    schema,
+                    &pointer_child(pointer, "additionalProperties"),
+                    notes,
+                ))),
+            };
+            SchemaIr::Object {
+                properties,
+                required,
+                additional,
+            }
+        }
+        "array" => {
+            consumed.extend(["items", "minItems", "maxItems"]);
+            let items = match map.get("items") {
+                Some(items) => json_to_ir(items, &pointer_child(pointer, "items"), notes),
+                None => open_schema(&pointer_child(pointer, "items"), notes),
+            };
+            SchemaIr::Array {
+                items: Box::new(items),
+                min_items: map.get("minItems").and_then(JsonValue::as_u64),
+                max_items: map.get("maxItems").and_then(JsonValue::as_u64),
+            }
+        }
+        "string" => {
+            consumed.extend(["minLength", "maxLength", "pattern", "format"]);
+            if map.get("contentEncoding").and_then(JsonValue::as_str) == Some("base64") {
+                consumed.extend(["contentEncoding", "contentMediaType"]);
+                return SchemaIr::Bytes;
+            }
+            SchemaIr::String {
+                min_len: map.get("minLength").and_then(JsonValue::as_u64),
+                max_len: map.get("maxLength").and_then(JsonValue::as_u64),
+                regex: map
+                    .get("pattern")
+                    .and_then(JsonValue::as_str)
+                    .map(String::from),
+                format: map
+                    .get("format")
+                    .and_then(JsonValue::as_str)
+                    .map(String::from),
+            }
+        }
+        "integer" => {
+            consumed.extend(["minimum", "maximum", "exclusiveMinimum", "exclusiveMaximum"]);
+            let bound = |key: &str, exclusive: &str, lower: bool| {
+                let inclusive = map.get(key).and_then(JsonValue::as_f64);
+                let exclusive = map.get(exclusive).and_then(JsonValue::as_f64);
+                let candidates = [
+                    inclusive.map(|value| if lower { value.ceil() } else { value.floor() }),
+                    exclusive.map(|value| {
+                        if lower {
+                            value.floor() + 1.0
+                        } else {
+                            value.ceil() - 1.0
+                        }
+                    }),
+                ];
+                let picked = candidates
+                    .into_iter()
+                    .flatten()
+                    .reduce(|a, b| if lower { a.max(b) } else { a.min(b) });
+                picked.map(|value| value as i64)
+            };
+            SchemaIr::Int {
+                min: bound("minimum", "exclusiveMinimum", true),
+                max: bound("maximum", "exclusiveMaximum", false),
+            }
+        }
+        "number" => {
+            consumed.extend(["minimum", "maximum"]);
+            for keyword in ["exclusiveMinimum", "exclusiveMaximum"] {
+                if map.contains_key(keyword) {
+                    consumed.push(keyword);
+                    note(
+                        notes,
+                        &pointer_child(pointer, keyword),
+                        format!("`{keyword}` treated as inclusive"),
+                    );
+                }
+            }
+            SchemaIr::Float {
+                min: map
+                    .get("minimum")
+                    .or_else(|| map.get("exclusiveMinimum"))
+                    .and_then(JsonValue::as_f64),
+                max: map
+                    .get("maximum")
+                    .or_else(|| map.get("exclusiveMaximum"))
+                    .and_then(JsonValue::as_f64),
+            }
+        }
+        "boolean" => SchemaIr::Bool,
+        "null" => SchemaIr::Null,
+        other => {
+            note(
+                notes,
+                &pointer_child(pointer, "type"),
+                format!("unknown type `{other}`"),
+            );
+            open_schema_silent()
+        }
+    }
+}
+
+/// Schemas without a type constraint: SchemaIr has no "any", so fall back to an open object.
+fn open_schema(pointer: &str, notes: &mut Vec<ConversionNote>) -> SchemaIr {
+    note(
+        notes,
+        pointer,
+        "untyped schema approximated as an open object".into(),
+    );
+    open_schema_silent()
+}
+
+fn open_schema_silent() -> SchemaIr {
+    SchemaIr::Object {
+        properties: BTreeMap::new(),
+        required: Vec::new(),
+        additional: AdditionalProperties::Allow,
+    }
+}
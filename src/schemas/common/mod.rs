@@ -1,2 +1,11 @@
 //! Common schema building blocks.
+pub mod codegen;
+pub mod compat;
+pub mod defaults;
+pub mod definitions;
+pub mod generate;
+pub mod json_schema;
+pub mod normalize;
+pub mod qa_predicate;
 pub mod schema_ir;
+pub mod validation;
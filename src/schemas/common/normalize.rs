@@ -0,0 +1,287 @@
+//! Canonical normalization of [`SchemaIr`].
+//!
+//! Semantically equal schemas can be written in several ways (unsorted `required`, reordered enum
+//! values, `-0.0` bounds, ...), and each spelling encodes to different canonical CBOR. Normalizing
+//! before hashing gives one spelling per meaning, so runtimes can cache by hash.
+use core::cmp::Ordering;
+
+use ciborium::value::Value;
+
+use super::schema_ir::{AdditionalProperties, SchemaIr};
+
+impl SchemaIr {
+    /// Rewrites the schema into its canonical form.
+    ///
+    /// Normalization applies these rules, recursively:
+    ///
+    /// - objects: `required` is sorted and deduplicated; property and additional-properties
+    ///   schemas are normalized,
+    /// - arrays: `min_items: Some(0)` becomes `None`,
+    /// - strings: `min_len: Some(0)` and an empty `regex` become `None`,
+    /// - floats: `-0.0` bounds become `0.0` and `NaN` bounds (which never constrain) become `None`,
+    /// - enums: `-0.0` values become `0.0`; values are sorted (see below) and deduplicated,
+    /// - `OneOf`: variants are normalized and sorted, and a single variant replaces the `OneOf`.
+    ///   Duplicate variants are kept: `OneOf` requires exactly one match, so dropping a duplicate
+    ///   would turn an always-ambiguous variant into an accepted one.
+    ///
+    /// Values sort by CBOR major type (integer, bytes, text, array, map, tag, bool, null, float)
+    /// and then by content. Schemas sort by kind (object, array, string, int, float, bool, null,
+    /// bytes, enum, oneOf, ref) and then field by field. `Ref`s are left untouched. Normalization
+    /// is idempotent.
+    pub fn normalize(&mut self) {
+        match self {
+            SchemaIr::Object {
+                properties,
+                required,
+                additional,
+            } => {
+                for property in properties.values_mut() {
+                    property.normalize();
+                }
+                required.sort();
+                required.dedup();
+                if let AdditionalProperties::Schema(extra) = additional {
+                    extra.normalize();
+                }
+            }
+            SchemaIr::Array {
+                items, min_items, ..
+            } => {
+                items.normalize();
+                if *min_items == Some(0) {
+                    *min_items = None;
+                }
+            }
+            SchemaIr::String { min_len, regex, .. } => {
+                if *min_len == Some(0) {
+                    *min_len = None;
+                }
+                if regex.as_deref() == Some("") {
+                    *regex = None;
+                }
+            }
+            SchemaIr::Float { min, max } => {
+                *min = min.and_then(normalize_bound);
+                *max = max.and_then(normalize_bound);
+            }
+            SchemaIr::Enum { values } => {
+                for value in values.iter_mut() {
+                    if let Value::Float(float) = value
+                        && *float == 0.0
+                    {
+                        *float = 0.0;
+                    }
+                }
+                values.sort_by(cmp_value);
+                values.dedup();
+            }
+            SchemaIr::OneOf { variants } => {
+                for variant in variants.iter_mut() {
+                    variant.normalize();
+                }
+                variants.sort_by(cmp_schema);
+                if variants.len() == 1
+                    && let Some(only) = variants.pop()
+                {
+                    *self = only;
+                }
+            }
+            SchemaIr::Int { .. }
+            | SchemaIr::Bool
+            | SchemaIr::Null
+            | SchemaIr::Bytes
+            | SchemaIr::Ref { .. } => {}
+        }
+    }
+
+    /// Returns a normalized copy of the schema (see [`SchemaIr::normalize`]).
+    pub fn normalized(&self) -> Self {
+        let mut schema = self.clone();
+        schema.normalize();
+        schema
+    }
+}
+
+fn normalize_bound(bound: f64) -> Option<f64> {
+    if bound.is_nan() {
+        None
+    } else if bound == 0.0 {
+        Some(0.0)
+    } else {
+        Some(bound)
+    }
+}
+
+fn schema_rank(schema: &SchemaIr) -> u8 {
+    match schema {
+        SchemaIr::Object { .. } => 0,
+        SchemaIr::Array { .. } => 1,
+        SchemaIr::String { .. } => 2,
+        SchemaIr::Int { .. } => 3,
+        SchemaIr::Float { .. } => 4,
+        SchemaIr::Bool => 5,
+        SchemaIr::Null => 6,
+        SchemaIr::Bytes => 7,
+        SchemaIr::Enum { .. } => 8,
+        SchemaIr::OneOf { .. } => 9,
+        SchemaIr::Ref { .. } => 10,
+    }
+}
+
+fn cmp_schema(a: &SchemaIr, b: &SchemaIr) -> Ordering {
+    schema_rank(a)
+        .cmp(&schema_rank(b))
+        .then_with(|| match (a, b) {
+            (
+                SchemaIr::Object {
+                    properties: a_props,
+                    required: a_required,
+                    additional: a_additional,
+                },
+                SchemaIr::Object {
+                    properties: b_props,
+                    required: b_required,
+                    additional: b_additional,
+                },
+            ) => cmp_seq(
+                a_props.iter(),
+                b_props.iter(),
+                |(a_name, a), (b_name, b)| a_name.cmp(b_name).then_with(|| cmp_schema(a, b)),
+            )
+            .then_with(|| a_required.cmp(b_required))
+            .then_with(|| cmp_additional(a_additional, b_additional)),
+            (
+                SchemaIr::Array {
+                    items: a_items,
+                    min_items: a_min,
+                    max_items: a_max,
+                },
+                SchemaIr::Array {
+                    items: b_items,
+                    min_items: b_min,
+                    max_items: b_max,
+                },
+            ) => cmp_schema(a_items, b_items).then_with(|| (a_min, a_max).cmp(&(b_min, b_max))),
+            (
+                SchemaIr::String {
+                    min_len: a_min,
+                    max_len: a_max,
+                    regex: a_regex,
+                    format: a_format,
+                },
+                SchemaIr::String {
+                    min_len: b_min,
+                    max_len: b_max,
+                    regex: b_regex,
+                    format: b_format,
+                },
+            ) => (a_min, a_max, a_regex, a_format).cmp(&(b_min, b_max, b_regex, b_format)),
+            (
+                SchemaIr::Int {
+                    min: a_min,
+                    max: a_max,
+                },
+                SchemaIr::Int {
+                    min: b_min,
+                    max: b_max,
+                },
+            ) => (a_min, a_max).cmp(&(b_min, b_max)),
+            (
+                SchemaIr::Float {
+                    min: a_min,
+                    max: a_max,
+                },
+                SchemaIr::Float {
+                    min: b_min,
+                    max: b_max,
+                },
+            ) => cmp_float_bound(*a_min, *b_min).then_with(|| cmp_float_bound(*a_max, *b_max)),
+            (SchemaIr::Enum { values: a }, SchemaIr::Enum { values: b }) => {
+                cmp_seq(a.iter(), b.iter(), |a, b| cmp_value(a, b))
+            }
+            (SchemaIr::OneOf { variants: a }, SchemaIr::OneOf { variants: b }) => {
+                cmp_seq(a.iter(), b.iter(), |a, b| cmp_schema(a, b))
+            }
+            (SchemaIr::Ref { id: a }, SchemaIr::Ref { id: b }) => a.cmp(b),
+            _ => Ordering::Equal,
+        })
+}
+
+fn cmp_additional(a: &AdditionalProperties, b: &AdditionalProperties) -> Ordering {
+    let rank = |policy: &AdditionalProperties| match policy {
+        AdditionalProperties::Allow => 0,
+        AdditionalProperties::Forbid => 1,
+        AdditionalProperties::Schema(_) => 2,
+    };
+    rank(a).cmp(&rank(b)).then_with(|| match (a, b) {
+        (AdditionalProperties::Schema(a), AdditionalProperties::Schema(b)) => cmp_schema(a, b),
+        _ => Ordering::Equal,
+    })
+}
+
+fn cmp_float_bound(a: Option<f64>, b: Option<f64>) -> Ordering {
+    match (a, b) {
+        (None, None) => Ordering::Equal,
+        (None, Some(_)) => Ordering::Less,
+        (Some(_), None) => Ordering::Greater,
+        (Some(a), Some(b)) => a.total_cmp(&b),
+    }
+}
+
+fn value_rank(value: &Value) -> u8 {
+    match value {
+        Value::Integer(_) => 0,
+        Value::Bytes(_) => 1,
+        Value::Text(_) => 2,
+        Value::Array(_) => 3,
+        Value::Map(_) => 4,
+        Value::Tag(..) => 5,
+        Value::Bool(_) => 6,
+        Value::Null => 7,
+        Value::Float(_) => 8,
+        _ => 9,
+    }
+}
+
+/// Total order over CBOR values used for enum values.
+pub(crate) fn cmp_value(a: &Value, b: &Value) -> Ordering {
+    value_rank(a)
+        .cmp(&value_rank(b))
+        .then_with(|| match (a, b) {
+            (Value::Integer(a), Value::Integer(b)) => i128::from(*a).cmp(&i128::from(*b)),
+            (Value::Bytes(a), Value::Bytes(b)) => a.cmp(b),
+            (Value::Text(a), Value::Text(b)) => a.cmp(b),
+            (Value::Array(a), Value::Array(b)) => {
+                cmp_seq(a.iter(), b.iter(), |a, b| cmp_value(a, b))
+            }
+            (Value::Map(a), Value::Map(b)) => cmp_seq(a.iter(), b.iter(), |(ak, av), (bk, bv)| {
+                cmp_value(ak, bk).then_with(|| cmp_value(av, bv))
+            }),
+            (Value::Tag(a_tag, a), Value::Tag(b_tag, b)) => {
+                a_tag.cmp(b_tag).then_with(|| cmp_value(a, b))
+            }
+            (Value::Bool(a), Value::Bool(b)) => a.cmp(b),
+            (Value::Float(a), Value::Float(b)) => a.total_cmp(b),
+            _ => Ordering::Equal,
+        })
+}
+
+fn cmp_seq<T>(
+    a: impl IntoIterator<Item = T>,
+    b: impl IntoIterator<Item = T>,
+    mut cmp: impl FnMut(&T, &T) -> Ordering,
+) -> Ordering {
+    let mut a = a.into_iter();
+    let mut b = b.into_iter();
+    loop {
+        match (a.next(), b.next()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) => match cmp(&x, &y) {
+                Ordering::Equal => {}
+                other => return other,
+            },
+        }
+    }
+}
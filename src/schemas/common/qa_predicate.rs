@@ -0,0 +1,133 @@
+//! Conditions over QA answers, used by `visible_if`/`required_if` on questions.
+//!
+//! Predicates reference answers by question id. Ids of nested questions are joined with `/`
+//! (`db/tls`). A reference is looked up in the enclosing scope first (the group or list item the
+//! question belongs to) and then from the root answers, so questions inside a repeated list can
+//! depend on their siblings. Missing and `null` answers count as unanswered.
+use alloc::{boxed::Box, string::String, vec::Vec};
+
+use ciborium::value::Value;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Condition evaluated against previously given answers.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case", tag = "op"))]
+pub enum QaPredicate {
+    /// The question has a non-null answer.
+    Answered {
+        /// Referenced question id.
+        question: String,
+    },
+    /// The answer equals `value`.
+    Equals {
+        /// Referenced question id.
+        question: String,
+        /// Expected answer.
+        value: Value,
+    },
+    /// The answer is missing or differs from `value`.
+    NotEquals {
+        /// Referenced question id.
+        question: String,
+        /// Rejected answer.
+        value: Value,
+    },
+    /// The answer is one of `values`; for multi-select answers, any selected value matches.
+    In {
+        /// Referenced question id.
+        question: String,
+        /// Accepted answers.
+        values: Vec<Value>,
+    },
+    /// Every predicate holds (true when empty).
+    All {
+        /// Combined predicates.
+        predicates: Vec<QaPredicate>,
+    },
+    /// At least one predicate holds (false when empty).
+    Any {
+        /// Combined predicates.
+        predicates: Vec<QaPredicate>,
+    },
+    /// The predicate does not hold.
+    Not {
+        /// Negated predicate.
+        predicate: Box<QaPredicate>,
+    },
+}
+
+impl QaPredicate {
+    /// Evaluates the predicate against a top-level answers map.
+    pub fn evaluate(&self, answers: &Value) -> bool {
+        self.evaluate_in(answers, answers)
+    }
+
+    /// Evaluates the predicate for a question nested in `scope` (a group or list item answer).
+    pub fn evaluate_in(&self, scope: &Value, root: &Value) -> bool {
+        match self {
+            Self::Answered { question } => lookup(question, scope, root).is_some(),
+            Self::Equals { question, value } => lookup(question, scope, root) == Some(value),
+            Self::NotEquals { question, value } => lookup(question, scope, root) != Some(value),
+            Self::In { question, values } => match lookup(question, scope, root) {
+                Some(Value::Array(selected)) => selected.iter().any(|item| values.contains(item)),
+                Some(answer) => values.contains(answer),
+                None => false,
+            },
+            Self::All { predicates } => predicates
+                .iter()
+                .all(|predicate| predicate.evaluate_in(scope, root)),
+            Self::Any { predicates } => predicates
+                .iter()
+                .any(|predicate| predicate.evaluate_in(scope, root)),
+            Self::Not { predicate } => !predicate.evaluate_in(scope, root),
+        }
+    }
+
+    /// Question ids referenced by the predicate, in order of appearance.
+    pub fn questions(&self) -> Vec<&str> {
+        let mut out = Vec::new();
+        self.collect_questions(&mut out);
+        out
+    }
+
+    fn collect_questions<'a>(&'a self, out: &mut Vec<&'a str>) {
+        match self {
+            Self::Answered { question }
+            | Self::Equals { question, .. }
+            | Self::NotEquals { question, .. }
+            | Self::In { question, .. } => {
+                if !out.contains(&question.as_str()) {
+                    out.push(question);
+                }
+            }
+            Self::All { predicates } | Self::Any { predicates } => {
+                for predicate in predicates {
+                    predicate.collect_questions(out);
+                }
+            }
+            Self::Not { predicate } => predicate.collect_questions(out),
+        }
+    }
+}
+
+fn lookup<'a>(question: &str, scope: &'a Value, root: &'a Value) -> Option<&'a Value> {
+    find(question, scope).or_else(|| find(question, root))
+}
+
+/// Looks up a `/`-joined answer path; `null` counts as unanswered.
+pub(crate) fn find<'a>(question: &str, answers: &'a Value) -> Option<&'a Value> {
+    let mut current = answers;
+    for segment in question.split('/') {
+        current = match current {
+            Value::Map(entries) => entries
+                .iter()
+                .find(|(key, _)| matches!(key, Value::Text(key) if key == segment))
+                .map(|(_, value)| value)?,
+            Value::Array(items) => items.get(segment.parse::<usize>().ok()?)?,
+            _ => return None,
+        };
+    }
+    (!current.is_null()).then_some(current)
+}
@@ -6,6 +6,8 @@ use serde::{Deserialize, Serialize};
 
 use ciborium::value::Value;
 
+pub use super::compat::{compat, compat_with};
+
 /// Additional properties policy for objects.
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Clone, Debug, Default, PartialEq)]
@@ -102,7 +104,8 @@ pub enum SchemaIr {
         #[cfg_attr(feature = "serde", serde(default))]
         variants: Vec<SchemaIr>,
     },
-    /// Reference schema (reserved for later).
+    /// Reference to a named definition, resolved through
+    /// [`SchemaDefinitions`](super::definitions::SchemaDefinitions).
     Ref {
         /// Reference identifier.
         id: String,
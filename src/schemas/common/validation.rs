@@ -0,0 +1,545 @@
+//! Value validation against [`SchemaIr`].
+//!
+//! Validation never stops at the first problem: every violation is collected together with the
+//! JSON pointer (RFC 6901) of the offending value, so callers can surface all errors at once.
+#[cfg(feature = "std")]
+use alloc::collections::BTreeMap;
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use ciborium::value::Value;
+
+use super::definitions::SchemaDefinitions;
+use super::schema_ir::{AdditionalProperties, SchemaIr};
+
+/// Single schema violation.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SchemaViolation {
+    /// JSON pointer to the offending value (`""` is the root).
+    pub pointer: String,
+    /// Stable machine-readable code (for example `SCHEMA_REQUIRED`).
+    pub code: String,
+    /// Human-readable description.
+    pub message: String,
+}
+
+impl SchemaViolation {
+    fn new(pointer: &str, code: &str, message: String) -> Self {
+        Self {
+            pointer: pointer.into(),
+            code: code.into(),
+            message,
+        }
+    }
+}
+
+impl SchemaIr {
+    /// Validates a CBOR value, returning every violation found.
+    pub fn validate(&self, value: &Value) -> Result<(), Vec<SchemaViolation>> {
+        Validator::new(false, None).finish(self, value)
+    }
+
+    /// Validates a CBOR value, resolving `Ref` schemas through `definitions`.
+    pub fn validate_with(
+        &self,
+        value: &Value,
+        definitions: &SchemaDefinitions,
+    ) -> Result<(), Vec<SchemaViolation>> {
+        Validator::new(false, Some(definitions)).finish(self, value)
+    }
+
+    /// Validates a JSON value, returning every violation found.
+    ///
+    /// JSON has no byte strings, so `Bytes` schemas accept strings (for example base64 payloads).
+    pub fn validate_json(&self, value: &serde_json::Value) -> Result<(), Vec<SchemaViolation>> {
+        Validator::new(true, None).finish(self, &json_to_cbor(value))
+    }
+
+    /// JSON counterpart of [`SchemaIr::validate_with`].
+    pub fn validate_json_with(
+        &self,
+        value: &serde_json::Value,
+        definitions: &SchemaDefinitions,
+    ) -> Result<(), Vec<SchemaViolation>> {
+        Validator::new(true, Some(definitions)).finish(self, &json_to_cbor(value))
+    }
+}
+
+/// Converts a JSON value into the equivalent CBOR value model.
+pub(crate) fn json_to_cbor(value: &serde_json::Value) -> Value {
+    match value {
+        serde_json::Value::Null => Value::Null,
+        serde_json::Value::Bool(flag) => Value::Bool(*flag),
+        serde_json::Value::Number(number) => {
+            if let Some(int) = number.as_i64() {
+                Value::Integer(int.into())
+            } else if let Some(int) = number.as_u64() {
+                Value::Integer(int.into())
+            } else {
+                Value::Float(number.as_f64().unwrap_or(f64::NAN))
+            }
+        }
+        serde_json::Value::String(text) => Value::Text(text.clone()),
+        serde_json::Value::Array(items) => Value::Array(items.iter().map(json_to_cbor).collect()),
+        serde_json::Value::Object(map) => Value::Map(
+            map.iter()
+                .map(|(key, value)| (Value::Text(key.clone()), json_to_cbor(value)))
+                .collect(),
+        ),
+    }
+}
+
+/// Appends an escaped reference token to a JSON pointer.
+pub(crate) fn pointer_child(pointer: &str, token: &str) -> String {
+    format!("{pointer}/{}", token.replace('~', "~0").replace('/', "~1"))
+}
+
+/// Short type label used in messages.
+pub(crate) fn value_kind(value: &Value) -> &'static str {
+    match value {
+        Value::Integer(_) => "integer",
+        Value::Float(_) => "number",
+        Value::Text(_) => "string",
+        Value::Bytes(_) => "bytes",
+        Value::Bool(_) => "boolean",
+        Value::Null => "null",
+        Value::Array(_) => "array",
+        Value::Map(_) => "object",
+        Value::Tag(..) => "tagged value",
+        _ => "unknown",
+    }
+}
+
+struct Validator<'a> {
+    json: bool,
+    definitions: Option<&'a SchemaDefinitions>,
+    /// References entered at the current value position, to stop unguarded cycles.
+    active: Vec<(String, String)>,
+    /// Compiled string patterns keyed by source, so each is compiled once per validation;
+    /// `None` marks an invalid pattern.
+    #[cfg(feature = "std")]
+    patterns: BTreeMap<String, Option<regex::Regex>>,
+}
+
+impl<'a> Validator<'a> {
+    fn new(json: bool, definitions: Option<&'a SchemaDefinitions>) -> Self {
+        Self {
+            json,
+            definitions,
+            active: Vec::new(),
+            #[cfg(feature = "std")]
+            patterns: BTreeMap::new(),
+        }
+    }
+
+    fn finish(mut self, schema: &SchemaIr, value: &Value) -> Result<(), Vec<SchemaViolation>> {
+        let mut violations = Vec::new();
+        self.check(schema, value, "", &mut violations);
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(violations)
+        }
+    }
+
+    #[cfg(feature = "std")]
+    fn check_pattern(
+        &mut self,
+        pattern: &str,
+        text: &str,
+        pointer: &str,
+        out: &mut Vec<SchemaViolation>,
+    ) {
+        let compiled = self
+            .patterns
+            .entry(pattern.to_string())
+            .or_insert_with(|| regex::Regex::new(pattern).ok());
+        match compiled {
+            Some(regex) if regex.is_match(text) => {}
+            Some(_) => out.push(SchemaViolation::new(
+                pointer,
+                "SCHEMA_PATTERN",
+                format!("Value does not match pattern `{pattern}`."),
+            )),
+            None => out.push(SchemaViolation::new(
+                pointer,
+                "SCHEMA_INVALID_PATTERN",
+                format!("Schema pattern `{pattern}` is not a valid regex."),
+            )),
+        }
+    }
+
+    /// Without `std` there is no regex engine; patterns are not enforced.
+    #[cfg(not(feature = "std"))]
+    fn check_pattern(
+        &mut self,
+        _pattern: &str,
+        _text: &str,
+        _pointer: &str,
+        _out: &mut Vec<SchemaViolation>,
+    ) {
+    }
+
+    fn check(
+        &mut self,
+        schema: &SchemaIr,
+        value: &Value,
+        pointer: &str,
+        out: &mut Vec<SchemaViolation>,
+    ) {
+        match schema {
+            SchemaIr::Object {
+                properties,
+                required,
+                additional,
+            } => {
+                let Value::Map(entries) = value else {
+                    return out.push(mismatch(pointer, "object", value));
+                };
+                let mut present = Vec::with_capacity(entries.len());
+                for (key, child) in entries {
+                    let Value::Text(key) = key else {
+                        out.push(SchemaViolation::new(
+                            pointer,
+                            "SCHEMA_INVALID_KEY",
+                            format!("Object keys must be strings, found {}.", value_kind(key)),
+                        ));
+                        continue;
+                    };
+                    present.push(key.as_str());
+                    let child_pointer = pointer_child(pointer, key);
+                    match (properties.get(key), additional) {
+                        (Some(property), _) => self.check(property, child, &child_pointer, out),
+                        (None, AdditionalProperties::Allow) => {}
+                        (None, AdditionalProperties::Forbid) => out.push(SchemaViolation::new(
+                            &child_pointer,
+                            "SCHEMA_ADDITIONAL_PROPERTY",
+                            format!("Property `{key}` is not allowed."),
+                        )),
+                        (None, AdditionalProperties::Schema(extra)) => {
+                            self.check(extra, child, &child_pointer, out)
+                        }
+                    }
+                }
+                for name in required {
+                    if !present.contains(&name.as_str()) {
+                        out.push(SchemaViolation::new(
+                            &pointer_child(pointer, name),
+                            "SCHEMA_REQUIRED",
+                            format!("Property `{name}` is required."),
+                        ));
+                    }
+                }
+            }
+            SchemaIr::Array {
+                items,
+                min_items,
+                max_items,
+            } => {
+                let Value::Array(values) = value else {
+                    return out.push(mismatch(pointer, "array", value));
+                };
+                let len = values.len() as u64;
+                if let Some(min) = min_items
+                    && len < *min
+                {
+                    out.push(SchemaViolation::new(
+                        pointer,
+                        "SCHEMA_MIN_ITEMS",
+                        format!("Expected at least {min} items, found {len}."),
+                    ));
+                }
+                if let Some(max) = max_items
+                    && len > *max
+                {
+                    out.push(SchemaViolation::new(
+                        pointer,
+                        "SCHEMA_MAX_ITEMS",
+                        format!("Expected at most {max} items, found {len}."),
+                    ));
+                }
+                for (index, item) in values.iter().enumerate() {
+                    self.check(
+                        items,
+                        item,
+                        &pointer_child(pointer, &index.to_string()),
+                        out,
+                    );
+                }
+            }
+            SchemaIr::String {
+                min_len,
+                max_len,
+                regex,
+                format,
+            } => {
+                let Value::Text(text) = value else {
+                    return out.push(mismatch(pointer, "string", value));
+                };
+                let len = text.chars().count() as u64;
+                if let Some(min) = min_len
+                    && len < *min
+                {
+                    out.push(SchemaViolation::new(
+                        pointer,
+                        "SCHEMA_MIN_LENGTH",
+                        format!("Expected at least {min} characters, found {len}."),
+                    ));
+                }
+                if let Some(max) = max_len
+                    && len > *max
+                {
+                    out.push(SchemaViolation::new(
+                        pointer,
+                        "SCHEMA_MAX_LENGTH",
+                        format!("Expected at most {max} characters, found {len}."),
+                    ));
+                }
+                if let Some(pattern) = regex {
+                    self.check_pattern(pattern, text, pointer, out);
+                }
+                if let Some(format) = format
+                    && !format_matches(format, text)
+                {
+                    out.push(SchemaViolation::new(
+                        pointer,
+                        "SCHEMA_FORMAT",
+                        format!("Value is not a valid `{format}`."),
+                    ));
+                }
+            }
+            SchemaIr::Int { min, max } => {
+                let Value::Integer(int) = value else {
+                    return out.push(mismatch(pointer, "integer", value));
+                };
+                let int = i128::from(*int);
+                check_range(int, min.map(i128::from), max.map(i128::from), pointer, out);
+            }
+            SchemaIr::Float { min, max } => {
+                let number = match value {
+                    Value::Float(float) => *float,
+                    Value::Integer(int) => i128::from(*int) as f64,
+                    _ => return out.push(mismatch(pointer, "number", value)),
+                };
+                check_range(number, *min, *max, pointer, out);
+            }
+            SchemaIr::Bool => {
+                if !matches!(value, Value::Bool(_)) {
+                    out.push(mismatch(pointer, "boolean", value));
+                }
+            }
+            SchemaIr::Null => {
+                if !value.is_null() {
+                    out.push(mismatch(pointer, "null", value));
+                }
+            }
+            SchemaIr::Bytes => {
+                let accepted = matches!(value, Value::Bytes(_))
+                    || (self.json && matches!(value, Value::Text(_)));
+                if !accepted {
+                    out.push(mismatch(pointer, "bytes", value));
+                }
+            }
+            SchemaIr::Enum { values } => {
+                if !values.contains(value) {
+                    out.push(SchemaViolation::new(
+                        pointer,
+                        "SCHEMA_ENUM",
+                        "Value is not one of the allowed values.".into(),
+                    ));
+                }
+            }
+            SchemaIr::OneOf { variants } => {
+                let matched = variants
+                    .iter()
+                    .filter(|variant| {
+                        let mut scratch = Vec::new();
+                        self.check(variant, value, pointer, &mut scratch);
+                        scratch.is_empty()
+                    })
+                    .count();
+                match matched {
+                    1 => {}
+                    0 => out.push(SchemaViolation::new(
+                        pointer,
+                        "SCHEMA_ONE_OF_NO_MATCH",
+                        "Value does not match any variant.".into(),
+                    )),
+                    count => out.push(SchemaViolation::new(
+                        pointer,
+                        "SCHEMA_ONE_OF_AMBIGUOUS",
+                        format!("Value matches {count} variants; exactly one is required."),
+                    )),
+                }
+            }
+            SchemaIr::Ref { id } => {
+                let Some(target) = self.definitions.and_then(|defs| defs.get(id)) else {
+                    return out.push(SchemaViolation::new(
+                        pointer,
+                        "SCHEMA_UNRESOLVED_REF",
+                        format!("Schema reference `{id}` cannot be resolved."),
+                    ));
+                };
+                let key = (id.clone(), pointer.to_string());
+                if self.active.contains(&key) {
+                    return out.push(SchemaViolation::new(
+                        pointer,
+                        "SCHEMA_REF_CYCLE",
+                        format!("Schema reference `{id}` loops back without consuming the value."),
+                    ));
+                }
+                self.active.push(key);
+                self.check(target, value, pointer, out);
+                self.active.pop();
+            }
+        }
+    }
+}
+
+fn mismatch(pointer: &str, expected: &str, value: &Value) -> SchemaViolation {
+    SchemaViolation::new(
+        pointer,
+        "SCHEMA_TYPE_MISMATCH",
+        format!("Expected {expected}, found {}.", value_kind(value)),
+    )
+}
+
+fn check_range<T: PartialOrd + core::fmt::Display>(
+    value: T,
+    min: Option<T>,
+    max: Option<T>,
+    pointer: &str,
+    out: &mut Vec<SchemaViolation>,
+) {
+    if let Some(min) = min
+        && value < min
+    {
+        out.push(SchemaViolation::new(
+            pointer,
+            "SCHEMA_MINIMUM",
+            format!("Value {value} is below the minimum {min}."),
+        ));
+    }
+    if let Some(max) = max
+        && value > max
+    {
+        out.push(SchemaViolation::new(
+            pointer,
+            "SCHEMA_MAXIMUM",
+            format!("Value {value} is above the maximum {max}."),
+        ));
+    }
+}
+
+/// Checks well-known `format` labels; unknown labels (for example `secret`) are annotations only.
+fn format_matches(format: &str, text: &str) -> bool {
+    match format {
+        "email" => text.split_once('@').is_some_and(|(local, domain)| {
+            !local.is_empty() && !domain.contains('@') && is_hostname(domain)
+        }),
+        "uri" | "url" => text.split_once(':').is_some_and(|(scheme, rest)| {
+            !rest.is_empty()
+                && scheme.starts_with(|ch: char| ch.is_ascii_alphabetic())
+                && scheme
+                    .chars()
+                    .all(|ch| ch.is_ascii_alphanumeric() || matches!(ch, '+' | '-' | '.'))
+        }),
+        "uuid" => {
+            let groups: Vec<&str> = text.split('-').collect();
+            groups.iter().map(|group| group.len()).eq([8, 4, 4, 4, 12])
+                && groups
+                    .iter()
+                    .all(|group| group.chars().all(|ch| ch.is_ascii_hexdigit()))
+        }
+        "date" => is_date(text),
+        "time" => is_time(text),
+        "date-time" => text
+            .split_once(['T', 't'])
+            .is_some_and(|(date, time)| is_date(date) && is_time(time)),
+        "hostname" => is_hostname(text),
+        "ipv4" => text.parse::<core::net::Ipv4Addr>().is_ok(),
+        "ipv6" => text.parse::<core::net::Ipv6Addr>().is_ok(),
+        _ => true,
+    }
+}
+
+fn digits(text: &str, len: usize) -> Option<u32> {
+    (text.len() == len && text.chars().all(|ch| ch.is_ascii_digit()))
+        .then(|| text.parse().ok())
+        .flatten()
+}
+
+fn is_date(text: &str) -> bool {
+    let mut parts = text.splitn(3, '-');
+    let (Some(_year), Some(month), Some(day)) = (
+        parts.next().and_then(|part| digits(part, 4)),
+        parts.next().and_then(|part| digits(part, 2)),
+        parts.next().and_then(|part| digits(part, 2)),
+    ) else {
+        return false;
+    };
+    (1..=12).contains(&month) && (1..=31).contains(&day)
+}
+
+/// `HH:MM:SS[.frac](Z|±HH:MM)` as in RFC 3339.
+fn is_time(text: &str) -> bool {
+    let (clock, offset) = if let Some(clock) = text.strip_suffix(['Z', 'z']) {
+        (clock, None)
+    } else if let Some(index) = text.rfind(['+', '-']) {
+        (&text[..index], Some(&text[index + 1..]))
+    } else {
+        return false;
+    };
+    let clock = clock.split_once('.').map_or(clock, |(whole, frac)| {
+        if !frac.is_empty() && frac.chars().all(|ch| ch.is_ascii_digit()) {
+            whole
+        } else {
+            ""
+        }
+    });
+    let offset_ok = offset.is_none_or(|offset| bounded_fields(offset, &[23, 59]));
+    bounded_fields(clock, &[23, 59, 60]) && offset_ok
+}
+
+/// `HH:MM[:SS[.frac]]` without an offset, as answered by time-of-day questions.
+pub(crate) fn is_partial_time(text: &str) -> bool {
+    let clock = match text.split_once('.') {
+        Some((whole, frac))
+            if whole.len() == 8
+                && !frac.is_empty()
+                && frac.chars().all(|ch| ch.is_ascii_digit()) =>
+        {
+            whole
+        }
+        Some(_) => return false,
+        None => text,
+    };
+    bounded_fields(clock, &[23, 59]) || bounded_fields(clock, &[23, 59, 60])
+}
+
+/// Checks `NN:NN[:NN]` fields against per-field upper bounds.
+fn bounded_fields(text: &str, bounds: &[u32]) -> bool {
+    let parts: Vec<&str> = text.split(':').collect();
+    parts.len() == bounds.len()
+        && parts
+            .iter()
+            .zip(bounds)
+            .all(|(part, max)| digits(part, 2).is_some_and(|value| value <= *max))
+}
+
+fn is_hostname(text: &str) -> bool {
+    !text.is_empty()
+        && text.len() <= 253
+        && text.split('.').all(|label| {
+            !label.is_empty()
+                && label.len() <= 63
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label
+                    .chars()
+                    .all(|ch| ch.is_ascii_alphanumeric() || ch == '-')
+        })
+}
@@ -7,6 +7,8 @@ use serde::{Deserialize, Serialize};
 use ciborium::value::Value;
 
 use crate::i18n_text::I18nText;
+#[cfg(all(feature = "std", feature = "serde"))]
+use crate::schemas::common::definitions::{SchemaDefinitions, SchemaRefError};
 use crate::schemas::common::schema_ir::SchemaIr;
 
 #[cfg(all(feature = "std", feature = "serde"))]
@@ -116,24 +118,77 @@ pub fn schema_hash(
     input: &SchemaIr,
     output: &SchemaIr,
     config: &SchemaIr,
+) -> canonical::Result<String> {
+    hash_material(input, output, config, BTreeMap::new())
+}
+
+/// Compute the schema hash over normalized schemas (see [`SchemaIr::normalize`]).
+///
+/// Semantically equal schemas yield the same hash, so runtimes can use it as a cache key. Hash
+/// values for a given normalized schema are locked by fixtures and stay stable across releases.
+#[cfg(all(feature = "std", feature = "serde"))]
+pub fn schema_hash_normalized(
+    input: &SchemaIr,
+    output: &SchemaIr,
+    config: &SchemaIr,
+) -> canonical::Result<String> {
+    schema_hash(
+        &input.normalized(),
+        &output.normalized(),
+        &config.normalized(),
+    )
+}
+
+/// Compute the schema hash, folding in every definition the schemas reference.
+///
+/// Changing a referenced definition changes the hash. Schemas without references hash exactly as
+/// with [`schema_hash`].
+#[cfg(all(feature = "std", feature = "serde"))]
+pub fn schema_hash_with(
+    input: &SchemaIr,
+    output: &SchemaIr,
+    config: &SchemaIr,
+    definitions: &SchemaDefinitions,
+) -> Result<String, SchemaHashError> {
+    let reachable = definitions.reachable([input, output, config])?;
+    Ok(hash_material(input, output, config, reachable)?)
+}
+
+/// Errors raised by [`schema_hash_with`].
+#[cfg(all(feature = "std", feature = "serde"))]
+#[derive(Debug, thiserror::Error)]
+pub enum SchemaHashError {
+    /// A reference could not be resolved.
+    #[error(transparent)]
+    Ref(#[from] SchemaRefError),
+    /// Canonical encoding failed.
+    #[error(transparent)]
+    Canonical(#[from] canonical::CanonicalError),
+}
+
+#[cfg(all(feature = "std", feature = "serde"))]
+fn hash_material(
+    input: &SchemaIr,
+    output: &SchemaIr,
+    config: &SchemaIr,
+    definitions: BTreeMap<&str, &SchemaIr>,
 ) -> canonical::Result<String> {
     #[derive(Serialize)]
     struct HashMaterial<'a> {
         input: &'a SchemaIr,
         output: &'a SchemaIr,
         config: &'a SchemaIr,
+        #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+        definitions: BTreeMap<&'a str, &'a SchemaIr>,
     }
 
     let material = HashMaterial {
         input,
         output,
         config,
+        definitions,
     };
     let bytes = canonical::to_canonical_cbor_allow_floats(&material)?;
     let digest = Sha256::digest(bytes.as_slice());
-    let mut hex = String::with_capacity(digest.len() * 2);
-    for byte in digest {
-        hex.push_str(&format!("{byte:02x}"));
-    }
-    Ok(hex)
+    Ok(crate::hex_encode(&digest))
 }
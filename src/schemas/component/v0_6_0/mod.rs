@@ -3,10 +3,11 @@ pub mod describe;
 pub mod qa;
 
 pub use crate::i18n_text::I18nText;
-#[cfg(all(feature = "std", feature = "serde"))]
-pub use describe::schema_hash;
+pub use crate::schemas::common::qa_predicate::QaPredicate;
 pub use describe::{
     ComponentDescribe, ComponentInfo, ComponentOperation, ComponentRunInput, ComponentRunOutput,
     RedactionKind, RedactionRule,
 };
+#[cfg(all(feature = "std", feature = "serde"))]
+pub use describe::{SchemaHashError, schema_hash, schema_hash_normalized, schema_hash_with};
 pub use qa::{ChoiceOption, ComponentQaSpec, QaMode, Question, QuestionKind};
@@ -4,6 +4,7 @@ pub mod qa;
 pub mod validation;
 
 pub use crate::i18n_text::I18nText;
+pub use crate::schemas::common::qa_predicate::QaPredicate;
 pub use describe::{CapabilityDescriptor, CapabilityMetadata, PackDescribe, PackInfo};
 pub use qa::{ChoiceOption, PackQaSpec, QaMode, Question, QuestionKind};
 pub use validation::{Diagnostic, PackValidationResult};
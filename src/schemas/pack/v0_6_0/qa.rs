@@ -8,6 +8,8 @@ use serde::{Deserialize, Serialize};
 use ciborium::value::Value;
 
 use crate::i18n_text::I18nText;
+use crate::schemas::common::qa_predicate::QaPredicate;
+use crate::secrets::SecretRequirement;
 
 /// QA mode.
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -86,6 +88,29 @@ impl PackQaSpec {
         }
         keys
     }
+
+    /// Effective default answers: spec-level `defaults` overridden by per-question defaults.
+    ///
+    /// This is the precedence `validate_answers` uses; feed it to `DefaultsApplier` to build
+    /// config from partial answers.
+    pub fn answer_defaults(&self) -> BTreeMap<String, Value> {
+        let mut defaults = self.defaults.clone();
+        for question in &self.questions {
+            if let Some(default) = &question.default {
+                defaults.insert(question.id.clone(), default.clone());
+            }
+        }
+        defaults
+    }
+
+    /// Secret requirements declared by `Secret` questions, including nested ones.
+    pub fn secret_requirements(&self) -> Vec<&SecretRequirement> {
+        let mut out = Vec::new();
+        for question in &self.questions {
+            question.collect_secret_requirements(&mut out);
+        }
+        out
+    }
 }
 
 /// Question entry.
@@ -106,9 +131,53 @@ pub struct Question {
     pub required: bool,
     /// Optional default value.
     pub default: Option<Value>,
+    /// Ask the question only when this predicate holds over prior answers.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub visible_if: Option<QaPredicate>,
+    /// Require an answer when this predicate holds, in addition to `required`.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub required_if: Option<QaPredicate>,
 }
 
 impl Question {
+    /// Whether the question should be asked, given the answers in `scope` and `root`.
+    ///
+    /// For top-level questions both are the answers map; for nested questions `scope` is the
+    /// enclosing group or list item answer.
+    pub fn is_visible(&self, scope: &Value, root: &Value) -> bool {
+        self.visible_if
+            .as_ref()
+            .is_none_or(|predicate| predicate.evaluate_in(scope, root))
+    }
+
+    /// Whether an answer is required; hidden questions are never required.
+    pub fn is_required(&self, scope: &Value, root: &Value) -> bool {
+        self.is_visible(scope, root)
+            && (self.required
+                || self
+                    .required_if
+                    .as_ref()
+                    .is_some_and(|predicate| predicate.evaluate_in(scope, root)))
+    }
+
+    fn collect_secret_requirements<'a>(&'a self, out: &mut Vec<&'a SecretRequirement>) {
+        match &self.kind {
+            QuestionKind::Secret { requirement } => out.push(requirement),
+            QuestionKind::Group { questions } | QuestionKind::List { questions, .. } => {
+                for question in questions {
+                    question.collect_secret_requirements(out);
+                }
+            }
+            _ => {}
+        }
+    }
+
     fn collect_i18n_keys(&self, keys: &mut alloc::collections::BTreeSet<String>) {
         keys.insert(self.label.key.clone());
         if let Some(help) = &self.help {
@@ -117,10 +186,18 @@ impl Question {
         if let Some(error) = &self.error {
             keys.insert(error.key.clone());
         }
-        if let QuestionKind::Choice { options } = &self.kind {
-            for option in options {
-                keys.insert(option.label.key.clone());
+        match &self.kind {
+            QuestionKind::Choice { options } | QuestionKind::MultiSelect { options, .. } => {
+                for option in options {
+                    keys.insert(option.label.key.clone());
+                }
             }
+            QuestionKind::Group { questions } | QuestionKind::List { questions, .. } => {
+                for question in questions {
+                    question.collect_i18n_keys(keys);
+                }
+            }
+            _ => {}
         }
     }
 }
@@ -141,6 +218,80 @@ pub enum QuestionKind {
     Number,
     /// Boolean input.
     Bool,
+    /// Several options may be selected; the answer is an array of option values.
+    MultiSelect {
+        /// Choice options presented to the user.
+        options: Vec<ChoiceOption>,
+        /// Minimum number of selections.
+        #[cfg_attr(
+            feature = "serde",
+            serde(default, skip_serializing_if = "Option::is_none")
+        )]
+        min_selected: Option<u32>,
+        /// Maximum number of selections.
+        #[cfg_attr(
+            feature = "serde",
+            serde(default, skip_serializing_if = "Option::is_none")
+        )]
+        max_selected: Option<u32>,
+    },
+    /// Secret input. The value is never echoed; the answer stores the requirement key.
+    Secret {
+        /// Secret the answer is stored under.
+        requirement: SecretRequirement,
+    },
+    /// URL text input.
+    Url,
+    /// Email address input.
+    Email,
+    /// Text input that must match a regular expression.
+    Pattern {
+        /// Regular expression the answer must match.
+        regex: String,
+    },
+    /// Integer input with optional inclusive bounds.
+    Integer {
+        /// Inclusive minimum.
+        #[cfg_attr(
+            feature = "serde",
+            serde(default, skip_serializing_if = "Option::is_none")
+        )]
+        min: Option<i64>,
+        /// Inclusive maximum.
+        #[cfg_attr(
+            feature = "serde",
+            serde(default, skip_serializing_if = "Option::is_none")
+        )]
+        max: Option<i64>,
+    },
+    /// Calendar date (`YYYY-MM-DD`).
+    Date,
+    /// Time of day without an offset (`HH:MM[:SS[.frac]]`).
+    Time,
+    /// RFC 3339 timestamp.
+    DateTime,
+    /// Nested questions answered as one object.
+    Group {
+        /// Questions inside the group.
+        questions: Vec<Question>,
+    },
+    /// Nested questions answered repeatedly, as an array of objects.
+    List {
+        /// Questions asked for each item.
+        questions: Vec<Question>,
+        /// Minimum number of items.
+        #[cfg_attr(
+            feature = "serde",
+            serde(default, skip_serializing_if = "Option::is_none")
+        )]
+        min_items: Option<u32>,
+        /// Maximum number of items.
+        #[cfg_attr(
+            feature = "serde",
+            serde(default, skip_serializing_if = "Option::is_none")
+        )]
+        max_items: Option<u32>,
+    },
 }
 
 /// Choice option.
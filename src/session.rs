@@ -194,20 +194,10 @@ impl ReplyScope {
         canonical.push_str(self.correlation.as_deref().unwrap_or(""));
 
         let digest = Sha256::digest(canonical.as_bytes());
-        hex_encode(digest.as_slice())
+        crate::hex_encode(digest.as_slice())
     }
 }
 
-fn hex_encode(bytes: &[u8]) -> String {
-    const HEX: &[u8; 16] = b"0123456789abcdef";
-    let mut out = String::with_capacity(bytes.len() * 2);
-    for &byte in bytes {
-        out.push(HEX[(byte >> 4) as usize] as char);
-        out.push(HEX[(byte & 0x0f) as usize] as char);
-    }
-    out
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
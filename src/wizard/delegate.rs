@@ -0,0 +1,244 @@
+//! Delegate resolution and plan flattening.
+//!
+//! A [`WizardStep::Delegate`] step names another wizard by target, id and mode. [`flatten_plan`]
+//! asks a [`WizardPlanResolver`] for each delegated plan, passing the step's prefilled answers,
+//! and splices the delegated steps into one plan without `Delegate` steps. Paths written by a
+//! delegate are nested under its prefix, and outputs reported by a delegate are renamed through
+//! the step's `output_map`. This is how a pack wizard composes component wizards.
+//!
+//! `RunCli` steps always run in the executor root, so a delegate whose paths are nested under a
+//! non-empty prefix may not run commands: flattening fails with
+//! [`WizardDelegateError::CommandUnderPrefix`] instead of letting, say, a component wizard's
+//! `cargo init` scaffold the pack root. Delegates that need commands return an empty prefix and
+//! write root-relative paths themselves.
+
+use alloc::{
+    collections::BTreeMap,
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+
+use serde_json::Value;
+use thiserror::Error;
+
+use super::{WizardId, WizardMode, WizardPlan, WizardStep, WizardTarget};
+
+/// A delegated wizard being resolved.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DelegateRequest<'a> {
+    /// Target scope requested by the delegate step.
+    pub target: WizardTarget,
+    /// Delegate wizard identifier.
+    pub id: &'a WizardId,
+    /// Delegate mode.
+    pub mode: WizardMode,
+    /// Prefilled answers from the delegate step.
+    pub answers: &'a BTreeMap<String, Value>,
+}
+
+/// Plan produced for a delegate.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ResolvedWizard {
+    /// Delegated plan; may contain further `Delegate` steps.
+    pub plan: WizardPlan,
+    /// Outputs of the delegate, renamed by the step's `output_map` when flattened.
+    pub outputs: BTreeMap<String, Value>,
+    /// Directory the delegate's paths are nested under. `None` uses the delegate id; an empty
+    /// prefix leaves paths unchanged and is required for delegates with `RunCli` steps.
+    pub prefix: Option<String>,
+}
+
+impl From<WizardPlan> for ResolvedWizard {
+    fn from(plan: WizardPlan) -> Self {
+        Self {
+            plan,
+            outputs: BTreeMap::new(),
+            prefix: None,
+        }
+    }
+}
+
+/// Provides plans for delegate steps.
+pub trait WizardPlanResolver {
+    /// Returns the plan for `request`, or `None` when the wizard is unknown.
+    fn resolve(&self, request: &DelegateRequest<'_>) -> Option<ResolvedWizard>;
+}
+
+impl<F> WizardPlanResolver for F
+where
+    F: Fn(&DelegateRequest<'_>) -> Option<ResolvedWizard>,
+{
+    fn resolve(&self, request: &DelegateRequest<'_>) -> Option<ResolvedWizard> {
+        self(request)
+    }
+}
+
+/// Static plans keyed by wizard id; answers are ignored.
+impl WizardPlanResolver for BTreeMap<WizardId, WizardPlan> {
+    fn resolve(&self, request: &DelegateRequest<'_>) -> Option<ResolvedWizard> {
+        self.get(request.id).cloned().map(ResolvedWizard::from)
+    }
+}
+
+/// A plan with every delegate spliced in.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FlattenedPlan {
+    /// Plan with the root metadata and no `Delegate` steps.
+    pub plan: WizardPlan,
+    /// Outputs of all delegates after `output_map` renaming; later delegates win on conflicts.
+    pub outputs: BTreeMap<String, Value>,
+}
+
+/// Errors produced while flattening a plan.
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+pub enum WizardDelegateError {
+    /// The resolver does not know the delegated wizard.
+    #[error("unknown delegate wizard `{id}` ({target:?}, {mode:?})", id = .id.as_str())]
+    UnknownDelegate {
+        /// Target scope.
+        target: WizardTarget,
+        /// Delegate wizard identifier.
+        id: WizardId,
+        /// Delegate mode.
+        mode: WizardMode,
+    },
+    /// A delegate nested under a prefix contains a `RunCli` step, which would run in the root.
+    #[error("delegate `{id}` runs `{command}` but its paths are nested under `{prefix}`", id = .id.as_str())]
+    CommandUnderPrefix {
+        /// Delegate wizard identifier.
+        id: WizardId,
+        /// Command of the rejected step.
+        command: String,
+        /// Prefix the delegate's paths are nested under.
+        prefix: String,
+    },
+    /// A delegate (directly or indirectly) delegates back to itself.
+    #[error("delegation cycle: {}", .chain.iter().map(WizardId::as_str).collect::<Vec<_>>().join(" -> "))]
+    Cycle {
+        /// Wizard ids from the first occurrence to the repeated one.
+        chain: Vec<WizardId>,
+    },
+}
+
+/// Flattens `plan` by resolving every `Delegate` step, depth first and in step order.
+pub fn flatten_plan(
+    plan: &WizardPlan,
+    resolver: &dyn WizardPlanResolver,
+) -> Result<FlattenedPlan, WizardDelegateError> {
+    let mut flattened = FlattenedPlan {
+        plan: WizardPlan {
+            meta: plan.meta.clone(),
+            steps: Vec::new(),
+        },
+        outputs: BTreeMap::new(),
+    };
+    let mut stack = vec![(plan.meta.target, plan.meta.id.clone())];
+    splice(
+        &plan.steps,
+        "",
+        resolver,
+        &mut stack,
+        &mut flattened.plan.steps,
+        &mut flattened.outputs,
+    )?;
+    Ok(flattened)
+}
+
+fn splice(
+    steps: &[WizardStep],
+    prefix: &str,
+    resolver: &dyn WizardPlanResolver,
+    stack: &mut Vec<(WizardTarget, WizardId)>,
+    out: &mut Vec<WizardStep>,
+    outputs: &mut BTreeMap<String, Value>,
+) -> Result<(), WizardDelegateError> {
+    for step in steps {
+        match step {
+            WizardStep::EnsureDir { paths } => out.push(WizardStep::EnsureDir {
+                paths: paths.iter().map(|path| join_prefix(prefix, path)).collect(),
+            }),
+            WizardStep::WriteFiles { files } => out.push(WizardStep::WriteFiles {
+                files: files
+                    .iter()
+                    .map(|(path, content)| (join_prefix(prefix, path), content.clone()))
+                    .collect(),
+            }),
+            WizardStep::RunCli { command, .. } => {
+                if !prefix.is_empty()
+                    && let Some((_, id)) = stack.last()
+                {
+                    return Err(WizardDelegateError::CommandUnderPrefix {
+                        id: id.clone(),
+                        command: command.clone(),
+                        prefix: prefix.to_string(),
+                    });
+                }
+                out.push(step.clone());
+            }
+            WizardStep::Delegate {
+                target,
+                id,
+                mode,
+                prefilled_answers,
+                output_map,
+            } => {
+                if let Some(start) = stack
+                    .iter()
+                    .position(|(seen_target, seen_id)| seen_target == target && seen_id == id)
+                {
+                    let mut chain: Vec<WizardId> =
+                        stack[start..].iter().map(|(_, id)| id.clone()).collect();
+                    chain.push(id.clone());
+                    return Err(WizardDelegateError::Cycle { chain });
+                }
+                let request = DelegateRequest {
+                    target: *target,
+                    id,
+                    mode: *mode,
+                    answers: prefilled_answers,
+                };
+                let resolved = resolver.resolve(&request).ok_or_else(|| {
+                    WizardDelegateError::UnknownDelegate {
+                        target: *target,
+                        id: id.clone(),
+                        mode: *mode,
+                    }
+                })?;
+                let nested = match &resolved.prefix {
+                    Some(own) => join_prefix(prefix, own),
+                    None => join_prefix(prefix, id.as_str()),
+                };
+                let mut delegate_outputs = resolved.outputs;
+                stack.push((*target, id.clone()));
+                splice(
+                    &resolved.plan.steps,
+                    &nested,
+                    resolver,
+                    stack,
+                    out,
+                    &mut delegate_outputs,
+                )?;
+                stack.pop();
+                for (key, value) in delegate_outputs {
+                    let key = output_map.get(&key).cloned().unwrap_or(key);
+                    outputs.insert(key, value);
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Nests `path` under `prefix`. Absolute paths are kept as-is so the executor still rejects them.
+fn join_prefix(prefix: &str, path: &str) -> String {
+    let prefix = prefix.trim_end_matches('/');
+    if prefix.is_empty() || path.starts_with('/') {
+        return path.to_string();
+    }
+    match path.trim_start_matches("./") {
+        "" | "." => prefix.to_string(),
+        path => format!("{prefix}/{path}"),
+    }
+}
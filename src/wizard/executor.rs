@@ -0,0 +1,767 @@
+//! Applies a [`WizardPlan`] to a directory tree.
+//!
+//! [`WizardExecutor`] confines every step to a root directory: plan paths must be relative, may
+//! not contain `..`, and may not resolve through symlinks to a location outside the root.
+//! `RunCli` steps only run commands on an explicit allowlist, and `Delegate` steps must be
+//! flattened before execution. In dry-run mode nothing is written; the returned
+//! [`ExecutionReport`] lists the same [`FileChange`]s, with diffs against existing files, that a
+//! real run would make.
+
+use alloc::{
+    collections::{BTreeMap, BTreeSet},
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+use std::process::Command;
+
+#[cfg(feature = "schemars")]
+use schemars::JsonSchema;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use super::journal::{JournalEntry, RollbackReport, WizardJournal};
+use super::{WizardId, WizardMode, WizardPlan, WizardStep};
+use crate::cbor::canonical::CanonicalError;
+
+/// Kind of change made (or planned) for a path.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum FileChangeKind {
+    /// A directory is created.
+    CreateDir,
+    /// A new file is written.
+    CreateFile,
+    /// An existing file is overwritten with different content.
+    ModifyFile,
+}
+
+/// One filesystem change produced by a plan step.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+pub struct FileChange {
+    /// Index of the plan step that made the change.
+    pub step: usize,
+    /// Path relative to the executor root, `/`-separated.
+    pub path: String,
+    /// Kind of change.
+    pub kind: FileChangeKind,
+    /// Unified diff against the previous content; `None` for directories, non-UTF-8 files and
+    /// rewrites too large to diff.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub diff: Option<String>,
+}
+
+/// Outcome of a single plan step.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case", tag = "status"))]
+pub enum StepOutcome {
+    /// Filesystem changes were applied (or were already in place).
+    Applied,
+    /// Dry run: the step was checked but not applied.
+    Planned,
+    /// Skipped on resume: the journal records the step as completed.
+    AlreadyCompleted,
+    /// An allowlisted command ran and exited successfully.
+    CommandCompleted {
+        /// Captured standard output (lossy UTF-8).
+        stdout: String,
+        /// Captured standard error (lossy UTF-8).
+        stderr: String,
+    },
+}
+
+/// Report entry for one plan step.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+pub struct StepReport {
+    /// Index of the step in the plan.
+    pub index: usize,
+    /// Step outcome.
+    pub outcome: StepOutcome,
+}
+
+/// Structured result of executing a plan.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+pub struct ExecutionReport {
+    /// Whether the run was a dry run.
+    pub dry_run: bool,
+    /// Filesystem changes in the order they were made.
+    pub changes: Vec<FileChange>,
+    /// One entry per plan step, in plan order.
+    pub steps: Vec<StepReport>,
+}
+
+/// Errors produced while executing a plan.
+#[derive(Debug, Error)]
+pub enum WizardExecError {
+    /// A plan path is empty, absolute, or contains `..`.
+    #[error("step {step}: invalid path `{path}`: {reason}")]
+    InvalidPath {
+        /// Step index.
+        step: usize,
+        /// Offending path as written in the plan.
+        path: String,
+        /// Why the path was rejected.
+        reason: &'static str,
+    },
+    /// A plan path resolves, through a symlink, outside the root.
+    #[error("step {step}: path `{path}` resolves outside the wizard root")]
+    EscapesRoot {
+        /// Step index.
+        step: usize,
+        /// Offending path.
+        path: String,
+    },
+    /// A `RunCli` command is not on the allowlist.
+    #[error("step {step}: command `{command}` is not allowed")]
+    CommandNotAllowed {
+        /// Step index.
+        step: usize,
+        /// Rejected command.
+        command: String,
+    },
+    /// A `RunCli` command exited unsuccessfully.
+    #[error("step {step}: command `{command}` failed with exit code {code:?}: {stderr}")]
+    CommandFailed {
+        /// Step index.
+        step: usize,
+        /// Failed command.
+        command: String,
+        /// Exit code, if the process exited normally.
+        code: Option<i32>,
+        /// Captured standard error (lossy UTF-8).
+        stderr: String,
+    },
+    /// The plan still contains a `Delegate` step.
+    #[error("step {step}: delegate `{id}` must be resolved before execution", id = .id.as_str())]
+    UnresolvedDelegate {
+        /// Step index.
+        step: usize,
+        /// Delegate wizard identifier.
+        id: WizardId,
+    },
+    /// The journal was recorded for another plan and still holds undo entries.
+    #[error(
+        "journal for plan `{plan_id}` still holds {entries} undo entries; roll it back or reset it first"
+    )]
+    JournalMismatch {
+        /// Plan the journal was recorded for.
+        plan_id: String,
+        /// Number of pending undo entries.
+        entries: usize,
+    },
+    /// Plan steps could not be encoded for the journal's plan digest.
+    #[error("plan steps could not be digested for the journal: {0}")]
+    PlanDigest(#[from] CanonicalError),
+    /// Filesystem or process I/O failed.
+    #[error("step {step}: {path}: {source}")]
+    Io {
+        /// Step index.
+        step: usize,
+        /// Path (or command) being accessed.
+        path: String,
+        /// Underlying error.
+        #[source]
+        source: io::Error,
+    },
+}
+
+/// Applies wizard plans under a root directory.
+#[derive(Clone, Debug)]
+pub struct WizardExecutor {
+    root: PathBuf,
+    dry_run: bool,
+    allowed_commands: BTreeSet<String>,
+}
+
+impl WizardExecutor {
+    /// Creates an executor for `root`. No commands are allowed by default.
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            dry_run: false,
+            allowed_commands: BTreeSet::new(),
+        }
+    }
+
+    /// Enables or disables dry-run mode.
+    pub fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Allows `RunCli` steps to run `command` (matched exactly against the step's command).
+    pub fn with_allowed_command(mut self, command: impl Into<String>) -> Self {
+        self.allowed_commands.insert(command.into());
+        self
+    }
+
+    /// Root directory all plan paths are resolved against.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Executes `plan`.
+    ///
+    /// Every path, command and delegate in the plan is checked before any step runs, so a plan
+    /// rejected for those reasons leaves the tree untouched.
+    pub fn execute(&self, plan: &WizardPlan) -> Result<ExecutionReport, WizardExecError> {
+        self.execute_journaled(plan, &mut WizardJournal::for_plan(plan)?)
+    }
+
+    /// Executes `plan`, recording undo information and completed steps in `journal`.
+    ///
+    /// The journal is updated as each change is made, so after an error it describes exactly
+    /// what the failed run changed and can be passed to [`WizardExecutor::rollback`]. A journal
+    /// recorded for a different plan is reset when it holds no undo entries; otherwise
+    /// [`WizardExecError::JournalMismatch`] is returned so those entries are not lost, and the
+    /// caller must roll back or replace the journal with [`WizardJournal::for_plan`] explicitly.
+    /// When the plan's mode is [`WizardMode::Update`],
+    /// steps the journal marks as completed are skipped, which resumes an interrupted run.
+    /// Dry runs leave the journal untouched.
+    pub fn execute_journaled(
+        &self,
+        plan: &WizardPlan,
+        journal: &mut WizardJournal,
+    ) -> Result<ExecutionReport, WizardExecError> {
+        self.check_plan(plan)?;
+        if !journal.is_for(plan) {
+            if !journal.entries.is_empty() {
+                return Err(WizardExecError::JournalMismatch {
+                    plan_id: journal.plan_id.as_str().to_string(),
+                    entries: journal.entries.len(),
+                });
+            }
+            *journal = WizardJournal::for_plan(plan)?;
+        }
+        let resume = plan.meta.mode == WizardMode::Update;
+        let mut state = RunState {
+            dirs: BTreeSet::new(),
+            files: BTreeMap::new(),
+            journal,
+        };
+        let mut report = ExecutionReport {
+            dry_run: self.dry_run,
+            ..ExecutionReport::default()
+        };
+        for (index, step) in plan.steps.iter().enumerate() {
+            if resume && state.journal.is_completed(index) {
+                report.steps.push(StepReport {
+                    index,
+                    outcome: StepOutcome::AlreadyCompleted,
+                });
+                continue;
+            }
+            let outcome = self.execute_step(index, step, &mut state, &mut report.changes)?;
+            if !self.dry_run && !state.journal.is_completed(index) {
+                state.journal.completed_steps.push(index);
+            }
+            report.steps.push(StepReport { index, outcome });
+        }
+        Ok(report)
+    }
+
+    /// Undoes the changes recorded in `journal`, newest first.
+    ///
+    /// Overwritten files get their previous content back and created files are removed.
+    /// Created directories are removed only when empty. Rollback always touches the tree,
+    /// even on a dry-run executor.
+    pub fn rollback(&self, journal: &WizardJournal) -> Result<RollbackReport, WizardExecError> {
+        let mut report = RollbackReport::default();
+        for entry in journal.entries.iter().rev() {
+            let step = entry.step();
+            let rel = relative_path(step, entry.path(), false)?;
+            self.confine(step, &rel)?;
+            let full = self.root.join(&rel);
+            let path = display_path(&rel);
+            match entry {
+                JournalEntry::ModifiedFile { previous, .. } => {
+                    fs::write(&full, previous).map_err(|source| io_error(step, &rel, source))?;
+                    report.restored.push(path);
+                }
+                JournalEntry::CreatedFile { .. } => match fs::remove_file(&full) {
+                    Ok(()) => report.removed.push(path),
+                    Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+                    Err(source) => return Err(io_error(step, &rel, source)),
+                },
+                JournalEntry::CreatedDir { .. } => match fs::remove_dir(&full) {
+                    Ok(()) => report.removed.push(path),
+                    Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+                    Err(_) if full.is_dir() => report.kept.push(path),
+                    Err(source) => return Err(io_error(step, &rel, source)),
+                },
+            }
+        }
+        Ok(report)
+    }
+
+    fn check_plan(&self, plan: &WizardPlan) -> Result<(), WizardExecError> {
+        for (index, step) in plan.steps.iter().enumerate() {
+            match step {
+                WizardStep::EnsureDir { paths } => {
+                    for path in paths {
+                        relative_path(index, path, true)?;
+                    }
+                }
+                WizardStep::WriteFiles { files } => {
+                    for path in files.keys() {
+                        relative_path(index, path, false)?;
+                    }
+                }
+                WizardStep::RunCli { command, .. } => {
+                    if !self.allowed_commands.contains(command) {
+                        return Err(WizardExecError::CommandNotAllowed {
+                            step: index,
+                            command: command.clone(),
+                        });
+                    }
+                }
+                WizardStep::Delegate { id, .. } => {
+                    return Err(WizardExecError::UnresolvedDelegate {
+                        step: index,
+                        id: id.clone(),
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn execute_step(
+        &self,
+        index: usize,
+        step: &WizardStep,
+        state: &mut RunState<'_>,
+        changes: &mut Vec<FileChange>,
+    ) -> Result<StepOutcome, WizardExecError> {
+        match step {
+            WizardStep::EnsureDir { paths } => {
+                for path in paths {
+                    let rel = relative_path(index, path, true)?;
+                    self.ensure_dir(index, &rel, state, changes)?;
+                }
+            }
+            WizardStep::WriteFiles { files } => {
+                for (path, content) in files {
+                    let rel = relative_path(index, path, false)?;
+                    if let Some(parent) = rel.parent() {
+                        self.ensure_dir(index, parent, state, changes)?;
+                    }
+                    self.write_file(index, &rel, content, state, changes)?;
+                }
+            }
+            WizardStep::RunCli { command, args } => {
+                if self.dry_run {
+                    return Ok(StepOutcome::Planned);
+                }
+                return self.run_command(index, command, args);
+            }
+            WizardStep::Delegate { id, .. } => {
+                return Err(WizardExecError::UnresolvedDelegate {
+                    step: index,
+                    id: id.clone(),
+                });
+            }
+        }
+        Ok(if self.dry_run {
+            StepOutcome::Planned
+        } else {
+            StepOutcome::Applied
+        })
+    }
+
+    fn ensure_dir(
+        &self,
+        index: usize,
+        rel: &Path,
+        state: &mut RunState<'_>,
+        changes: &mut Vec<FileChange>,
+    ) -> Result<(), WizardExecError> {
+        let mut current = PathBuf::new();
+        for component in rel.components() {
+            current.push(component);
+            if state.dirs.contains(&current) {
+                continue;
+            }
+            self.confine(index, &current)?;
+            let full = self.root.join(&current);
+            if !full.is_dir() {
+                if !self.dry_run {
+                    fs::create_dir_all(&full)
+                        .map_err(|source| io_error(index, &current, source))?;
+                    state.journal.entries.push(JournalEntry::CreatedDir {
+                        step: index,
+                        path: display_path(&current),
+                    });
+                }
+                changes.push(FileChange {
+                    step: index,
+                    path: display_path(&current),
+                    kind: FileChangeKind::CreateDir,
+                    diff: None,
+                });
+            }
+            state.dirs.insert(current.clone());
+        }
+        Ok(())
+    }
+
+    fn write_file(
+        &self,
+        index: usize,
+        rel: &Path,
+        content: &str,
+        state: &mut RunState<'_>,
+        changes: &mut Vec<FileChange>,
+    ) -> Result<(), WizardExecError> {
+        self.confine(index, rel)?;
+        let full = self.root.join(rel);
+        let previous_bytes = match state.files.get(rel) {
+            Some(planned) => Some(planned.clone().into_bytes()),
+            None => match fs::read(&full) {
+                Ok(bytes) => Some(bytes),
+                Err(err) if err.kind() == io::ErrorKind::NotFound => None,
+                Err(source) => return Err(io_error(index, rel, source)),
+            },
+        };
+        if previous_bytes.as_deref() == Some(content.as_bytes()) {
+            return Ok(());
+        }
+        let path = display_path(rel);
+        let previous = previous_bytes
+            .as_ref()
+            .map(|bytes| String::from_utf8(bytes.clone()).ok());
+        if !self.dry_run {
+            fs::write(&full, content).map_err(|source| io_error(index, rel, source))?;
+            state.journal.entries.push(match previous_bytes {
+                Some(previous) => JournalEntry::ModifiedFile {
+                    step: index,
+                    path: path.clone(),
+                    previous,
+                },
+                None => JournalEntry::CreatedFile {
+                    step: index,
+                    path: path.clone(),
+                },
+            });
+        }
+        let (kind, diff) = match &previous {
+            None => (
+                FileChangeKind::CreateFile,
+                unified_diff(&path, None, content),
+            ),
+            Some(old) => (
+                FileChangeKind::ModifyFile,
+                old.as_deref()
+                    .and_then(|old| unified_diff(&path, Some(old), content)),
+            ),
+        };
+        changes.push(FileChange {
+            step: index,
+            path,
+            kind,
+            diff,
+        });
+        state.files.insert(rel.to_path_buf(), content.to_string());
+        Ok(())
+    }
+
+    fn run_command(
+        &self,
+        index: usize,
+        command: &str,
+        args: &[String],
+    ) -> Result<StepOutcome, WizardExecError> {
+        let output = Command::new(command)
+            .args(args)
+            .current_dir(&self.root)
+            .output()
+            .map_err(|source| WizardExecError::Io {
+                step: index,
+                path: command.to_string(),
+                source,
+            })?;
+        let stdout = String::from_utf8_lossy(&output.stdout).into_owned();
+        let stderr = String::from_utf8_lossy(&output.stderr).into_owned();
+        if !output.status.success() {
+            return Err(WizardExecError::CommandFailed {
+                step: index,
+                command: command.to_string(),
+                code: output.status.code(),
+                stderr,
+            });
+        }
+        Ok(StepOutcome::CommandCompleted { stdout, stderr })
+    }
+
+    /// Rejects `rel` when its deepest existing ancestor resolves outside the root, which catches
+    /// symlinked directories and files pointing elsewhere.
+    fn confine(&self, index: usize, rel: &Path) -> Result<(), WizardExecError> {
+        let mut existing = self.root.join(rel);
+        while fs::symlink_metadata(&existing).is_err() {
+            if !existing.pop() || !existing.starts_with(&self.root) || existing == self.root {
+                return Ok(());
+            }
+        }
+        let escapes = match (fs::canonicalize(&existing), fs::canonicalize(&self.root)) {
+            (Ok(resolved), Ok(root)) => !resolved.starts_with(root),
+            // A dangling symlink cannot be resolved; refuse to write through it.
+            _ => true,
+        };
+        if escapes {
+            return Err(WizardExecError::EscapesRoot {
+                step: index,
+                path: display_path(rel),
+            });
+        }
+        Ok(())
+    }
+}
+
+struct RunState<'a> {
+    dirs: BTreeSet<PathBuf>,
+    files: BTreeMap<PathBuf, String>,
+    journal: &'a mut WizardJournal,
+}
+
+/// Normalizes a plan path, dropping `.` components. `allow_root` accepts paths that normalize
+/// to the root itself (only meaningful for directories).
+fn relative_path(index: usize, path: &str, allow_root: bool) -> Result<PathBuf, WizardExecError> {
+    let invalid = |reason| WizardExecError::InvalidPath {
+        step: index,
+        path: path.to_string(),
+        reason,
+    };
+    let mut normalized = PathBuf::new();
+    for component in Path::new(path).components() {
+        match component {
+            Component::Normal(part) => normalized.push(part),
+            Component::CurDir => {}
+            Component::ParentDir => {
+                return Err(invalid("parent directory components are not allowed"));
+            }
+            Component::RootDir | Component::Prefix(_) => {
+                return Err(invalid("absolute paths are not allowed"));
+            }
+        }
+    }
+    if normalized.as_os_str().is_empty() && !allow_root {
+        return Err(invalid("path is empty"));
+    }
+    Ok(normalized)
+}
+
+fn display_path(path: &Path) -> String {
+    path.components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn io_error(index: usize, rel: &Path, source: io::Error) -> WizardExecError {
+    WizardExecError::Io {
+        step: index,
+        path: display_path(rel),
+        source,
+    }
+}
+
+const DIFF_CONTEXT: usize = 3;
+/// Largest number of inserted plus removed lines a diff is computed for.
+const MAX_DIFF_EDITS: usize = 1000;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Edit {
+    Keep,
+    Remove,
+    Insert,
+}
+
+/// Unified diff with three lines of context; `old` is `None` for new files.
+///
+/// Returns `None` when the files differ in more than [`MAX_DIFF_EDITS`] lines.
+fn unified_diff(path: &str, old: Option<&str>, new: &str) -> Option<String> {
+    let old_lines: Vec<&str> = old.map(|text| text.lines().collect()).unwrap_or_default();
+    let new_lines: Vec<&str> = new.lines().collect();
+    let edits = line_edits(&old_lines, &new_lines)?;
+
+    let mut out = match old {
+        Some(_) => format!("--- a/{path}\n+++ b/{path}\n"),
+        None => format!("--- /dev/null\n+++ b/{path}\n"),
+    };
+    // Positions (edit index, old line, new line) at the start of each edit.
+    let mut positions = Vec::with_capacity(edits.len() + 1);
+    let (mut old_at, mut new_at) = (0, 0);
+    for edit in &edits {
+        positions.push((old_at, new_at));
+        match edit {
+            Edit::Keep => {
+                old_at += 1;
+                new_at += 1;
+            }
+            Edit::Remove => old_at += 1,
+            Edit::Insert => new_at += 1,
+        }
+    }
+
+    let mut index = 0;
+    while index < edits.len() {
+        if edits[index] == Edit::Keep {
+            index += 1;
+            continue;
+        }
+        let start = index.saturating_sub(DIFF_CONTEXT);
+        // Merge changes separated by at most twice the context into one hunk.
+        let mut last_change = index;
+        let mut cursor = index;
+        while cursor < edits.len() {
+            if edits[cursor] != Edit::Keep {
+                last_change = cursor;
+                cursor += 1;
+                continue;
+            }
+            let run_end = edits[cursor..]
+                .iter()
+                .position(|edit| *edit != Edit::Keep)
+                .map_or(edits.len(), |offset| cursor + offset);
+            if run_end == edits.len() || run_end - cursor > DIFF_CONTEXT * 2 {
+                break;
+            }
+            cursor = run_end;
+        }
+        let end = (last_change + 1 + DIFF_CONTEXT).min(edits.len());
+
+        let (old_start, new_start) = positions[start];
+        let hunk = &edits[start..end];
+        let old_count = hunk.iter().filter(|edit| **edit != Edit::Insert).count();
+        let new_count = hunk.iter().filter(|edit| **edit != Edit::Remove).count();
+        out.push_str(&format!(
+            "@@ -{} +{} @@\n",
+            hunk_range(old_start, old_count),
+            hunk_range(new_start, new_count)
+        ));
+        for (offset, edit) in hunk.iter().enumerate() {
+            let (old_at, new_at) = positions[start + offset];
+            match edit {
+                Edit::Keep => out.push_str(&format!(" {}\n", old_lines[old_at])),
+                Edit::Remove => out.push_str(&format!("-{}\n", old_lines[old_at])),
+                Edit::Insert => out.push_str(&format!("+{}\n", new_lines[new_at])),
+            }
+        }
+        index = end;
+    }
+    Some(out)
+}
+
+fn hunk_range(start: usize, count: usize) -> String {
+    match count {
+        0 => format!("{start},0"),
+        1 => format!("{}", start + 1),
+        _ => format!("{},{count}", start + 1),
+    }
+}
+
+/// Shortest line edit script, or `None` when it needs more than [`MAX_DIFF_EDITS`] changes.
+fn line_edits(old: &[&str], new: &[&str]) -> Option<Vec<Edit>> {
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let old_mid = &old[prefix..old.len() - suffix];
+    let new_mid = &new[prefix..new.len() - suffix];
+
+    let mut edits = vec![Edit::Keep; prefix];
+    if old_mid.is_empty() || new_mid.is_empty() {
+        edits.extend(core::iter::repeat_n(Edit::Remove, old_mid.len()));
+        edits.extend(core::iter::repeat_n(Edit::Insert, new_mid.len()));
+    } else {
+        edits.extend(myers_edits(old_mid, new_mid)?);
+    }
+    edits.extend(core::iter::repeat_n(Edit::Keep, suffix));
+    Some(edits)
+}
+
+/// Myers' O(ND) greedy diff. Memory grows with the square of the edit count, which is capped.
+fn myers_edits(old: &[&str], new: &[&str]) -> Option<Vec<Edit>> {
+    let (n, m) = (old.len() as isize, new.len() as isize);
+    let max = (n + m).min(MAX_DIFF_EDITS as isize);
+    let offset = max + 1;
+    // Furthest `x` reached on each diagonal `k = x - y`, stored at `k + offset`.
+    let mut furthest = vec![0isize; (2 * offset + 1) as usize];
+    // Snapshot of diagonals `-d - 1..=d + 1` taken before each round `d`.
+    let mut trace = Vec::new();
+    for d in 0..=max {
+        trace.push(furthest[(offset - d - 1) as usize..=(offset + d + 1) as usize].to_vec());
+        for k in (-d..=d).step_by(2) {
+            let at = (k + offset) as usize;
+            let mut x = if k == -d || (k != d && furthest[at - 1] < furthest[at + 1]) {
+                furthest[at + 1]
+            } else {
+                furthest[at - 1] + 1
+            };
+            let mut y = x - k;
+            while x < n && y < m && old[x as usize] == new[y as usize] {
+                x += 1;
+                y += 1;
+            }
+            furthest[at] = x;
+            if x >= n && y >= m {
+                return Some(backtrack(&trace, n, m));
+            }
+        }
+    }
+    None
+}
+
+/// Walks the Myers trace back from the end and returns the edits in forward order.
+fn backtrack(trace: &[Vec<isize>], n: isize, m: isize) -> Vec<Edit> {
+    let mut edits = Vec::new();
+    let (mut x, mut y) = (n, m);
+    for (d, snapshot) in trace.iter().enumerate().rev() {
+        let d = d as isize;
+        let reached = |k: isize| snapshot[(k + d + 1) as usize];
+        let k = x - y;
+        let prev_k = if k == -d || (k != d && reached(k - 1) < reached(k + 1)) {
+            k + 1
+        } else {
+            k - 1
+        };
+        let prev_x = reached(prev_k);
+        let prev_y = prev_x - prev_k;
+        while x > prev_x && y > prev_y {
+            edits.push(Edit::Keep);
+            x -= 1;
+            y -= 1;
+        }
+        if d > 0 {
+            edits.push(if x == prev_x {
+                Edit::Insert
+            } else {
+                Edit::Remove
+            });
+        }
+        x = prev_x;
+        y = prev_y;
+    }
+    edits.reverse();
+    edits
+}
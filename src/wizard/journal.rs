@@ -0,0 +1,196 @@
+//! Undo journal for wizard executions.
+//!
+//! [`WizardExecutor::execute_journaled`](super::executor::WizardExecutor::execute_journaled)
+//! records every directory it creates and the previous content of every file it overwrites,
+//! along with the steps that completed. The journal is stored next to the project (see
+//! [`journal_path_for`]) so a failed run can be undone with
+//! [`WizardExecutor::rollback`](super::executor::WizardExecutor::rollback), or resumed by
+//! re-running the same plan in [`WizardMode::Update`](super::WizardMode::Update).
+
+use alloc::{format, string::String, vec::Vec};
+#[cfg(feature = "serde")]
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[cfg(feature = "schemars")]
+use schemars::JsonSchema;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "serde")]
+use serde_with::serde_as;
+
+use super::{WizardId, WizardPlan};
+use crate::cbor::canonical;
+#[cfg(feature = "serde")]
+use crate::{ErrorCode, GResult, GreenticError};
+
+/// File name of the journal inside the wizard root.
+pub const JOURNAL_FILE_NAME: &str = ".wizard-journal.json";
+
+/// A single undoable change.
+#[cfg_attr(feature = "serde", serde_as)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case", tag = "type"))]
+pub enum JournalEntry {
+    /// A directory was created.
+    CreatedDir {
+        /// Step that created the directory.
+        step: usize,
+        /// Path relative to the wizard root, `/`-separated.
+        path: String,
+    },
+    /// A file that did not exist was written.
+    CreatedFile {
+        /// Step that wrote the file.
+        step: usize,
+        /// Path relative to the wizard root, `/`-separated.
+        path: String,
+    },
+    /// An existing file was overwritten.
+    ModifiedFile {
+        /// Step that overwrote the file.
+        step: usize,
+        /// Path relative to the wizard root, `/`-separated.
+        path: String,
+        /// Previous file content (base64 encoded when serialized).
+        #[cfg_attr(feature = "serde", serde_as(as = "serde_with::base64::Base64"))]
+        previous: Vec<u8>,
+    },
+}
+
+impl JournalEntry {
+    /// Step that made the change.
+    pub fn step(&self) -> usize {
+        match self {
+            Self::CreatedDir { step, .. }
+            | Self::CreatedFile { step, .. }
+            | Self::ModifiedFile { step, .. } => *step,
+        }
+    }
+
+    /// Path of the change, relative to the wizard root.
+    pub fn path(&self) -> &str {
+        match self {
+            Self::CreatedDir { path, .. }
+            | Self::CreatedFile { path, .. }
+            | Self::ModifiedFile { path, .. } => path,
+        }
+    }
+}
+
+/// Journal of one plan's executions.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+pub struct WizardJournal {
+    /// Identifier of the journaled plan.
+    pub plan_id: WizardId,
+    /// Digest of the plan steps (`blake3:<hex>`); a journal only resumes the plan it was made for.
+    pub plan_digest: String,
+    /// Indexes of steps that completed, in completion order.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub completed_steps: Vec<usize>,
+    /// Changes in the order they were made.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub entries: Vec<JournalEntry>,
+}
+
+impl WizardJournal {
+    /// Creates an empty journal for `plan`.
+    ///
+    /// Fails when the plan's steps cannot be encoded for [`plan_digest`].
+    pub fn for_plan(plan: &WizardPlan) -> Result<Self, canonical::CanonicalError> {
+        Ok(Self {
+            plan_id: plan.meta.id.clone(),
+            plan_digest: plan_digest(plan)?,
+            completed_steps: Vec::new(),
+            entries: Vec::new(),
+        })
+    }
+
+    /// Returns `true` when the journal was recorded for `plan` (same id and steps).
+    ///
+    /// A plan whose steps cannot be digested matches no journal.
+    pub fn is_for(&self, plan: &WizardPlan) -> bool {
+        self.plan_id == plan.meta.id
+            && plan_digest(plan).is_ok_and(|digest| digest == self.plan_digest)
+    }
+
+    /// Returns `true` when step `index` completed.
+    pub fn is_completed(&self, index: usize) -> bool {
+        self.completed_steps.contains(&index)
+    }
+}
+
+/// Digest of a plan's steps, used to match a journal to the plan it was recorded for.
+///
+/// The digest covers the canonical CBOR encoding of every step, including delegate targets,
+/// modes and prefilled answers. Fails when a step holds a value canonical CBOR cannot encode.
+pub fn plan_digest(plan: &WizardPlan) -> Result<String, canonical::CanonicalError> {
+    let bytes = canonical::to_canonical_cbor_allow_floats(&plan.steps)?;
+    Ok(format!("blake3:{}", blake3::hash(&bytes).to_hex()))
+}
+
+/// Outcome of a rollback.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+pub struct RollbackReport {
+    /// Files restored to their previous content.
+    pub restored: Vec<String>,
+    /// Created files and directories that were removed.
+    pub removed: Vec<String>,
+    /// Created directories left in place because they still contain other files.
+    pub kept: Vec<String>,
+}
+
+/// Journal location for a wizard root.
+pub fn journal_path_for(root: &Path) -> PathBuf {
+    root.join(JOURNAL_FILE_NAME)
+}
+
+/// Reads a wizard journal from disk.
+#[cfg(feature = "serde")]
+pub fn read_wizard_journal(path: &Path) -> GResult<WizardJournal> {
+    let raw = fs::read_to_string(path).map_err(|err| {
+        let code = match err.kind() {
+            std::io::ErrorKind::NotFound => ErrorCode::NotFound,
+            _ => ErrorCode::Unavailable,
+        };
+        GreenticError::new(code, format!("read wizard journal: {err}")).with_source(err)
+    })?;
+    serde_json::from_str(&raw).map_err(|err| {
+        GreenticError::new(
+            ErrorCode::InvalidInput,
+            format!("parse wizard journal: {err}"),
+        )
+        .with_source(err)
+    })
+}
+
+/// Writes a wizard journal to disk.
+#[cfg(feature = "serde")]
+pub fn write_wizard_journal(path: &Path, journal: &WizardJournal) -> GResult<()> {
+    let raw = serde_json::to_string_pretty(journal).map_err(|err| {
+        GreenticError::new(
+            ErrorCode::Internal,
+            format!("serialize wizard journal: {err}"),
+        )
+        .with_source(err)
+    })?;
+    fs::write(path, raw).map_err(|err| {
+        GreenticError::new(
+            ErrorCode::Unavailable,
+            format!("write wizard journal: {err}"),
+        )
+        .with_source(err)
+    })
+}
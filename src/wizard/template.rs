@@ -0,0 +1,410 @@
+//! Declarative wizard plans rendered from QA answers.
+//!
+//! A [`WizardTemplate`] pairs a QA spec with templated steps, so packs can ship scaffolding as
+//! data. [`render_wizard_template`] validates the answers against the spec (filling defaults),
+//! drops steps whose `when` predicate does not hold, and expands placeholders in paths, file
+//! contents, command arguments and delegate answers.
+//!
+//! Template syntax:
+//!
+//! - `{{name}}` inserts an answer; nested answers use `/`-joined ids (`{{db/host}}`). Text is
+//!   inserted as-is, numbers and booleans in their plain form, and multi-select answers as a
+//!   comma-separated list. Referencing an unanswered question is an error.
+//! - `{{#if name}}…{{else}}…{{/if}}` renders a branch depending on whether the answer is truthy
+//!   (answered and not `false`, `0`, `""` or `[]`). Conditions may also compare an answer with
+//!   `==`/`!=` against a quoted string, a number, `true` or `false`.
+//! - `\{{` produces a literal `{{`.
+//!
+//! Files whose path renders to an empty string are skipped, which makes whole files conditional.
+
+use alloc::{
+    collections::BTreeMap,
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use ciborium::value::Value;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use thiserror::Error;
+
+use super::{WizardPlan, WizardPlanMeta, WizardStep};
+use crate::qa::{AnswerError, check_qa_answers};
+use crate::schemas::common::qa_predicate::{self, QaPredicate};
+use crate::schemas::component::v0_6_0::ComponentQaSpec;
+
+/// A plan step with an optional condition.
+///
+/// Paths, file contents, command arguments and string values in delegate `prefilled_answers`
+/// are templates.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TemplateStep {
+    /// The step is rendered only when this predicate holds for the answers.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub when: Option<QaPredicate>,
+    /// Templated step.
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub step: WizardStep,
+}
+
+impl From<WizardStep> for TemplateStep {
+    fn from(step: WizardStep) -> Self {
+        Self { when: None, step }
+    }
+}
+
+/// QA spec plus templated steps that render into a [`WizardPlan`].
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct WizardTemplate {
+    /// Metadata copied into the rendered plan.
+    pub meta: WizardPlanMeta,
+    /// Questions whose answers drive the template.
+    pub qa: ComponentQaSpec,
+    /// Templated steps, rendered in order.
+    pub steps: Vec<TemplateStep>,
+}
+
+/// Errors produced while rendering a template.
+#[derive(Clone, Debug, Error, PartialEq)]
+pub enum WizardTemplateError {
+    /// Answers do not satisfy the template's QA spec.
+    #[error("answers failed validation ({} errors)", .0.len())]
+    InvalidAnswers(Vec<AnswerError>),
+    /// A template string is malformed or references an unusable answer.
+    #[error("step {step}, {field}: {message}")]
+    Template {
+        /// Index of the template step.
+        step: usize,
+        /// Templated field (for example ``file `src/lib.rs` ``).
+        field: String,
+        /// What went wrong.
+        message: String,
+    },
+}
+
+/// Renders `template` with `answers` into a plan.
+///
+/// Rendering is deterministic: the same template and answers always produce the same plan.
+pub fn render_wizard_template(
+    template: &WizardTemplate,
+    answers: &BTreeMap<String, Value>,
+) -> Result<WizardPlan, WizardTemplateError> {
+    let mut entries: Vec<(Value, Value)> = answers
+        .iter()
+        .map(|(key, value)| (Value::Text(key.clone()), value.clone()))
+        .collect();
+    check_qa_answers(&template.qa, &mut entries).map_err(WizardTemplateError::InvalidAnswers)?;
+    let root = Value::Map(entries);
+
+    let mut steps = Vec::new();
+    for (index, templated) in template.steps.iter().enumerate() {
+        if let Some(when) = &templated.when
+            && !when.evaluate(&root)
+        {
+            continue;
+        }
+        let render = |field: &dyn Fn() -> String, text: &str| {
+            render_str(text, &root).map_err(|message| WizardTemplateError::Template {
+                step: index,
+                field: field(),
+                message,
+            })
+        };
+        let step = match &templated.step {
+            WizardStep::EnsureDir { paths } => WizardStep::EnsureDir {
+                paths: paths
+                    .iter()
+                    .map(|path| render(&|| format!("path `{path}`"), path))
+                    .filter(|path| !matches!(path, Ok(path) if path.is_empty()))
+                    .collect::<Result<_, _>>()?,
+            },
+            WizardStep::WriteFiles { files } => {
+                let mut rendered = BTreeMap::new();
+                for (path, content) in files {
+                    let target = render(&|| format!("path `{path}`"), path)?;
+                    if target.is_empty() {
+                        continue;
+                    }
+                    let content = render(&|| format!("file `{path}`"), content)?;
+                    rendered.insert(target, content);
+                }
+                WizardStep::WriteFiles { files: rendered }
+            }
+            WizardStep::RunCli { command, args } => WizardStep::RunCli {
+                command: command.clone(),
+                args: args
+                    .iter()
+                    .map(|arg| render(&|| format!("argument `{arg}`"), arg))
+                    .collect::<Result<_, _>>()?,
+            },
+            WizardStep::Delegate {
+                target,
+                id,
+                mode,
+                prefilled_answers,
+                output_map,
+            } => WizardStep::Delegate {
+                target: *target,
+                id: id.clone(),
+                mode: *mode,
+                prefilled_answers: prefilled_answers
+                    .iter()
+                    .map(|(key, value)| {
+                        render_json(value, &|text| {
+                            render(&|| format!("prefilled answer `{key}`"), text)
+                        })
+                        .map(|value| (key.clone(), value))
+                    })
+                    .collect::<Result<_, _>>()?,
+                output_map: output_map.clone(),
+            },
+        };
+        steps.push(step);
+    }
+    Ok(WizardPlan {
+        meta: template.meta.clone(),
+        steps,
+    })
+}
+
+fn render_json(
+    value: &JsonValue,
+    render: &dyn Fn(&str) -> Result<String, WizardTemplateError>,
+) -> Result<JsonValue, WizardTemplateError> {
+    Ok(match value {
+        JsonValue::String(text) => JsonValue::String(render(text)?),
+        JsonValue::Array(items) => JsonValue::Array(
+            items
+                .iter()
+                .map(|item| render_json(item, render))
+                .collect::<Result<_, _>>()?,
+        ),
+        JsonValue::Object(map) => JsonValue::Object(
+            map.iter()
+                .map(|(key, item)| Ok((key.clone(), render_json(item, render)?)))
+                .collect::<Result<_, WizardTemplateError>>()?,
+        ),
+        other => other.clone(),
+    })
+}
+
+enum Node {
+    Text(String),
+    Answer(String),
+    If {
+        condition: Condition,
+        then: Vec<Node>,
+        otherwise: Vec<Node>,
+    },
+}
+
+enum Condition {
+    Truthy(String),
+    Equals(String, Value),
+    NotEquals(String, Value),
+}
+
+/// How a block of nodes ended while parsing.
+enum BlockEnd {
+    Eof,
+    Else,
+    EndIf,
+}
+
+fn render_str(template: &str, answers: &Value) -> Result<String, String> {
+    if !template.contains("{{") {
+        return Ok(template.to_string());
+    }
+    let mut rest = template;
+    let (nodes, end) = parse_block(&mut rest)?;
+    match end {
+        BlockEnd::Eof => {}
+        BlockEnd::Else => return Err("`{{else}}` outside `{{#if}}`".into()),
+        BlockEnd::EndIf => return Err("`{{/if}}` without `{{#if}}`".into()),
+    }
+    let mut out = String::new();
+    render_nodes(&nodes, answers, &mut out)?;
+    Ok(out)
+}
+
+fn parse_block(rest: &mut &str) -> Result<(Vec<Node>, BlockEnd), String> {
+    let mut nodes = Vec::new();
+    let mut text = String::new();
+    loop {
+        let Some(open) = rest.find("{{") else {
+            text.push_str(rest);
+            *rest = "";
+            break;
+        };
+        if rest[..open].ends_with('\\') {
+            text.push_str(&rest[..open - 1]);
+            text.push_str("{{");
+            *rest = &rest[open + 2..];
+            continue;
+        }
+        text.push_str(&rest[..open]);
+        let after = &rest[open + 2..];
+        let close = after
+            .find("}}")
+            .ok_or_else(|| "unclosed `{{`".to_string())?;
+        let tag = after[..close].trim();
+        *rest = &after[close + 2..];
+        if !text.is_empty() {
+            nodes.push(Node::Text(core::mem::take(&mut text)));
+        }
+        if let Some(condition) = tag.strip_prefix("#if ") {
+            let condition = parse_condition(condition.trim())?;
+            let (then, end) = parse_block(rest)?;
+            let otherwise = match end {
+                BlockEnd::Else => match parse_block(rest)? {
+                    (otherwise, BlockEnd::EndIf) => otherwise,
+                    (_, BlockEnd::Else) => return Err("duplicate `{{else}}`".into()),
+                    (_, BlockEnd::Eof) => return Err("unclosed `{{#if}}`".into()),
+                },
+                BlockEnd::EndIf => Vec::new(),
+                BlockEnd::Eof => return Err("unclosed `{{#if}}`".into()),
+            };
+            nodes.push(Node::If {
+                condition,
+                then,
+                otherwise,
+            });
+        } else if tag == "else" {
+            return Ok((nodes, BlockEnd::Else));
+        } else if tag == "/if" {
+            return Ok((nodes, BlockEnd::EndIf));
+        } else if tag.is_empty() || tag.starts_with(['#', '/']) {
+            return Err(format!("unsupported tag `{{{{{tag}}}}}`"));
+        } else {
+            nodes.push(Node::Answer(tag.to_string()));
+        }
+    }
+    if !text.is_empty() {
+        nodes.push(Node::Text(text));
+    }
+    Ok((nodes, BlockEnd::Eof))
+}
+
+/// Parses `name`, `name == literal` or `name != literal`; the name ends at the first space or
+/// operator character, so operators inside a quoted literal are left alone.
+fn parse_condition(condition: &str) -> Result<Condition, String> {
+    let end = condition
+        .find(|c: char| c.is_whitespace() || c == '=' || c == '!')
+        .unwrap_or(condition.len());
+    let (name, rest) = condition.split_at(end);
+    if name.is_empty() {
+        return Err("`{{#if}}` needs a condition".into());
+    }
+    let name = name.to_string();
+    let rest = rest.trim_start();
+    if rest.is_empty() {
+        return Ok(Condition::Truthy(name));
+    }
+    if let Some(literal) = rest.strip_prefix("==") {
+        return Ok(Condition::Equals(name, parse_literal(literal.trim())?));
+    }
+    if let Some(literal) = rest.strip_prefix("!=") {
+        return Ok(Condition::NotEquals(name, parse_literal(literal.trim())?));
+    }
+    Err(format!(
+        "expected `==` or `!=` after `{name}` in `{condition}`"
+    ))
+}
+
+fn parse_literal(literal: &str) -> Result<Value, String> {
+    if let Some(text) = literal
+        .strip_prefix('"')
+        .and_then(|text| text.strip_suffix('"'))
+    {
+        return Ok(Value::Text(text.to_string()));
+    }
+    match literal {
+        "true" => Ok(Value::Bool(true)),
+        "false" => Ok(Value::Bool(false)),
+        _ => literal
+            .parse::<i64>()
+            .map(|number| Value::Integer(number.into()))
+            .or_else(|_| literal.parse::<f64>().map(Value::Float))
+            .map_err(|_| format!("invalid literal `{literal}`")),
+    }
+}
+
+fn render_nodes(nodes: &[Node], answers: &Value, out: &mut String) -> Result<(), String> {
+    for node in nodes {
+        match node {
+            Node::Text(text) => out.push_str(text),
+            Node::Answer(name) => {
+                let value = qa_predicate::find(name, answers)
+                    .ok_or_else(|| format!("`{name}` has no answer"))?;
+                out.push_str(&format_answer(name, value)?);
+            }
+            Node::If {
+                condition,
+                then,
+                otherwise,
+            } => {
+                let holds =
+                    match condition {
+                        Condition::Truthy(name) => {
+                            qa_predicate::find(name, answers).is_some_and(truthy)
+                        }
+                        Condition::Equals(name, literal) => qa_predicate::find(name, answers)
+                            .is_some_and(|value| same(value, literal)),
+                        Condition::NotEquals(name, literal) => !qa_predicate::find(name, answers)
+                            .is_some_and(|value| same(value, literal)),
+                    };
+                render_nodes(if holds { then } else { otherwise }, answers, out)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn truthy(value: &Value) -> bool {
+    match value {
+        Value::Bool(flag) => *flag,
+        Value::Integer(number) => i128::from(*number) != 0,
+        Value::Float(number) => *number != 0.0,
+        Value::Text(text) => !text.is_empty(),
+        Value::Array(items) => !items.is_empty(),
+        Value::Map(entries) => !entries.is_empty(),
+        Value::Null => false,
+        _ => true,
+    }
+}
+
+/// Compares an answer with a literal, treating integers and integral floats as equal.
+fn same(value: &Value, literal: &Value) -> bool {
+    match (value, literal) {
+        (Value::Integer(number), Value::Float(float))
+        | (Value::Float(float), Value::Integer(number)) => i128::from(*number) as f64 == *float,
+        _ => value == literal,
+    }
+}
+
+fn format_answer(name: &str, value: &Value) -> Result<String, String> {
+    Ok(match value {
+        Value::Text(text) => text.clone(),
+        Value::Bool(flag) => flag.to_string(),
+        Value::Integer(number) => i128::from(*number).to_string(),
+        Value::Float(number) => number.to_string(),
+        Value::Array(items) => items
+            .iter()
+            .map(|item| match item {
+                Value::Array(_) | Value::Map(_) => Err(format!(
+                    "`{name}` contains a nested value that cannot be inserted"
+                )),
+                item => format_answer(name, item),
+            })
+            .collect::<Result<Vec<_>, _>>()?
+            .join(", "),
+        _ => return Err(format!("`{name}` is not a scalar answer")),
+    })
+}
@@ -0,0 +1,260 @@
+use ciborium::value::Value;
+use greentic_types::adapters::registry::{
+    AdapterError, AdapterRegistry, FLOW_SCHEMA_VERSION, latest_version,
+};
+use greentic_types::schemas::common::schema_ir::SchemaIr;
+use greentic_types::schemas::component::v0_6_0::{ComponentDescribe, ComponentQaSpec, QaMode};
+use greentic_types::schemas::pack::v0_6_0::{PackQaSpec, QaMode as PackQaMode};
+use semver::Version;
+
+fn version(text: &str) -> Version {
+    Version::parse(text).expect("version")
+}
+
+fn tagged(value: Value, key: &str) -> Value {
+    let Value::Map(mut entries) = value else {
+        panic!("expected map");
+    };
+    entries.push((Value::Text(key.into()), Value::Bool(true)));
+    Value::Map(entries)
+}
+
+#[test]
+fn composes_registered_hops_into_the_shortest_chain() {
+    let mut registry = AdapterRegistry::new();
+    registry
+        .register(
+            "example.doc",
+            version("1.0.0"),
+            version("1.1.0"),
+            |value, warnings| {
+                warnings.push("renamed `a` to `b`".into());
+                Ok(tagged(value, "v1_1"))
+            },
+        )
+        .register(
+            "example.doc",
+            version("1.1.0"),
+            version("2.0.0"),
+            |value, _| Ok(tagged(value, "v2")),
+        )
+        .register("example.doc", version("2.0.0"), version("3.0.0"), |_, _| {
+            Err("unsupported".into())
+        });
+
+    assert_eq!(
+        registry.path("example.doc", &version("1.0.0"), &version("2.0.0")),
+        Some(vec![
+            (version("1.0.0"), version("1.1.0")),
+            (version("1.1.0"), version("2.0.0")),
+        ])
+    );
+    assert_eq!(
+        registry.path("example.doc", &version("2.0.0"), &version("1.0.0")),
+        None
+    );
+
+    let migration = registry
+        .migrate(
+            "example.doc",
+            &version("1.0.0"),
+            &version("2.0.0"),
+            Value::Map(Vec::new()),
+        )
+        .expect("migrate");
+    assert_eq!(migration.schema_id, "example.doc@2.0.0");
+    assert_eq!(migration.hops.len(), 2);
+    assert_eq!(
+        migration.warnings().collect::<Vec<_>>(),
+        ["renamed `a` to `b`"]
+    );
+    assert_eq!(
+        migration.value,
+        Value::Map(vec![
+            (Value::Text("v1_1".into()), Value::Bool(true)),
+            (Value::Text("v2".into()), Value::Bool(true)),
+        ])
+    );
+
+    let err = registry
+        .migrate(
+            "example.doc",
+            &version("1.0.0"),
+            &version("3.0.0"),
+            Value::Map(Vec::new()),
+        )
+        .expect_err("last hop fails");
+    assert!(matches!(
+        err,
+        AdapterError::Hop { ref from, ref message, .. } if from == "2.0.0" && message == "unsupported"
+    ));
+}
+
+#[test]
+fn upgrades_legacy_component_qa_to_latest() {
+    let legacy: Value =
+        serde_json::from_str(include_str!("../fixtures/legacy/component_v0_5_0_qa.json"))
+            .expect("legacy fixture");
+
+    let migration = AdapterRegistry::builtin()
+        .upgrade_to_latest("greentic.component.qa@0.5.0", legacy)
+        .expect("upgrade");
+    assert_eq!(migration.schema_id, "greentic.component.qa@0.6.0");
+    assert_eq!(migration.warnings().count(), 1);
+
+    let spec = migration
+        .to_cbor()
+        .expect("encode")
+        .decode::<ComponentQaSpec>()
+        .expect("decode");
+    assert_eq!(spec.mode, QaMode::Setup);
+    assert_eq!(spec.questions[0].id, "api_key");
+}
+
+#[test]
+fn upgrades_legacy_qa_to_pack_qa() {
+    let legacy: Value =
+        serde_json::from_str(include_str!("../fixtures/legacy/component_v0_5_0_qa.json"))
+            .expect("legacy fixture");
+
+    let migration = AdapterRegistry::builtin()
+        .upgrade_to_latest("greentic.pack.qa@0.5.0", legacy)
+        .expect("upgrade");
+    assert_eq!(migration.schema_id, "greentic.pack.qa@0.6.0");
+    let spec = migration
+        .to_cbor()
+        .expect("encode")
+        .decode::<PackQaSpec>()
+        .expect("decode");
+    assert_eq!(spec.mode, PackQaMode::Setup);
+    assert_eq!(spec.questions[0].id, "api_key");
+}
+
+#[test]
+fn latest_documents_pass_through_and_bad_ids_are_rejected() {
+    let registry = AdapterRegistry::builtin();
+    assert_eq!(
+        latest_version("greentic.pack.describe").expect("pack describe"),
+        version("0.6.0")
+    );
+
+    let describe = Value::Map(vec![(Value::Text("id".into()), Value::Text("demo".into()))]);
+    let migration = registry
+        .upgrade_to_latest("greentic.pack.describe@0.6.0", describe.clone())
+        .expect("no-op upgrade");
+    assert!(migration.hops.is_empty());
+    assert_eq!(migration.value, describe);
+
+    assert!(matches!(
+        registry.upgrade_to_latest("greentic.pack.describe", Value::Null),
+        Err(AdapterError::InvalidSchemaId(_))
+    ));
+    assert!(matches!(
+        registry.upgrade_to_latest("example.unknown@1.0.0", Value::Null),
+        Err(AdapterError::UnknownSchema(_))
+    ));
+    // Manifests and flows are versioned by `schema_version` strings, not `SCHEMAS` ids.
+    assert!(matches!(
+        registry.upgrade_to_latest("greentic.flow@1.0.0", Value::Null),
+        Err(AdapterError::UnknownSchema(_))
+    ));
+    assert!(matches!(
+        registry.upgrade_to_latest("greentic.pack.describe@0.4.0", Value::Null),
+        Err(AdapterError::NoPath { .. })
+    ));
+}
+
+#[test]
+fn upgrades_legacy_component_manifests_to_describe() {
+    let legacy: Value = serde_json::from_str(
+        r#"{
+            "id": "greentic.demo.weather",
+            "version": "0.3.0",
+            "supports": ["messaging"],
+            "world": "greentic:component/world",
+            "profiles": {},
+            "capabilities": { "wasi": {}, "host": {} },
+            "operations": [{
+                "name": "forecast",
+                "input_schema": {
+                    "type": "object",
+                    "properties": { "city": { "type": "string" } },
+                    "required": ["city"]
+                },
+                "output_schema": { "type": "string" }
+            }]
+        }"#,
+    )
+    .expect("legacy manifest");
+
+    let migration = AdapterRegistry::builtin()
+        .upgrade_to_latest("greentic.component.describe@0.5.0", legacy)
+        .expect("upgrade");
+    assert_eq!(migration.schema_id, "greentic.component.describe@0.6.0");
+    assert!(
+        migration
+            .warnings()
+            .any(|warning| warning.contains("assumed `runtime`"))
+    );
+
+    let describe = migration
+        .to_cbor()
+        .expect("encode")
+        .decode::<ComponentDescribe>()
+        .expect("decode");
+    assert_eq!(describe.info.id, "greentic.demo.weather");
+    assert_eq!(describe.info.version, "0.3.0");
+    assert_eq!(describe.operations[0].id, "forecast");
+    assert!(matches!(
+        describe.operations[0].input.schema,
+        SchemaIr::Object { ref required, .. } if required == &["city".to_string()]
+    ));
+    assert!(!describe.operations[0].schema_hash.is_empty());
+    assert_eq!(
+        describe.metadata["world"],
+        Value::Text("greentic:component/world".into())
+    );
+}
+
+#[test]
+fn pack_manifests_and_flows_upgrade_by_schema_version() {
+    let document = |schema_version: &str| {
+        Value::Map(vec![
+            (
+                Value::Text("schema_version".into()),
+                Value::Text(schema_version.into()),
+            ),
+            (Value::Text("id".into()), Value::Text("demo".into())),
+        ])
+    };
+    let mut registry = AdapterRegistry::new();
+    registry.register("pack", version("0.0.0"), version("1.0.0"), |value, _| {
+        Ok(tagged(value, "v1"))
+    });
+
+    let migration = registry
+        .upgrade_pack_manifest(document("pack-v0"))
+        .expect("pack-v0");
+    assert_eq!(migration.schema_id, "pack-v1");
+    assert_eq!(migration.hops.len(), 1);
+    assert_eq!(migration.value, tagged(document("pack-v1"), "v1"));
+
+    let flow = registry
+        .upgrade_flow(document(FLOW_SCHEMA_VERSION))
+        .expect("current flow");
+    assert!(flow.hops.is_empty());
+    assert_eq!(flow.value, document(FLOW_SCHEMA_VERSION));
+
+    assert!(matches!(
+        registry.upgrade_flow(document("flow-v0")),
+        Err(AdapterError::NoPath { ref schema, .. }) if schema == "flow"
+    ));
+    assert!(matches!(
+        registry.upgrade_pack_manifest(document("flow-v1")),
+        Err(AdapterError::InvalidSchemaVersion { ref family, .. }) if family == "pack"
+    ));
+    assert!(matches!(
+        registry.upgrade_pack_manifest(Value::Map(Vec::new())),
+        Err(AdapterError::MissingSchemaVersion)
+    ));
+}
@@ -0,0 +1,54 @@
+//! QA spec builders shared by the QA integration tests.
+#![allow(dead_code)]
+
+use std::collections::BTreeMap;
+
+use ciborium::value::Value;
+use greentic_types::cbor::canonical;
+use greentic_types::schemas::component::v0_6_0::{
+    ChoiceOption, ComponentQaSpec, QaMode, Question, QuestionKind,
+};
+use greentic_types::{CborBytes, I18nText};
+
+/// Question labelled `qa.<id>` without conditions or a default.
+pub fn question(id: &str, kind: QuestionKind, required: bool) -> Question {
+    Question {
+        id: id.into(),
+        label: I18nText::new(format!("qa.{id}"), None),
+        help: None,
+        error: None,
+        kind,
+        required,
+        default: None,
+        visible_if: None,
+        required_if: None,
+    }
+}
+
+/// Choice option labelled `qa.option.<value>`.
+pub fn option(value: &str, fallback: Option<&str>) -> ChoiceOption {
+    ChoiceOption {
+        value: value.into(),
+        label: I18nText::new(format!("qa.option.{value}"), fallback.map(str::to_string)),
+    }
+}
+
+/// Spec titled `qa.title` with no description or spec-level defaults.
+pub fn spec(mode: QaMode, questions: Vec<Question>) -> ComponentQaSpec {
+    ComponentQaSpec {
+        mode,
+        title: I18nText::new("qa.title", None),
+        description: None,
+        questions,
+        defaults: BTreeMap::new(),
+    }
+}
+
+/// Canonical CBOR answer map.
+pub fn answers(entries: &[(&str, Value)]) -> CborBytes {
+    let map: BTreeMap<String, Value> = entries
+        .iter()
+        .map(|(key, value)| (key.to_string(), value.clone()))
+        .collect();
+    CborBytes::new(canonical::to_canonical_cbor_allow_floats(&map).expect("encode answers"))
+}
@@ -0,0 +1,267 @@
+#![cfg(feature = "serde")]
+
+use std::collections::BTreeMap;
+
+use greentic_types::pack::extensions::component_sources::{
+    ArtifactLocationV1, ComponentSourceEntryV1, ComponentSourcesV1, ResolvedComponentV1,
+};
+use greentic_types::{
+    ComponentCapabilities, ComponentManifest, ComponentProfiles, ComponentSourceRefV1,
+    FLOW_RESOLVE_SCHEMA_VERSION, Flow, FlowComponentRef, FlowId, FlowKind, FlowMetadata,
+    FlowResolveV1, InputMapping, Node, NodeResolveV1, OutputMapping, PackId, PackKind,
+    PackManifest, PackSignatures, ResolveModeV1, ResourceHints, Routing, TelemetryHints,
+    check_flow_resolve_consistency, check_flow_sidecars, regenerate_flow_resolve_summary,
+    sidecar_path_for_flow, write_flow_resolve, write_regenerated_flow_resolve_summary,
+};
+use indexmap::IndexMap;
+use semver::Version;
+use serde_json::Value;
+
+const DIGEST: &str = "sha256:deadbeef";
+
+fn node(id: &str, component_id: &str) -> Node {
+    Node {
+        id: id.parse().unwrap(),
+        component: FlowComponentRef {
+            id: component_id.parse().unwrap(),
+            pack_alias: None,
+            operation: None,
+        },
+        input: InputMapping {
+            mapping: Value::Null,
+        },
+        output: OutputMapping {
+            mapping: Value::Null,
+        },
+        routing: Routing::End,
+        telemetry: TelemetryHints::default(),
+    }
+}
+
+fn flow(node_ids: &[&str]) -> Flow {
+    let mut nodes: IndexMap<_, _, greentic_types::flow::FlowHasher> = IndexMap::default();
+    for id in node_ids {
+        nodes.insert(id.parse().unwrap(), node(id, "greentic.demo.component"));
+    }
+    Flow {
+        schema_version: "flow-v1".into(),
+        id: FlowId::new("main").unwrap(),
+        kind: FlowKind::Messaging,
+        entrypoints: BTreeMap::new(),
+        nodes,
+        metadata: FlowMetadata::default(),
+    }
+}
+
+fn resolve(node_ids: &[&str], digest: &str) -> FlowResolveV1 {
+    FlowResolveV1 {
+        schema_version: FLOW_RESOLVE_SCHEMA_VERSION,
+        flow: "main.ygtc".into(),
+        nodes: node_ids
+            .iter()
+            .map(|id| {
+                (
+                    id.to_string(),
+                    NodeResolveV1 {
+                        source: ComponentSourceRefV1::Oci {
+                            r#ref: "ghcr.io/greentic/demo/component:1.2.3".into(),
+                            digest: Some(digest.into()),
+                        },
+                        mode: Some(ResolveModeV1::Pinned),
+                    },
+                )
+            })
+            .collect(),
+    }
+}
+
+fn manifest() -> PackManifest {
+    let mut manifest = PackManifest {
+        schema_version: "pack-v1".into(),
+        pack_id: PackId::new("dev.local.resolve").unwrap(),
+        name: None,
+        version: Version::parse("0.1.0").unwrap(),
+        kind: PackKind::Application,
+        publisher: "tests".into(),
+        components: vec![ComponentManifest {
+            id: "greentic.demo.component".parse().unwrap(),
+            version: Version::parse("1.2.3").unwrap(),
+            supports: vec![FlowKind::Messaging],
+            world: "greentic:component/world".into(),
+            profiles: ComponentProfiles::default(),
+            capabilities: ComponentCapabilities::default(),
+            configurators: None,
+            operations: Vec::new(),
+            config_schema: None,
+            resources: ResourceHints::default(),
+            dev_flows: BTreeMap::new(),
+        }],
+        flows: Vec::new(),
+        dependencies: Vec::new(),
+        capabilities: Vec::new(),
+        secret_requirements: Vec::new(),
+        signatures: PackSignatures::default(),
+        bootstrap: None,
+        extensions: None,
+    };
+    manifest
+        .set_component_sources_v1(ComponentSourcesV1::new(vec![ComponentSourceEntryV1 {
+            name: "demo".into(),
+            component_id: Some("greentic.demo.component".parse().unwrap()),
+            source: "oci://ghcr.io/greentic/demo/component:1.2.3"
+                .parse()
+                .unwrap(),
+            resolved: ResolvedComponentV1 {
+                digest: DIGEST.into(),
+                signature: None,
+                signed_by: None,
+            },
+            artifact: ArtifactLocationV1::Remote,
+            licensing_hint: None,
+            metering_hint: None,
+        }]))
+        .expect("set component sources");
+    manifest
+}
+
+fn codes(report: &greentic_types::ValidationReport) -> Vec<&str> {
+    report
+        .diagnostics
+        .iter()
+        .map(|diag| diag.code.as_str())
+        .collect()
+}
+
+#[test]
+fn consistent_sidecars_produce_empty_report() {
+    let flow = flow(&["fetch", "reply"]);
+    let resolve = resolve(&["fetch", "reply"], DIGEST);
+    let manifest = manifest();
+    let summary = regenerate_flow_resolve_summary(&flow, &resolve, &manifest).expect("summary");
+
+    let report = check_flow_resolve_consistency(&flow, &resolve, Some(&summary), &manifest);
+    assert!(report.diagnostics.is_empty(), "{:?}", report.diagnostics);
+    assert_eq!(report.pack_id, Some(manifest.pack_id.clone()));
+
+    let node = &summary.nodes["fetch"];
+    assert_eq!(node.digest, DIGEST);
+    let metadata = node.manifest.as_ref().expect("manifest metadata");
+    assert_eq!(metadata.world, "greentic:component/world");
+    assert_eq!(metadata.version, Version::parse("1.2.3").unwrap());
+}
+
+#[test]
+fn reports_unresolved_and_stale_nodes() {
+    let flow = flow(&["fetch", "reply"]);
+    let manifest = manifest();
+    let summary =
+        regenerate_flow_resolve_summary(&flow, &resolve(&["fetch", "reply"], DIGEST), &manifest)
+            .expect("summary");
+    let resolve = resolve(&["fetch", "deleted"], DIGEST);
+
+    let report = check_flow_resolve_consistency(&flow, &resolve, Some(&summary), &manifest);
+    assert!(report.has_errors());
+    let codes = codes(&report);
+    assert!(codes.contains(&"FLOW_RESOLVE_NODE_UNRESOLVED"));
+    assert!(codes.contains(&"FLOW_RESOLVE_STALE_NODE"));
+    let unresolved = report
+        .diagnostics
+        .iter()
+        .find(|diag| diag.code == "FLOW_RESOLVE_NODE_UNRESOLVED")
+        .expect("unresolved diagnostic");
+    assert_eq!(unresolved.path.as_deref(), Some("nodes.reply"));
+}
+
+#[test]
+fn reports_digest_drift_against_component_sources() {
+    let flow = flow(&["fetch"]);
+    let manifest = manifest();
+    let resolve = resolve(&["fetch"], "sha256:cafebabe");
+    let summary = regenerate_flow_resolve_summary(&flow, &resolve, &manifest).expect("summary");
+
+    let report = check_flow_resolve_consistency(&flow, &resolve, Some(&summary), &manifest);
+    assert!(codes(&report).contains(&"FLOW_RESOLVE_DIGEST_MISMATCH"));
+}
+
+#[test]
+fn reports_summary_drift_and_source_mismatch() {
+    let flow = flow(&["fetch"]);
+    let manifest = manifest();
+    let resolve = resolve(&["fetch"], DIGEST);
+    let mut summary = regenerate_flow_resolve_summary(&flow, &resolve, &manifest).expect("summary");
+    summary.nodes.get_mut("fetch").unwrap().digest = "sha256:0000".into();
+    summary
+        .nodes
+        .insert("removed".into(), summary.nodes["fetch"].clone());
+
+    let mut drifted = resolve.clone();
+    drifted.nodes.get_mut("fetch").unwrap().source = ComponentSourceRefV1::Oci {
+        r#ref: "ghcr.io/greentic/other/component:1.0.0".into(),
+        digest: Some(DIGEST.into()),
+    };
+
+    let report = check_flow_resolve_consistency(&flow, &drifted, Some(&summary), &manifest);
+    let codes = codes(&report);
+    assert!(codes.contains(&"FLOW_RESOLVE_COMPONENT_SOURCE_MISMATCH"));
+    assert!(codes.contains(&"FLOW_RESOLVE_SUMMARY_SOURCE_MISMATCH"));
+    assert!(codes.contains(&"FLOW_RESOLVE_DIGEST_MISMATCH"));
+    assert!(codes.contains(&"FLOW_RESOLVE_SUMMARY_STALE_NODE"));
+}
+
+#[test]
+fn regenerate_is_deterministic_and_drops_stale_nodes() {
+    let flow = flow(&["reply", "fetch"]);
+    let manifest = manifest();
+    let resolve = resolve(&["fetch", "reply", "deleted"], DIGEST);
+
+    let first = regenerate_flow_resolve_summary(&flow, &resolve, &manifest).expect("first");
+    let second = regenerate_flow_resolve_summary(&flow, &resolve, &manifest).expect("second");
+    assert_eq!(
+        serde_json::to_string(&first).unwrap(),
+        serde_json::to_string(&second).unwrap()
+    );
+    assert_eq!(
+        first.nodes.keys().collect::<Vec<_>>(),
+        vec!["fetch", "reply"]
+    );
+}
+
+#[test]
+fn regenerate_uses_component_sources_digest_for_tracked_nodes() {
+    let flow = flow(&["fetch"]);
+    let manifest = manifest();
+    let mut resolve = resolve(&["fetch"], DIGEST);
+    resolve.nodes.get_mut("fetch").unwrap().source = ComponentSourceRefV1::Oci {
+        r#ref: "ghcr.io/greentic/demo/component:1.2.3".into(),
+        digest: None,
+    };
+
+    let summary = regenerate_flow_resolve_summary(&flow, &resolve, &manifest).expect("summary");
+    assert_eq!(summary.nodes["fetch"].digest, DIGEST);
+}
+
+#[test]
+fn sidecar_helpers_check_and_rewrite_summary_on_disk() {
+    let dir = std::env::temp_dir().join(format!(
+        "greentic-types-resolve-check-{}",
+        std::process::id()
+    ));
+    std::fs::create_dir_all(&dir).expect("temp dir");
+    let flow_path = dir.join("main.ygtc");
+    let flow = flow(&["fetch"]);
+    let manifest = manifest();
+    write_flow_resolve(
+        &sidecar_path_for_flow(&flow_path),
+        &resolve(&["fetch"], DIGEST),
+    )
+    .expect("write resolve");
+
+    let report = check_flow_sidecars(&flow_path, &flow, &manifest).expect("check without summary");
+    assert!(report.diagnostics.is_empty(), "{:?}", report.diagnostics);
+
+    write_regenerated_flow_resolve_summary(&flow_path, &flow, &manifest).expect("write summary");
+    let report = check_flow_sidecars(&flow_path, &flow, &manifest).expect("check with summary");
+    assert!(report.diagnostics.is_empty(), "{:?}", report.diagnostics);
+
+    std::fs::remove_dir_all(&dir).ok();
+}
@@ -0,0 +1,145 @@
+use std::collections::BTreeMap;
+
+use greentic_types::cbor::canonical;
+use greentic_types::schemas::component::v0_6_0::{ComponentQaSpec, QaMode};
+use greentic_types::{
+    CborBytes, I18nText, MessageCatalog, MessageCatalogError, QaForm, QaFormRenderer, Severity,
+    bundle_pack_catalogs,
+};
+
+fn catalog() -> MessageCatalog {
+    MessageCatalog::from_json(
+        r#"{
+            "default_locale": "en",
+            "locales": {
+                "en": { "qa.title": "Setup", "qa.name": "Name", "qa.color": "Color" },
+                "en-gb": { "qa.color": "Colour" },
+                "de": { "qa.title": "Einrichtung" }
+            }
+        }"#,
+    )
+    .expect("catalog")
+}
+
+#[test]
+fn lookups_follow_bcp47_fallback_chains() {
+    let catalog = catalog();
+    assert!(catalog.locales.contains_key("en-GB"));
+    assert_eq!(
+        catalog.fallback_chain("de-Latn-CH"),
+        ["de-Latn-CH", "de-Latn", "de", "en"]
+    );
+    assert_eq!(catalog.fallback_chain("en_gb"), ["en-GB", "en"]);
+
+    assert_eq!(catalog.message("en-GB", "qa.color"), Some("Colour"));
+    assert_eq!(catalog.message("en-US", "qa.color"), Some("Color"));
+    assert_eq!(catalog.message("de-CH", "qa.title"), Some("Einrichtung"));
+    assert_eq!(catalog.message("de-CH", "qa.name"), Some("Name"));
+    assert_eq!(catalog.message("de", "qa.missing"), None);
+
+    let inline = I18nText::new("qa.missing", Some("Inline".into()));
+    assert_eq!(catalog.resolve("de", &inline), "Inline");
+    assert_eq!(
+        catalog.resolve("fr", &I18nText::new("qa.title", None)),
+        "Setup"
+    );
+    assert_eq!(
+        catalog.resolve("fr", &I18nText::new("qa.bare", None)),
+        "qa.bare"
+    );
+    assert_eq!(
+        catalog.missing_keys("de", ["qa.title", "qa.name", "qa.bare"]),
+        ["qa.bare"]
+    );
+
+    // The QA form renderer follows the same chain.
+    let spec = ComponentQaSpec {
+        mode: QaMode::Setup,
+        title: I18nText::new("qa.title", None),
+        description: None,
+        questions: Vec::new(),
+        defaults: BTreeMap::new(),
+    };
+    let rendered = QaFormRenderer::new(&spec)
+        .with_message_catalog(&catalog, "de-AT")
+        .render();
+    let QaForm::AdaptiveCard(card) = rendered.form else {
+        panic!("expected adaptive card");
+    };
+    assert_eq!(card["body"][0]["text"], "Einrichtung");
+}
+
+#[test]
+fn catalogs_roundtrip_through_canonical_cbor_and_reject_bad_locales() {
+    let catalog = catalog();
+    let bytes = catalog.to_cbor().expect("encode");
+    bytes.ensure_canonical().expect("canonical");
+    assert_eq!(MessageCatalog::from_cbor(&bytes).expect("decode"), catalog);
+
+    // CBOR written by other tools is normalized on load.
+    let raw = CborBytes::new(
+        canonical::to_canonical_cbor(&BTreeMap::from([(
+            "locales",
+            BTreeMap::from([("pt-br", BTreeMap::from([("k", "v")]))]),
+        )]))
+        .expect("encode raw"),
+    );
+    let loaded = MessageCatalog::from_cbor(&raw).expect("decode raw");
+    assert_eq!(loaded.message("pt-BR", "k"), Some("v"));
+
+    assert!(matches!(
+        MessageCatalog::from_json(r#"{ "locales": { "en-gb": {}, "en-GB": {} } }"#),
+        Err(MessageCatalogError::DuplicateLocale { .. })
+    ));
+    assert!(matches!(
+        MessageCatalog::new().insert("not a tag!", "k", "v"),
+        Err(MessageCatalogError::Locale { .. })
+    ));
+}
+
+#[test]
+fn pack_catalogs_win_over_component_catalogs() {
+    let mut pack = MessageCatalog::new()
+        .with_default_locale("en")
+        .expect("default");
+    pack.insert("en", "qa.title", "Weather pack")
+        .expect("insert");
+    let mut forecast = MessageCatalog::new();
+    forecast
+        .insert("en", "qa.title", "Forecast")
+        .expect("insert");
+    forecast.insert("en", "qa.city", "City").expect("insert");
+    forecast.insert("fr", "qa.units", "Unités").expect("insert");
+    let mut alerts = MessageCatalog::new();
+    alerts.insert("en", "qa.city", "Town").expect("insert");
+
+    let (bundle, diagnostics) =
+        bundle_pack_catalogs(&pack, &[("forecast", &forecast), ("alerts", &alerts)]);
+    assert_eq!(bundle.default_locale.as_deref(), Some("en"));
+    assert_eq!(bundle.message("en", "qa.title"), Some("Weather pack"));
+    assert_eq!(bundle.message("en", "qa.city"), Some("City"));
+    assert_eq!(bundle.message("fr-CA", "qa.units"), Some("Unités"));
+
+    let codes: Vec<(&str, Severity)> = diagnostics
+        .iter()
+        .map(|diagnostic| (diagnostic.code.as_str(), diagnostic.severity))
+        .collect();
+    assert_eq!(
+        codes,
+        [
+            ("I18N_CATALOG_CONFLICT", Severity::Warn),
+            ("I18N_CATALOG_MISSING_DEFAULT_KEY", Severity::Warn),
+        ]
+    );
+    assert!(diagnostics[0].message.contains("keeping `forecast`"));
+    assert_eq!(diagnostics[1].data["key"], "qa.units");
+
+    let (_, diagnostics) = bundle_pack_catalogs(
+        &MessageCatalog::new()
+            .with_default_locale("nl")
+            .expect("default"),
+        &[("forecast", &forecast)],
+    );
+    assert_eq!(diagnostics[0].code, "I18N_CATALOG_DEFAULT_MISSING");
+    assert_eq!(diagnostics[0].severity, Severity::Error);
+}
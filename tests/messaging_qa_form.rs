@@ -0,0 +1,326 @@
+mod common;
+
+use std::collections::BTreeMap;
+
+use ciborium::value::Value;
+use greentic_types::cbor::canonical;
+use greentic_types::messaging::qa_form;
+use greentic_types::schemas::component::v0_6_0::{
+    ChoiceOption, ComponentQaSpec, QaMode, QaPredicate, Question, QuestionKind,
+};
+use greentic_types::{
+    AdaptiveCardVersion, AnswerErrorKind, CanonicalPolicy, CapabilityProfile, CborBytes,
+    MessageCatalog, QaForm, QaFormRenderer, SchemaSource, SecretKey, SecretRequirement, Tier,
+    parse_qa_submit, validate_answers,
+};
+use serde_json::json;
+
+fn question(id: &str, kind: QuestionKind, required: bool) -> Question {
+    let mut question = common::question(id, kind, required);
+    question.label.fallback = Some(id.to_uppercase());
+    question
+}
+
+fn options(values: &[&str]) -> Vec<ChoiceOption> {
+    values
+        .iter()
+        .map(|value| common::option(value, Some(&value.to_uppercase())))
+        .collect()
+}
+
+fn spec() -> ComponentQaSpec {
+    let mut cert = question("cert", QuestionKind::Text, true);
+    cert.visible_if = Some(QaPredicate::Equals {
+        question: "tls".into(),
+        value: Value::Bool(true),
+    });
+    let mut token = SecretRequirement::default();
+    token.key = SecretKey::new("api_token").expect("secret key");
+    let mut spec = common::spec(
+        QaMode::Setup,
+        vec![
+            question("name", QuestionKind::Text, true),
+            question("tls", QuestionKind::Bool, false),
+            cert,
+            question(
+                "region",
+                QuestionKind::Choice {
+                    options: options(&["eu", "us"]),
+                },
+                false,
+            ),
+            question(
+                "features",
+                QuestionKind::MultiSelect {
+                    options: options(&["logs", "metrics"]),
+                    min_selected: None,
+                    max_selected: None,
+                },
+                false,
+            ),
+            question(
+                "db",
+                QuestionKind::Group {
+                    questions: vec![
+                        question("host", QuestionKind::Text, true),
+                        question(
+                            "port",
+                            QuestionKind::Integer {
+                                min: Some(1),
+                                max: Some(65535),
+                            },
+                            false,
+                        ),
+                    ],
+                },
+                false,
+            ),
+            question("token", QuestionKind::Secret { requirement: token }, false),
+            question(
+                "replicas",
+                QuestionKind::List {
+                    questions: vec![question("host", QuestionKind::Text, true)],
+                    min_items: None,
+                    max_items: None,
+                },
+                false,
+            ),
+        ],
+    );
+    spec.title.fallback = Some("Setup".into());
+    spec
+}
+
+fn card_json(form: &QaForm) -> &serde_json::Value {
+    match form {
+        QaForm::AdaptiveCard(card) => card,
+        other => panic!("expected adaptive card, got {other:?}"),
+    }
+}
+
+fn input_ids(card: &serde_json::Value) -> Vec<String> {
+    fn walk(elements: &serde_json::Value, out: &mut Vec<String>) {
+        for element in elements.as_array().into_iter().flatten() {
+            if let Some(id) = element["id"].as_str() {
+                out.push(id.into());
+            }
+            walk(&element["items"], out);
+        }
+    }
+    let mut out = Vec::new();
+    walk(&card["body"], &mut out);
+    out
+}
+
+#[test]
+fn renders_adaptive_card_with_localized_inputs() {
+    let spec = spec();
+    let catalog = MessageCatalog::from_locales(
+        BTreeMap::from([(
+            "de".to_string(),
+            BTreeMap::from([
+                ("qa.name".to_string(), "Name".to_string()),
+                ("qa.form.submit".to_string(), "Senden".to_string()),
+            ]),
+        )]),
+        None,
+    )
+    .expect("catalog");
+    let rendered = QaFormRenderer::new(&spec)
+        .with_message_catalog(&catalog, "de-CH")
+        .render();
+    assert_eq!(rendered.diagnostics.tier, Some(Tier::TierA));
+    assert!(rendered.diagnostics.warnings[0].contains("`replicas`"));
+
+    let card = card_json(&rendered.form);
+    assert_eq!(card["version"], "1.5");
+    assert_eq!(card["actions"][0]["title"], "Senden");
+    // `cert` is hidden until `tls` is answered; secrets never get an input.
+    assert_eq!(
+        input_ids(card),
+        ["name", "tls", "region", "features", "db/host", "db/port"]
+    );
+    let name = &card["body"][1];
+    assert_eq!(name["type"], "Input.Text");
+    assert_eq!(name["label"], "Name");
+    assert_eq!(name["isRequired"], true);
+    assert_eq!(card["body"][4]["isMultiSelect"], true);
+    assert_eq!(card["body"][5]["type"], "Container");
+    assert_eq!(card["body"][5]["items"][2]["max"], 65535);
+
+    let answers = BTreeMap::from([("tls".to_string(), Value::Bool(true))]);
+    let rendered = QaFormRenderer::new(&spec).with_answers(&answers).render();
+    let card = card_json(&rendered.form);
+    assert!(input_ids(card).contains(&"cert".to_string()));
+    assert_eq!(card["body"][2]["value"], "true");
+}
+
+#[test]
+fn degrades_to_older_cards_and_text_prompts() {
+    let spec = spec();
+    let old = CapabilityProfile {
+        max_adaptive_card_version: Some(AdaptiveCardVersion::from("1.2")),
+        ..CapabilityProfile::default()
+    };
+    let rendered = QaFormRenderer::new(&spec).with_profile(&old).render();
+    assert_eq!(rendered.diagnostics.tier, Some(Tier::TierB));
+    let card = card_json(&rendered.form);
+    assert_eq!(card["version"], "1.2");
+    assert_eq!(card["body"][1]["text"], "NAME");
+    assert!(card["body"][2].get("isRequired").is_none());
+
+    let text_only = CapabilityProfile {
+        supports_input_controls: Some(false),
+        supports_markdown: Some(true),
+        ..CapabilityProfile::default()
+    };
+    let rendered = QaFormRenderer::new(&spec).with_profile(&text_only).render();
+    assert_eq!(rendered.diagnostics.tier, Some(Tier::TierD));
+    let QaForm::TextPrompts(prompts) = rendered.form else {
+        panic!("expected text prompts");
+    };
+    let ids: Vec<&str> = prompts
+        .iter()
+        .map(|prompt| prompt.question_id.as_str())
+        .collect();
+    assert_eq!(
+        ids,
+        ["name", "tls", "region", "features", "db/host", "db/port"]
+    );
+    assert_eq!(prompts[0].text, "**NAME** (required)");
+    assert_eq!(
+        prompts[2].text,
+        "**REGION**\n1. EU\n2. US\nReply with a number."
+    );
+}
+
+#[test]
+fn localizes_text_prompts_through_the_catalog() {
+    let spec = spec();
+    let messages = [
+        (qa_form::REQUIRED_KEY, "(Pflichtfeld)"),
+        (qa_form::REPLY_CHOICE_KEY, "Antworte mit einer Nummer."),
+        (qa_form::REPLY_BOOL_KEY, "Antworte mit ja oder nein."),
+        (
+            qa_form::REPLY_INTEGER_RANGE_KEY,
+            "Antworte mit einer ganzen Zahl von {min} bis {max}.",
+        ),
+        (qa_form::CURRENT_KEY, "Aktuell: {value}"),
+        (qa_form::YES_KEY, "ja"),
+    ];
+    let catalog = MessageCatalog::from_locales(
+        BTreeMap::from([(
+            "de".to_string(),
+            messages
+                .iter()
+                .map(|(key, message)| (key.to_string(), message.to_string()))
+                .collect(),
+        )]),
+        None,
+    )
+    .expect("catalog");
+    let text_only = CapabilityProfile {
+        supports_input_controls: Some(false),
+        ..CapabilityProfile::default()
+    };
+    let answers = BTreeMap::from([("tls".to_string(), Value::Bool(true))]);
+    let rendered = QaFormRenderer::new(&spec)
+        .with_profile(&text_only)
+        .with_message_catalog(&catalog, "de")
+        .with_answers(&answers)
+        .render();
+    let QaForm::TextPrompts(prompts) = rendered.form else {
+        panic!("expected text prompts");
+    };
+    let text = |id: &str| {
+        prompts
+            .iter()
+            .find(|prompt| prompt.question_id == id)
+            .map(|prompt| prompt.text.as_str())
+            .expect("prompt")
+    };
+    assert_eq!(text("name"), "NAME (Pflichtfeld)");
+    assert_eq!(text("tls"), "TLS\nAntworte mit ja oder nein.\nAktuell: ja");
+    assert_eq!(
+        text("region"),
+        "REGION\n1. EU\n2. US\nAntworte mit einer Nummer."
+    );
+    assert_eq!(
+        text("db/port"),
+        "PORT\nAntworte mit einer ganzen Zahl von 1 bis 65535."
+    );
+    // Keys missing from the catalog keep their English fallback.
+    assert!(text("features").ends_with("Reply with one or more numbers, separated by commas."));
+}
+
+#[test]
+fn parses_card_submissions_and_text_replies_into_answers() {
+    let spec = spec();
+    let answers = parse_qa_submit(
+        &spec,
+        &json!({
+            "name": "demo",
+            "tls": "false",
+            "cert": "",
+            "region": "us",
+            "features": "logs,metrics",
+            "db/host": "db.local",
+            "db/port": "5432",
+            "action": "submit"
+        }),
+    )
+    .expect("parse card submit");
+    assert_eq!(answers["tls"], Value::Bool(false));
+    assert!(!answers.contains_key("cert"));
+    assert_eq!(
+        answers["features"],
+        Value::Array(vec![
+            Value::Text("logs".into()),
+            Value::Text("metrics".into())
+        ])
+    );
+    assert_eq!(answers["token"], Value::Text("api_token".into()));
+    assert_eq!(
+        answers["db"],
+        Value::Map(vec![
+            (Value::Text("host".into()), Value::Text("db.local".into())),
+            (Value::Text("port".into()), Value::Integer(5432.into())),
+        ])
+    );
+
+    // The parsed answers pass validation against the same spec.
+    let schema = SchemaSource::InlineCbor(CborBytes::new(
+        canonical::to_canonical_cbor(&spec).expect("encode spec"),
+    ));
+    let input = CborBytes::new(canonical::to_canonical_cbor(&answers).expect("encode answers"));
+    validate_answers(&schema, &input, CanonicalPolicy::RequireCanonical).expect("valid answers");
+
+    // Text replies use prompt ids and accept option numbers and yes/no.
+    let answers = parse_qa_submit(
+        &spec,
+        &json!({ "name": "demo", "tls": "yes", "region": "1", "features": "2" }),
+    )
+    .expect("parse text replies");
+    assert_eq!(answers["tls"], Value::Bool(true));
+    assert_eq!(answers["region"], Value::Text("eu".into()));
+    for (reply, expected) in [("on", true), ("Y", true), ("off", false), ("0", false)] {
+        let answers = parse_qa_submit(&spec, &json!({ "name": "demo", "tls": reply }))
+            .expect("parse boolean reply");
+        assert_eq!(answers["tls"], Value::Bool(expected), "{reply}");
+    }
+    assert_eq!(
+        answers["features"],
+        Value::Array(vec![Value::Text("metrics".into())])
+    );
+
+    let errors = parse_qa_submit(&spec, &json!({ "region": "mars", "db/port": "x" }))
+        .expect_err("invalid replies");
+    assert_eq!(errors[0].question_id, "region");
+    assert_eq!(
+        errors[0].kind,
+        AnswerErrorKind::InvalidChoice {
+            value: "mars".into()
+        }
+    );
+    assert_eq!(errors[1].question_id, "db/port");
+}
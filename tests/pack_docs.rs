@@ -0,0 +1,205 @@
+#![cfg(feature = "serde")]
+
+use std::collections::BTreeMap;
+
+use ciborium::value::Value as CborValue;
+use greentic_types::schemas::common::schema_ir::{AdditionalProperties, SchemaIr};
+use greentic_types::schemas::pack::v0_6_0::{ChoiceOption, QaMode, Question, QuestionKind};
+use greentic_types::{
+    ComponentCapabilities, ComponentDescribe, ComponentDescribeOperation, ComponentInfo,
+    ComponentManifest, ComponentProfiles, ComponentRunInput, ComponentRunOutput, Flow, FlowId,
+    FlowKind, FlowMetadata, I18nText, MessageCatalog, PackDocs, PackFlowEntry, PackId, PackKind,
+    PackManifest, PackQaSpec, PackSignatures, ResourceHints, SecretKey, SecretRequirement,
+};
+use semver::Version;
+use serde_json::json;
+
+fn manifest() -> PackManifest {
+    let mut secret = SecretRequirement::default();
+    secret.key = SecretKey::new("api_token").unwrap();
+    secret.description = Some("Token for the <upstream> API".into());
+
+    PackManifest {
+        schema_version: "pack-v1".into(),
+        pack_id: PackId::new("dev.local.docs").unwrap(),
+        name: Some("Docs Demo".into()),
+        version: Version::parse("1.2.0").unwrap(),
+        kind: PackKind::Application,
+        publisher: "tests".into(),
+        components: vec![ComponentManifest {
+            id: "greentic.demo.weather".parse().unwrap(),
+            version: Version::parse("0.3.0").unwrap(),
+            supports: vec![FlowKind::Messaging],
+            world: "greentic:component/world".into(),
+            profiles: ComponentProfiles::default(),
+            capabilities: ComponentCapabilities::default(),
+            configurators: None,
+            operations: Vec::new(),
+            config_schema: None,
+            resources: ResourceHints::default(),
+            dev_flows: BTreeMap::new(),
+        }],
+        flows: vec![PackFlowEntry {
+            id: FlowId::new("main").unwrap(),
+            kind: FlowKind::Messaging,
+            flow: Flow {
+                schema_version: "flow-v1".into(),
+                id: FlowId::new("main").unwrap(),
+                kind: FlowKind::Messaging,
+                entrypoints: BTreeMap::from([("chat".to_string(), json!({ "channel": "teams" }))]),
+                nodes: Default::default(),
+                metadata: FlowMetadata::default(),
+            },
+            tags: vec!["demo".into()],
+            entrypoints: vec!["chat".into()],
+        }],
+        dependencies: Vec::new(),
+        capabilities: Vec::new(),
+        secret_requirements: vec![secret],
+        signatures: PackSignatures::default(),
+        bootstrap: None,
+        extensions: None,
+    }
+}
+
+fn component() -> ComponentDescribe {
+    let input = SchemaIr::Object {
+        properties: BTreeMap::from([
+            (
+                "city".to_string(),
+                SchemaIr::String {
+                    min_len: Some(1),
+                    max_len: None,
+                    regex: None,
+                    format: None,
+                },
+            ),
+            (
+                "units".to_string(),
+                SchemaIr::Enum {
+                    values: vec![
+                        CborValue::Text("metric".into()),
+                        CborValue::Text("imperial".into()),
+                    ],
+                },
+            ),
+        ]),
+        required: vec!["city".into()],
+        additional: AdditionalProperties::Forbid,
+    };
+    ComponentDescribe {
+        info: ComponentInfo {
+            id: "greentic.demo.weather".into(),
+            version: "0.3.0".into(),
+            role: "tool".into(),
+            display_name: None,
+        },
+        provided_capabilities: Vec::new(),
+        required_capabilities: Vec::new(),
+        metadata: BTreeMap::new(),
+        operations: vec![ComponentDescribeOperation {
+            id: "forecast".into(),
+            display_name: Some(I18nText::new("op.forecast", Some("Forecast".into()))),
+            input: ComponentRunInput { schema: input },
+            output: ComponentRunOutput {
+                schema: SchemaIr::Array {
+                    items: Box::new(SchemaIr::Float {
+                        min: None,
+                        max: None,
+                    }),
+                    min_items: None,
+                    max_items: Some(7),
+                },
+            },
+            defaults: BTreeMap::new(),
+            redactions: Vec::new(),
+            constraints: BTreeMap::new(),
+            schema_hash: String::new(),
+        }],
+        config_schema: SchemaIr::Object {
+            properties: BTreeMap::new(),
+            required: Vec::new(),
+            additional: AdditionalProperties::Allow,
+        },
+    }
+}
+
+fn qa_spec() -> PackQaSpec {
+    PackQaSpec {
+        mode: QaMode::Setup,
+        title: I18nText::new("qa.title", Some("Setup".into())),
+        description: None,
+        questions: vec![Question {
+            id: "region".into(),
+            label: I18nText::new("qa.region", Some("Region".into())),
+            help: None,
+            error: None,
+            kind: QuestionKind::Choice {
+                options: vec![ChoiceOption {
+                    value: "eu".into(),
+                    label: I18nText::new("qa.region.eu", Some("Europe".into())),
+                }],
+            },
+            required: true,
+            default: Some(CborValue::Text("eu".into())),
+            visible_if: None,
+            required_if: None,
+        }],
+        defaults: BTreeMap::new(),
+    }
+}
+
+#[test]
+fn markdown_covers_flows_components_secrets_and_questions() {
+    let manifest = manifest();
+    let component = component();
+    let qa = qa_spec();
+    let mut catalog = MessageCatalog::new()
+        .with_default_locale("en")
+        .expect("default locale");
+    catalog
+        .insert("en", "qa.region", "Datacenter region")
+        .expect("insert");
+
+    let markdown = PackDocs::new(&manifest)
+        .with_component(&component)
+        .with_qa_spec(&qa)
+        .with_catalog(&catalog, "en-GB")
+        .to_markdown();
+
+    assert!(markdown.starts_with("# Docs Demo\n"));
+    assert!(markdown.contains("| chat | {\"channel\":\"teams\"} |"));
+    assert!(markdown.contains("#### Operation `forecast` — Forecast"));
+    assert!(markdown.contains("| city | string | yes | length >= 1 |"));
+    assert!(markdown.contains("| units | enum | no | one of: metric, imperial |"));
+    assert!(markdown.contains("| (root) | array<number> | yes | items <= 7 |"));
+    assert!(markdown.contains("| api_token | yes |  | Token for the <upstream> API |"));
+    assert!(markdown.contains("### Setup (setup)"));
+    assert!(markdown.contains("| region | Datacenter region | choice: eu (Europe) | yes | eu |"));
+}
+
+#[test]
+fn html_escapes_text_and_matches_markdown_sections() {
+    let manifest = manifest();
+    let component = component();
+    let docs = PackDocs::new(&manifest).with_component(&component);
+
+    let html = docs.to_html();
+    assert!(html.starts_with("<!DOCTYPE html>"));
+    assert!(html.contains("<title>Docs Demo</title>"));
+    assert!(html.contains("<td>Token for the &lt;upstream&gt; API</td>"));
+    assert!(html.contains("<h4>Operation `forecast` — Forecast</h4>"));
+    assert_eq!(
+        html.matches("<h2>").count(),
+        docs.to_markdown().matches("\n## ").count()
+    );
+}
+
+#[test]
+fn output_is_deterministic() {
+    let manifest = manifest();
+    let component = component();
+    let docs = PackDocs::new(&manifest).with_component(&component);
+    assert_eq!(docs.to_markdown(), docs.to_markdown());
+    assert_eq!(docs.to_html(), docs.to_html());
+}
@@ -221,6 +221,139 @@ fn pack_manifest_cbor_encoding_is_deterministic() {
     assert_eq!(first, second);
 }
 
+fn shuffled_pack_manifest() -> PackManifest {
+    let mut manifest = sample_pack_manifest();
+    manifest.components.reverse();
+    manifest.components.push(sample_component(
+        "component.router",
+        vec![FlowKind::Messaging],
+    ));
+    manifest.dependencies.insert(
+        0,
+        PackDependency {
+            alias: "provider.events".into(),
+            pack_id: PackId::new("vendor.events").unwrap(),
+            version_req: greentic_types::SemverReq::parse("^2.0").unwrap(),
+            required_capabilities: vec!["messaging".into(), "events".into(), "events".into()],
+        },
+    );
+    manifest.capabilities.insert(
+        0,
+        ComponentCapability {
+            name: "zeta".into(),
+            description: None,
+        },
+    );
+    manifest.capabilities.push(ComponentCapability {
+        name: "events".into(),
+        description: None,
+    });
+    let mut other_secret = sample_secret_requirement();
+    other_secret.key = "ANOTHER_KEY".into();
+    manifest.secret_requirements.push(other_secret);
+    manifest
+        .secret_requirements
+        .push(sample_secret_requirement());
+    manifest
+}
+
+#[test]
+fn pack_manifest_normalize_sorts_and_dedupes() {
+    let manifest = shuffled_pack_manifest().normalized();
+
+    let component_ids: Vec<_> = manifest
+        .components
+        .iter()
+        .map(|component| component.id.as_str())
+        .collect();
+    assert_eq!(
+        component_ids,
+        vec!["component.end", "component.handler", "component.router"]
+    );
+    let aliases: Vec<_> = manifest
+        .dependencies
+        .iter()
+        .map(|dep| dep.alias.as_str())
+        .collect();
+    assert_eq!(aliases, vec!["provider.events", "provider.messaging"]);
+    assert_eq!(
+        manifest.dependencies[0].required_capabilities,
+        vec!["events".to_string(), "messaging".to_string()]
+    );
+    let capabilities: Vec<_> = manifest
+        .capabilities
+        .iter()
+        .map(|cap| cap.name.as_str())
+        .collect();
+    assert_eq!(capabilities, vec!["events", "messaging", "zeta"]);
+    let secrets: Vec<_> = manifest
+        .secret_requirements
+        .iter()
+        .map(|req| req.key.as_str())
+        .collect();
+    assert_eq!(secrets, vec!["ANOTHER_KEY", "TEST_API_KEY"]);
+
+    let nodes: Vec<_> = manifest.flows[0]
+        .flow
+        .nodes
+        .keys()
+        .map(|id| id.as_str())
+        .collect();
+    assert_eq!(
+        nodes,
+        vec!["start", "handler", "end"],
+        "node order is preserved"
+    );
+
+    let mut again = manifest.clone();
+    again.normalize();
+    assert_eq!(again, manifest, "normalize is idempotent");
+}
+
+#[test]
+fn pack_manifest_normalized_cbor_is_byte_stable() {
+    let manifest = shuffled_pack_manifest().normalized();
+    let first = encode_pack_manifest(&manifest).expect("encode");
+    let decoded = decode_pack_manifest(&first).expect("decode");
+    let second = encode_pack_manifest(&decoded).expect("re-encode");
+    assert_eq!(first, second);
+
+    let from_other_order =
+        encode_pack_manifest(&sample_pack_manifest().normalized()).expect("encode sample");
+    let mut reordered = sample_pack_manifest();
+    reordered.components.reverse();
+    let from_reordered = encode_pack_manifest(&reordered.normalized()).expect("encode reordered");
+    assert_eq!(from_other_order, from_reordered);
+}
+
+#[test]
+fn pack_manifest_content_digest_ignores_order_and_signatures() {
+    let manifest = sample_pack_manifest();
+    let digest = manifest.content_digest().expect("digest");
+    assert!(digest.starts_with("sha256:"));
+    assert_eq!(digest.len(), "sha256:".len() + 64);
+
+    let mut reordered = manifest.clone();
+    reordered.components.reverse();
+    reordered.components.push(reordered.components[0].clone());
+    assert_eq!(reordered.content_digest().expect("digest"), digest);
+
+    let mut signed = manifest.clone();
+    signed
+        .signatures
+        .signatures
+        .push(greentic_types::Signature::new(
+            "key-1",
+            greentic_types::SignatureAlgorithm::Ed25519,
+            vec![1, 2, 3],
+        ));
+    assert_eq!(signed.content_digest().expect("digest"), digest);
+
+    let mut changed = manifest;
+    changed.publisher = "someone-else".into();
+    assert_ne!(changed.content_digest().expect("digest"), digest);
+}
+
 #[test]
 fn pack_manifest_yaml_roundtrip_without_bootstrap() {
     let manifest: PackManifest = serde_yaml_bw::from_str(include_str!(
@@ -0,0 +1,336 @@
+#![cfg(feature = "serde")]
+
+use std::collections::BTreeMap;
+
+use greentic_types::{
+    CapabilitiesExtensionV1, CapabilityOfferV1, CapabilityProviderRefV1, CapabilityScopeOverlayV1,
+    CapabilityScopeV1, ComponentCapabilities, ComponentManifest, ComponentProfiles,
+    ExtensionInline, ExtensionOverlayV1, ExtensionRef, Flow, FlowId, FlowKind, FlowMetadata,
+    OverlaySelectorV1, PackFlowEntry, PackId, PackKind, PackManifest, PackOverlayEntryV1,
+    PackOverlayError, PackOverlayV1, PackSignatures, ResourceHints, TenantCtx, apply_pack_overlay,
+};
+use semver::Version;
+use serde_json::{Value, json};
+
+fn manifest() -> PackManifest {
+    let mut manifest = PackManifest {
+        schema_version: "pack-v1".into(),
+        pack_id: PackId::new("dev.local.overlay").unwrap(),
+        name: None,
+        version: Version::parse("0.1.0").unwrap(),
+        kind: PackKind::Application,
+        publisher: "tests".into(),
+        components: vec![ComponentManifest {
+            id: "greentic.demo.component".parse().unwrap(),
+            version: Version::parse("1.0.0").unwrap(),
+            supports: vec![FlowKind::Messaging],
+            world: "greentic:component/world".into(),
+            profiles: ComponentProfiles::default(),
+            capabilities: ComponentCapabilities::default(),
+            configurators: None,
+            operations: Vec::new(),
+            config_schema: None,
+            resources: ResourceHints::default(),
+            dev_flows: BTreeMap::new(),
+        }],
+        flows: vec![PackFlowEntry {
+            id: FlowId::new("main").unwrap(),
+            kind: FlowKind::Messaging,
+            flow: Flow {
+                schema_version: "flow-v1".into(),
+                id: FlowId::new("main").unwrap(),
+                kind: FlowKind::Messaging,
+                entrypoints: BTreeMap::from([("default".to_string(), Value::Null)]),
+                nodes: Default::default(),
+                metadata: FlowMetadata::default(),
+            },
+            tags: Vec::new(),
+            entrypoints: vec!["default".into()],
+        }],
+        dependencies: Vec::new(),
+        capabilities: Vec::new(),
+        secret_requirements: Vec::new(),
+        signatures: PackSignatures::default(),
+        bootstrap: None,
+        extensions: None,
+    };
+    manifest
+        .set_capabilities_extension_v1(CapabilitiesExtensionV1::new(vec![CapabilityOfferV1 {
+            offer_id: "state-redis".into(),
+            cap_id: "greentic.cap.state".into(),
+            version: "v1".into(),
+            provider: CapabilityProviderRefV1 {
+                component_ref: "greentic.demo.component".into(),
+                op: "state.dispatch".into(),
+            },
+            scope: Some(CapabilityScopeV1 {
+                envs: vec!["dev".into()],
+                tenants: Vec::new(),
+                teams: Vec::new(),
+            }),
+            priority: 0,
+            requires_setup: false,
+            setup: None,
+            applies_to: None,
+        }]))
+        .expect("set capabilities");
+    manifest
+}
+
+fn entry(id: &str, selector: OverlaySelectorV1) -> PackOverlayEntryV1 {
+    PackOverlayEntryV1 {
+        id: id.into(),
+        selector,
+        component_config: BTreeMap::new(),
+        capability_scopes: BTreeMap::new(),
+        flow_entrypoints: BTreeMap::new(),
+        extensions: BTreeMap::new(),
+    }
+}
+
+fn config_entry(id: &str, selector: OverlaySelectorV1, value: &str) -> PackOverlayEntryV1 {
+    let mut entry = entry(id, selector);
+    entry.component_config.insert(
+        "greentic.demo.component".parse().unwrap(),
+        BTreeMap::from([("endpoint".to_string(), json!(value))]),
+    );
+    entry
+}
+
+fn ctx(env: &str, tenant: &str, team: Option<&str>) -> TenantCtx {
+    TenantCtx::new(env.parse().unwrap(), tenant.parse().unwrap())
+        .with_team(team.map(|team| team.parse().unwrap()))
+}
+
+fn selector(env: Option<&str>, tenant: Option<&str>, team: Option<&str>) -> OverlaySelectorV1 {
+    OverlaySelectorV1 {
+        env: env.map(|env| env.parse().unwrap()),
+        tenant: tenant.map(|tenant| tenant.parse().unwrap()),
+        team: team.map(|team| team.parse().unwrap()),
+    }
+}
+
+fn endpoint_default(manifest: &PackManifest) -> Value {
+    manifest.components[0].config_schema.as_ref().unwrap()["properties"]["endpoint"]["default"]
+        .clone()
+}
+
+#[test]
+fn applies_entries_by_specificity_and_records_provenance() {
+    let mut overlay = PackOverlayV1::new(PackId::new("dev.local.overlay").unwrap());
+    // Declared most specific first to prove ordering does not depend on declaration order.
+    overlay.overlays = vec![
+        config_entry(
+            "team",
+            selector(Some("prod"), Some("acme"), Some("ops")),
+            "team",
+        ),
+        config_entry(
+            "tenant",
+            selector(Some("prod"), Some("acme"), None),
+            "tenant",
+        ),
+        config_entry("env", selector(Some("prod"), None, None), "env"),
+        config_entry(
+            "other-env",
+            selector(Some("staging"), None, None),
+            "staging",
+        ),
+    ];
+
+    let result = apply_pack_overlay(&manifest(), &overlay, &ctx("prod", "acme", Some("ops")))
+        .expect("apply");
+    assert_eq!(result.applied, vec!["env", "tenant", "team"]);
+    assert_eq!(endpoint_default(&result.manifest), json!("team"));
+    assert_eq!(
+        result.provenance["components.greentic.demo.component.config.endpoint"],
+        "team"
+    );
+
+    let result =
+        apply_pack_overlay(&manifest(), &overlay, &ctx("prod", "acme", None)).expect("apply");
+    assert_eq!(result.applied, vec!["env", "tenant"]);
+    assert_eq!(endpoint_default(&result.manifest), json!("tenant"));
+}
+
+#[test]
+fn tenant_entries_override_env_entries_declared_later() {
+    let mut overlay = PackOverlayV1::new(PackId::new("dev.local.overlay").unwrap());
+    overlay.overlays = vec![
+        config_entry("tenant", selector(None, Some("acme"), None), "tenant"),
+        config_entry("env", selector(Some("prod"), None, None), "env"),
+        config_entry(
+            "env-tenant",
+            selector(Some("prod"), Some("acme"), None),
+            "env-tenant",
+        ),
+    ];
+    let result =
+        apply_pack_overlay(&manifest(), &overlay, &ctx("prod", "acme", None)).expect("apply");
+    assert_eq!(result.applied, vec!["env", "tenant", "env-tenant"]);
+    assert_eq!(endpoint_default(&result.manifest), json!("env-tenant"));
+
+    overlay.overlays.pop();
+    let result =
+        apply_pack_overlay(&manifest(), &overlay, &ctx("prod", "acme", None)).expect("apply");
+    assert_eq!(endpoint_default(&result.manifest), json!("tenant"));
+}
+
+#[test]
+fn adjusts_capability_scopes_and_flow_entrypoints() {
+    let mut entry = entry("prod", selector(Some("prod"), None, None));
+    entry.capability_scopes.insert(
+        "state-redis".into(),
+        CapabilityScopeOverlayV1 {
+            replace: None,
+            add: CapabilityScopeV1 {
+                envs: vec!["prod".into()],
+                tenants: vec!["acme".into()],
+                teams: Vec::new(),
+            },
+            remove: CapabilityScopeV1 {
+                envs: vec!["dev".into()],
+                tenants: Vec::new(),
+                teams: Vec::new(),
+            },
+        },
+    );
+    entry.flow_entrypoints.insert(
+        FlowId::new("main").unwrap(),
+        BTreeMap::from([
+            ("default".to_string(), Value::Null),
+            ("webhook".to_string(), json!({ "path": "/hooks/prod" })),
+        ]),
+    );
+    let mut overlay = PackOverlayV1::new(PackId::new("dev.local.overlay").unwrap());
+    overlay.overlays.push(entry);
+
+    let result =
+        apply_pack_overlay(&manifest(), &overlay, &ctx("prod", "acme", None)).expect("apply");
+    let caps = result
+        .manifest
+        .get_capabilities_extension_v1()
+        .expect("decode")
+        .expect("capabilities");
+    let scope = caps.offers[0].scope.as_ref().expect("scope");
+    assert_eq!(scope.envs, vec!["prod"]);
+    assert_eq!(scope.tenants, vec!["acme"]);
+
+    let flow = &result.manifest.flows[0];
+    assert_eq!(flow.entrypoints, vec!["webhook"]);
+    assert!(!flow.flow.entrypoints.contains_key("default"));
+    assert_eq!(
+        flow.flow.entrypoints["webhook"],
+        json!({ "path": "/hooks/prod" })
+    );
+    assert_eq!(
+        result.provenance["capabilities.offers.state-redis.scope"],
+        "prod"
+    );
+    assert_eq!(result.provenance["flows.main.entrypoints.default"], "prod");
+}
+
+#[test]
+fn merge_patches_extension_payloads() {
+    let mut base = entry("base", OverlaySelectorV1::default());
+    base.extensions.insert(
+        "acme.settings".into(),
+        ExtensionOverlayV1 {
+            version: Some("2.0.0".into()),
+            patch: json!({ "region": "eu", "limits": { "rps": 10, "burst": 20 } }),
+        },
+    );
+    let mut prod = entry("prod", selector(Some("prod"), None, None));
+    prod.extensions.insert(
+        "acme.settings".into(),
+        ExtensionOverlayV1 {
+            version: None,
+            patch: json!({ "limits": { "rps": 100, "burst": null } }),
+        },
+    );
+    let mut overlay = PackOverlayV1::new(PackId::new("dev.local.overlay").unwrap());
+    overlay.overlays = vec![prod, base];
+
+    let result =
+        apply_pack_overlay(&manifest(), &overlay, &ctx("prod", "acme", None)).expect("apply");
+    let extension = &result.manifest.extensions.as_ref().unwrap()["acme.settings"];
+    let Some(ExtensionInline::Other(value)) = &extension.inline else {
+        panic!("expected inline payload");
+    };
+    assert_eq!(value, &json!({ "region": "eu", "limits": { "rps": 100 } }));
+    assert_eq!(extension.version, "2.0.0");
+    assert_eq!(result.provenance["extensions.acme.settings.region"], "base");
+    assert_eq!(
+        result.provenance["extensions.acme.settings.limits.rps"],
+        "prod"
+    );
+    assert_eq!(
+        result.provenance["extensions.acme.settings.limits.burst"],
+        "prod"
+    );
+}
+
+#[test]
+fn rejects_unversioned_and_referenced_extensions() {
+    let patch = ExtensionOverlayV1 {
+        version: None,
+        patch: json!({ "region": "eu" }),
+    };
+    let mut create = entry("create", OverlaySelectorV1::default());
+    create
+        .extensions
+        .insert("acme.settings".into(), patch.clone());
+    let mut overlay = PackOverlayV1::new(PackId::new("dev.local.overlay").unwrap());
+    overlay.overlays = vec![create];
+    let err = apply_pack_overlay(&manifest(), &overlay, &ctx("prod", "acme", None)).unwrap_err();
+    assert!(matches!(
+        err,
+        PackOverlayError::MissingExtensionVersion { .. }
+    ));
+
+    let mut referenced = manifest();
+    referenced.extensions = Some(BTreeMap::from([(
+        "acme.settings".to_string(),
+        ExtensionRef {
+            kind: "acme.settings".into(),
+            version: "1.0.0".into(),
+            digest: Some("sha256:abc".into()),
+            location: Some("oci://registry/acme-settings".into()),
+            inline: None,
+        },
+    )]));
+    let err = apply_pack_overlay(&referenced, &overlay, &ctx("prod", "acme", None)).unwrap_err();
+    assert!(matches!(err, PackOverlayError::InvalidExtension { .. }));
+}
+
+#[test]
+fn rejects_unknown_targets_and_foreign_packs() {
+    let mut overlay = PackOverlayV1::new(PackId::new("dev.local.other").unwrap());
+    let err = apply_pack_overlay(&manifest(), &overlay, &ctx("prod", "acme", None)).unwrap_err();
+    assert!(matches!(err, PackOverlayError::PackMismatch { .. }));
+
+    overlay.pack_id = PackId::new("dev.local.overlay").unwrap();
+    let mut bad = entry("bad", OverlaySelectorV1::default());
+    bad.flow_entrypoints
+        .insert(FlowId::new("missing").unwrap(), BTreeMap::new());
+    overlay.overlays.push(bad);
+    let err = apply_pack_overlay(&manifest(), &overlay, &ctx("prod", "acme", None)).unwrap_err();
+    assert!(matches!(err, PackOverlayError::UnknownFlow { .. }));
+}
+
+#[test]
+fn overlay_document_roundtrips_through_json() {
+    let mut overlay = PackOverlayV1::new(PackId::new("dev.local.overlay").unwrap());
+    overlay.overlays.push(config_entry(
+        "tenant",
+        selector(Some("prod"), Some("acme"), None),
+        "https://acme.example",
+    ));
+    let json = serde_json::to_value(&overlay).expect("serialize");
+    assert_eq!(
+        json["overlays"][0]["selector"],
+        json!({ "env": "prod", "tenant": "acme" })
+    );
+    let decoded: PackOverlayV1 = serde_json::from_value(json).expect("deserialize");
+    assert_eq!(decoded, overlay);
+}
@@ -0,0 +1,228 @@
+#![cfg(feature = "pack-yaml")]
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use greentic_types::{PACK_YAML_FILE, load_pack_yaml, parse_pack_yaml, scaffold_pack_yaml};
+
+const FLOW: &str = "schema_version: flow-v1
+id: main
+kind: messaging
+entrypoints:
+  chat: null
+";
+
+const COMPONENT: &str = r#"{
+  "id": "greentic.demo.weather",
+  "version": "0.3.0",
+  "supports": ["messaging"],
+  "world": "greentic:component/world",
+  "profiles": {},
+  "capabilities": { "wasi": {}, "host": {} }
+}"#;
+
+const QA: &str = "mode: setup
+title: { key: qa.title, fallback: Setup }
+description: null
+questions:
+  - id: region
+    label: { key: qa.region, fallback: Region }
+    help: null
+    error: null
+    kind: { type: text }
+    required: true
+    default: null
+defaults: {}
+";
+
+fn pack_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "greentic-types-pack-yaml-{name}-{}",
+        std::process::id()
+    ));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).expect("temp dir");
+    dir
+}
+
+fn write(dir: &Path, relative: &str, contents: &str) {
+    let path = dir.join(relative);
+    fs::create_dir_all(path.parent().unwrap()).expect("parent dir");
+    fs::write(path, contents).expect("write file");
+}
+
+#[test]
+fn loads_pack_yaml_with_includes() {
+    let dir = pack_dir("includes");
+    write(&dir, "flows/main.ygtc", FLOW);
+    write(&dir, "components/weather.json", COMPONENT);
+    write(&dir, "qa/setup.yaml", QA);
+    write(&dir, "i18n/en.json", r#"{ "qa.title": "Setup" }"#);
+    write(&dir, "i18n/en-gb.json", r#"{ "qa.region": "Region" }"#);
+    write(
+        &dir,
+        PACK_YAML_FILE,
+        "pack_id: dev.local.weather
+name: Weather
+version: 0.1.0
+kind: application
+publisher: acme
+components:
+  - include: components/weather.json
+flows:
+  - include: ./flows/main.ygtc
+    tags: [chat]
+qa:
+  - qa/setup.yaml
+i18n:
+  en: i18n/en.json
+  en_gb: i18n/en-gb.json
+",
+    );
+
+    let load = load_pack_yaml(&dir.join(PACK_YAML_FILE)).expect("load");
+    let manifest = load.manifest.expect("manifest");
+    assert_eq!(manifest.schema_version, "pack-v1");
+    assert_eq!(manifest.name.as_deref(), Some("Weather"));
+    assert_eq!(manifest.components[0].id.as_str(), "greentic.demo.weather");
+    assert_eq!(manifest.flows[0].id.as_str(), "main");
+    assert_eq!(manifest.flows[0].tags, vec!["chat"]);
+    assert_eq!(load.qa_specs.len(), 1);
+    assert_eq!(load.i18n.message("en", "qa.title"), Some("Setup"));
+    assert_eq!(load.i18n.message("en-GB", "qa.title"), Some("Setup"));
+
+    // `qa.region` is missing from `en`; that is a warning, not an error. `en-GB` inherits
+    // `qa.title` from `en`.
+    assert_eq!(load.report.diagnostics.len(), 1);
+    assert_eq!(
+        load.report.diagnostics[0].code,
+        "PACK_YAML_I18N_MISSING_KEY"
+    );
+    assert_eq!(load.report.diagnostics[0].path.as_deref(), Some("i18n.en"));
+    fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn reports_include_errors_with_locations() {
+    let dir = pack_dir("errors");
+    write(&dir, "flows/broken.ygtc", "id: [\n");
+    let source = "pack_id: dev.local.broken
+version: 0.1.0
+kind: application
+publisher: acme
+flows:
+  - include: flows/missing.ygtc
+  - include: flows/broken.ygtc
+  - include: ../outside.ygtc
+";
+
+    let load = parse_pack_yaml(source, &dir);
+    assert!(load.manifest.is_none());
+    let codes: Vec<&str> = load
+        .report
+        .diagnostics
+        .iter()
+        .map(|diag| diag.code.as_str())
+        .collect();
+    assert_eq!(
+        codes,
+        vec![
+            "PACK_YAML_INCLUDE_NOT_FOUND",
+            "PACK_YAML_INCLUDE_PARSE_ERROR",
+            "PACK_YAML_INCLUDE_INVALID_PATH",
+        ]
+    );
+    let missing = &load.report.diagnostics[0];
+    assert_eq!(missing.path.as_deref(), Some("flows[0]"));
+    assert_eq!(missing.data["file"], "pack.yaml");
+    assert_eq!(missing.data["line"], 6);
+    assert_eq!(missing.data["column"], 14);
+    assert_eq!(load.report.diagnostics[1].data["file"], "flows/broken.ygtc");
+    fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn duplicate_diagnostics_point_at_the_duplicate_entry() {
+    let dir = pack_dir("duplicates");
+    write(&dir, "flows/main.ygtc", FLOW);
+    write(&dir, "flows/again.ygtc", FLOW);
+    write(&dir, "components/weather.json", COMPONENT);
+    let source = "pack_id: greentic.demo.weather
+version: 0.1.0
+kind: application
+publisher: acme
+components:
+  - include: components/weather.json
+  - include: components/weather.json
+flows:
+  - include: flows/main.ygtc
+  - include: flows/again.ygtc
+";
+
+    let load = parse_pack_yaml(source, &dir);
+    let located: Vec<(&str, &str, u64, u64)> = load
+        .report
+        .diagnostics
+        .iter()
+        .map(|diag| {
+            (
+                diag.code.as_str(),
+                diag.path.as_deref().unwrap_or_default(),
+                diag.data["line"].as_u64().expect("line"),
+                diag.data["column"].as_u64().expect("column"),
+            )
+        })
+        .collect();
+    assert_eq!(
+        located,
+        vec![
+            ("PACK_YAML_DUPLICATE_COMPONENT", "components[1]", 7, 14),
+            ("PACK_YAML_DUPLICATE_FLOW", "flows[1]", 10, 14),
+        ]
+    );
+    fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn reports_root_parse_errors() {
+    let load = parse_pack_yaml("pack_id: dev.local.x\nversion: [\n", Path::new("."));
+    assert!(load.manifest.is_none());
+    let diag = &load.report.diagnostics[0];
+    assert_eq!(diag.code, "PACK_YAML_PARSE_ERROR");
+    assert!(diag.data["line"].is_u64());
+}
+
+#[test]
+fn scaffold_roundtrips_through_loader() {
+    let dir = pack_dir("scaffold");
+    write(&dir, "flows/main.ygtc", FLOW);
+    write(&dir, "components/weather.json", COMPONENT);
+    write(
+        &dir,
+        PACK_YAML_FILE,
+        "pack_id: dev.local.weather
+version: 0.1.0
+kind: application
+publisher: acme
+components:
+  - include: components/weather.json
+flows:
+  - include: flows/main.ygtc
+",
+    );
+    let manifest = load_pack_yaml(&dir.join(PACK_YAML_FILE))
+        .expect("load")
+        .manifest
+        .expect("manifest");
+
+    let scaffold = scaffold_pack_yaml(&manifest).expect("scaffold");
+    assert!(scaffold.files.contains_key("flows/main.ygtc"));
+    let out = pack_dir("scaffold-out");
+    scaffold.write_to(&out).expect("write scaffold");
+
+    let reloaded = load_pack_yaml(&out.join(PACK_YAML_FILE)).expect("reload");
+    assert!(reloaded.report.diagnostics.is_empty());
+    assert_eq!(reloaded.manifest.expect("manifest"), manifest);
+    fs::remove_dir_all(&dir).ok();
+    fs::remove_dir_all(&out).ok();
+}
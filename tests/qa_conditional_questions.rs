@@ -0,0 +1,316 @@
+mod common;
+
+use ciborium::value::Value;
+use greentic_types::adapters::component_v0_5_0_to_v0_6_0::adapt_component_qa_spec;
+use greentic_types::cbor::canonical;
+use greentic_types::schemas::component::v0_5_0::LegacyComponentQaSpec;
+use greentic_types::schemas::component::v0_6_0::{
+    ChoiceOption, ComponentQaSpec, QaMode, QaPredicate, QuestionKind,
+};
+use greentic_types::{
+    AnswerError, AnswerErrorKind, CanonicalPolicy, CborBytes, SchemaSource, SecretKey,
+    SecretRequirement, ValidateAnswersError, validate_answers,
+};
+
+use common::question;
+
+fn option(value: &str) -> ChoiceOption {
+    common::option(value, None)
+}
+
+fn text(value: &str) -> Value {
+    Value::Text(value.into())
+}
+
+fn map(entries: &[(&str, Value)]) -> Value {
+    Value::Map(
+        entries
+            .iter()
+            .map(|(key, value)| (text(key), value.clone()))
+            .collect(),
+    )
+}
+
+fn spec() -> ComponentQaSpec {
+    let mut tls_cert = question("tls_cert", QuestionKind::Text, false);
+    tls_cert.visible_if = Some(QaPredicate::Equals {
+        question: "tls".into(),
+        value: Value::Bool(true),
+    });
+    tls_cert.required_if = Some(QaPredicate::Answered {
+        question: "tls".into(),
+    });
+    let mut port = question(
+        "port",
+        QuestionKind::Integer {
+            min: Some(1),
+            max: Some(65535),
+        },
+        false,
+    );
+    port.default = Some(Value::Integer(5432.into()));
+    let mut alias = question("alias", QuestionKind::Text, false);
+    alias.required_if = Some(QaPredicate::Not {
+        predicate: Box::new(QaPredicate::Equals {
+            question: "primary".into(),
+            value: Value::Bool(true),
+        }),
+    });
+    common::spec(
+        QaMode::Setup,
+        vec![
+            question("tls", QuestionKind::Bool, true),
+            tls_cert,
+            question(
+                "features",
+                QuestionKind::MultiSelect {
+                    options: vec![option("metrics"), option("tracing"), option("logs")],
+                    min_selected: Some(1),
+                    max_selected: Some(2),
+                },
+                false,
+            ),
+            question(
+                "api_token",
+                QuestionKind::Secret {
+                    requirement: {
+                        let mut requirement = SecretRequirement::default();
+                        requirement.key = SecretKey::new("db/api_token").expect("secret key");
+                        requirement
+                    },
+                },
+                false,
+            ),
+            question("homepage", QuestionKind::Url, false),
+            question("contact", QuestionKind::Email, false),
+            question(
+                "slug",
+                QuestionKind::Pattern {
+                    regex: "^[a-z-]+$".into(),
+                },
+                false,
+            ),
+            question("since", QuestionKind::Date, false),
+            question("starts_at", QuestionKind::Time, false),
+            question(
+                "db",
+                QuestionKind::Group {
+                    questions: vec![question("host", QuestionKind::Text, true), port],
+                },
+                false,
+            ),
+            question(
+                "replicas",
+                QuestionKind::List {
+                    questions: vec![question("primary", QuestionKind::Bool, true), alias],
+                    min_items: Some(1),
+                    max_items: None,
+                },
+                false,
+            ),
+        ],
+    )
+}
+
+fn validate(answers: Value) -> Result<Value, ValidateAnswersError> {
+    let schema = SchemaSource::InlineCbor(CborBytes::new(
+        canonical::to_canonical_cbor(&spec()).expect("encode spec"),
+    ));
+    let input = CborBytes::new(canonical::to_canonical_cbor(&answers).expect("encode answers"));
+    validate_answers(&schema, &input, CanonicalPolicy::Canonicalize)
+        .map(|bytes| canonical::from_cbor(bytes.as_slice()).expect("decode answers"))
+}
+
+fn errors(answers: Value) -> Vec<AnswerError> {
+    match validate(answers) {
+        Err(ValidateAnswersError::Invalid(errors)) => errors,
+        other => panic!("expected invalid answers, got {other:?}"),
+    }
+}
+
+#[test]
+fn predicates_evaluate_against_scope_then_root() {
+    let root = map(&[
+        ("tls", Value::Bool(true)),
+        ("features", Value::Array(vec![text("logs")])),
+        (
+            "replicas",
+            Value::Array(vec![map(&[("primary", Value::Bool(false))])]),
+        ),
+    ]);
+    let item = map(&[("primary", Value::Bool(false))]);
+
+    let in_features = QaPredicate::In {
+        question: "features".into(),
+        values: vec![text("tracing"), text("logs")],
+    };
+    assert!(in_features.evaluate(&root));
+    assert!(
+        QaPredicate::Equals {
+            question: "replicas/0/primary".into(),
+            value: Value::Bool(false),
+        }
+        .evaluate(&root)
+    );
+    let sibling_and_root = QaPredicate::All {
+        predicates: vec![
+            QaPredicate::NotEquals {
+                question: "primary".into(),
+                value: Value::Bool(true),
+            },
+            QaPredicate::Answered {
+                question: "tls".into(),
+            },
+        ],
+    };
+    assert!(sibling_and_root.evaluate_in(&item, &root));
+    assert!(!QaPredicate::Any { predicates: vec![] }.evaluate(&root));
+    assert_eq!(sibling_and_root.questions(), vec!["primary", "tls"]);
+
+    // Predicates use an `op`-tagged canonical CBOR form.
+    let bytes = canonical::to_canonical_cbor(&sibling_and_root).expect("encode predicate");
+    let decoded: Value = canonical::from_cbor(&bytes).expect("decode predicate");
+    let Value::Map(entries) = decoded else {
+        panic!("predicate must encode as a map");
+    };
+    assert!(entries.contains(&(text("op"), text("all"))));
+}
+
+#[test]
+fn validates_conditional_and_nested_answers() {
+    let output = validate(map(&[
+        ("tls", Value::Bool(false)),
+        ("features", Value::Array(vec![text("metrics")])),
+        ("api_token", text("db/api_token")),
+        ("homepage", text("https://example.com")),
+        ("since", text("2024-02-29")),
+        ("db", map(&[("host", text("db.local"))])),
+        (
+            "replicas",
+            Value::Array(vec![map(&[("primary", Value::Bool(true))])]),
+        ),
+    ]))
+    .expect("valid answers");
+    // The group default was filled in and the hidden `tls_cert` was not required.
+    assert_eq!(
+        output,
+        map(&[
+            (
+                "db",
+                map(&[
+                    ("host", text("db.local")),
+                    ("port", Value::Integer(5432.into()))
+                ])
+            ),
+            ("tls", Value::Bool(false)),
+            ("since", text("2024-02-29")),
+            ("features", Value::Array(vec![text("metrics")])),
+            ("homepage", text("https://example.com")),
+            (
+                "replicas",
+                Value::Array(vec![map(&[("primary", Value::Bool(true))])])
+            ),
+            ("api_token", text("db/api_token")),
+        ])
+    );
+
+    let errors = errors(map(&[
+        ("tls", Value::Bool(true)),
+        (
+            "features",
+            Value::Array(vec![text("metrics"), text("tracing"), text("logs")]),
+        ),
+        ("api_token", text("hunter2")),
+        ("contact", text("not-an-email")),
+        ("slug", text("Bad Slug")),
+        (
+            "db",
+            map(&[("port", Value::Integer(70000.into())), ("user", text("x"))]),
+        ),
+        (
+            "replicas",
+            Value::Array(vec![
+                map(&[("primary", Value::Bool(false))]),
+                Value::Bool(true),
+            ]),
+        ),
+    ]));
+    let ids: Vec<(&str, &AnswerErrorKind)> = errors
+        .iter()
+        .map(|error| (error.question_id.as_str(), &error.kind))
+        .collect();
+    assert!(ids.contains(&("tls_cert", &AnswerErrorKind::Missing)));
+    assert!(ids.contains(&("api_token", &AnswerErrorKind::InlineSecret)));
+    assert!(ids.contains(&("db/user", &AnswerErrorKind::UnknownKey)));
+    assert!(ids.contains(&("db/host", &AnswerErrorKind::Missing)));
+    assert!(ids.contains(&("replicas/0/alias", &AnswerErrorKind::Missing)));
+    for id in ["features", "contact", "slug", "db/port"] {
+        assert!(
+            ids.iter()
+                .any(|(found, kind)| *found == id
+                    && matches!(kind, AnswerErrorKind::Constraint { .. })),
+            "expected constraint error for {id}: {errors:?}"
+        );
+    }
+    assert!(ids.contains(&(
+        "replicas/1",
+        &AnswerErrorKind::TypeMismatch {
+            expected: "object".into(),
+            found: "boolean".into(),
+        }
+    )));
+}
+
+#[test]
+fn time_answers_are_times_of_day_without_offset() {
+    for answer in ["14:30", "14:30:00", "23:59:60", "08:15:30.250"] {
+        validate(map(&[
+            ("tls", Value::Bool(false)),
+            ("starts_at", text(answer)),
+        ]))
+        .unwrap_or_else(|err| panic!("{answer} should be valid: {err:?}"));
+    }
+    for answer in ["14:30:00Z", "24:00", "14:30:00.", "2pm"] {
+        let errors = errors(map(&[
+            ("tls", Value::Bool(false)),
+            ("starts_at", text(answer)),
+        ]));
+        assert_eq!(errors[0].question_id, "starts_at", "{answer}");
+        assert!(matches!(errors[0].kind, AnswerErrorKind::Constraint { .. }));
+    }
+}
+
+#[test]
+fn rich_specs_roundtrip_canonical_cbor() {
+    let spec = spec();
+    let bytes = canonical::to_canonical_cbor(&spec).expect("encode spec");
+    canonical::ensure_canonical(&bytes).expect("canonical spec");
+    let decoded: ComponentQaSpec = canonical::from_cbor(&bytes).expect("decode spec");
+    assert_eq!(decoded, spec);
+
+    let secrets = decoded.secret_requirements();
+    assert_eq!(secrets.len(), 1);
+    assert_eq!(secrets[0].key.as_str(), "db/api_token");
+    let keys = decoded.i18n_keys();
+    assert!(keys.contains("qa.option.tracing"));
+    assert!(keys.contains("qa.host"));
+    assert!(keys.contains("qa.alias"));
+}
+
+#[test]
+fn legacy_adapter_emits_unconditional_questions() {
+    let legacy: LegacyComponentQaSpec =
+        serde_json::from_str(include_str!("../fixtures/legacy/component_v0_5_0_qa.json"))
+            .expect("legacy fixture");
+    let bytes = adapt_component_qa_spec(QaMode::Setup, &legacy).expect("adapt");
+    canonical::ensure_canonical(bytes.as_slice()).expect("canonical adapter output");
+    let spec = bytes.decode::<ComponentQaSpec>().expect("decode");
+    assert!(
+        spec.questions
+            .iter()
+            .all(|question| question.visible_if.is_none() && question.required_if.is_none())
+    );
+    // Unset predicates are omitted, so specs without conditions keep their previous encoding.
+    let reencoded = canonical::to_canonical_cbor(&spec).expect("encode");
+    assert_eq!(reencoded, bytes.as_slice());
+}
@@ -0,0 +1,167 @@
+mod common;
+
+use std::collections::BTreeMap;
+
+use ciborium::value::Value;
+use greentic_types::cbor::canonical;
+use greentic_types::schemas::component::v0_6_0::{ComponentQaSpec, QaMode, Question, QuestionKind};
+use greentic_types::{
+    AnswerErrorKind, AskReason, CborBytes, MigratedAnswer, QuestionToAsk, ValidateAnswersError,
+    diff_answers, diff_answers_with,
+};
+
+use common::{answers, option, question};
+
+fn choice(values: &[(&str, &str)]) -> QuestionKind {
+    QuestionKind::Choice {
+        options: values
+            .iter()
+            .map(|(value, label)| option(value, Some(label)))
+            .collect(),
+    }
+}
+
+fn spec(questions: Vec<Question>) -> ComponentQaSpec {
+    common::spec(QaMode::Update, questions)
+}
+
+fn ask(id: &str, reason: AskReason) -> QuestionToAsk {
+    QuestionToAsk {
+        question_id: id.into(),
+        reason,
+    }
+}
+
+#[test]
+fn plans_preserved_new_invalid_and_dropped_answers() {
+    let old = spec(vec![
+        question("name", QuestionKind::Text, true),
+        question("region", QuestionKind::Text, false),
+        question("tier", choice(&[("free", "Free"), ("pro", "Pro")]), false),
+        question("retries", QuestionKind::Number, false),
+        question("legacy", QuestionKind::Bool, false),
+        question("notes", QuestionKind::Text, false),
+    ]);
+    let new = spec(vec![
+        question("name", QuestionKind::Text, true),
+        question(
+            "region",
+            choice(&[("eu", "Europe"), ("us", "United States")]),
+            true,
+        ),
+        question("tier", choice(&[("pro", "Pro")]), false),
+        question(
+            "retries",
+            QuestionKind::Integer {
+                min: Some(0),
+                max: Some(5),
+            },
+            false,
+        ),
+        question("notes", QuestionKind::Text, true),
+        question("owner", QuestionKind::Email, true),
+    ]);
+    let old_answers = answers(&[
+        ("name", Value::Text("demo".into())),
+        ("region", Value::Text("europe".into())),
+        ("tier", Value::Text("free".into())),
+        ("retries", Value::Float(3.0)),
+        ("legacy", Value::Bool(true)),
+    ]);
+
+    let plan = diff_answers(&old, &new, &old_answers).expect("plan");
+    assert_eq!(
+        plan.preserved,
+        BTreeMap::from([
+            ("name".to_string(), Value::Text("demo".into())),
+            ("region".to_string(), Value::Text("eu".into())),
+            ("retries".to_string(), Value::Integer(3.into())),
+        ])
+    );
+    assert_eq!(
+        plan.migrated,
+        vec![
+            MigratedAnswer {
+                question_id: "region".into(),
+                from: Value::Text("europe".into()),
+                to: Value::Text("eu".into()),
+            },
+            MigratedAnswer {
+                question_id: "retries".into(),
+                from: Value::Float(3.0),
+                to: Value::Integer(3.into()),
+            },
+        ]
+    );
+    assert_eq!(
+        plan.ask,
+        vec![
+            ask(
+                "tier",
+                AskReason::Invalid(vec![greentic_types::AnswerError {
+                    question_id: "tier".into(),
+                    kind: AnswerErrorKind::InvalidChoice {
+                        value: "free".into()
+                    },
+                }])
+            ),
+            ask("notes", AskReason::NowRequired),
+            ask("owner", AskReason::New),
+        ]
+    );
+    assert_eq!(plan.dropped, vec!["legacy".to_string()]);
+}
+
+#[test]
+fn text_answers_convert_to_booleans_with_the_shared_spellings() {
+    let old = spec(vec![
+        question("tls", QuestionKind::Text, false),
+        question("debug", QuestionKind::Text, false),
+        question("metrics", QuestionKind::Text, false),
+    ]);
+    let new = spec(vec![
+        question("tls", QuestionKind::Bool, false),
+        question("debug", QuestionKind::Bool, false),
+        question("metrics", QuestionKind::Bool, false),
+    ]);
+    let old_answers = answers(&[
+        ("tls", Value::Text("on".into())),
+        ("debug", Value::Text(" 0 ".into())),
+        ("metrics", Value::Text("maybe".into())),
+    ]);
+
+    let plan = diff_answers(&old, &new, &old_answers).expect("plan");
+    assert_eq!(plan.preserved["tls"], Value::Bool(true));
+    assert_eq!(plan.preserved["debug"], Value::Bool(false));
+    assert_eq!(plan.ask, vec![ask("metrics", AskReason::KindChanged)]);
+}
+
+#[test]
+fn custom_converters_run_before_builtins() {
+    let old = spec(vec![question("size", QuestionKind::Text, true)]);
+    let new = spec(vec![question(
+        "size",
+        choice(&[("s", "Small"), ("l", "Large")]),
+        true,
+    )]);
+    let old_answers = answers(&[("size", Value::Text("tiny".into()))]);
+
+    let plan = diff_answers(&old, &new, &old_answers).expect("plan");
+    assert_eq!(plan.ask, vec![ask("size", AskReason::KindChanged)]);
+
+    let tiny_is_small = |_: &str, _: &QuestionKind, _: &QuestionKind, answer: &Value| {
+        (answer == &Value::Text("tiny".into())).then(|| Value::Text("s".into()))
+    };
+    let plan = diff_answers_with(&old, &new, &old_answers, &tiny_is_small).expect("plan");
+    assert!(plan.ask.is_empty());
+    assert_eq!(plan.preserved["size"], Value::Text("s".into()));
+
+    assert!(matches!(
+        diff_answers(
+            &old,
+            &new,
+            &CborBytes::new(canonical::to_canonical_cbor(&"x").expect("encode"))
+        ),
+        Err(ValidateAnswersError::NotMap)
+    ));
+}
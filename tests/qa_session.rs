@@ -0,0 +1,202 @@
+mod common;
+
+use std::collections::BTreeMap;
+
+use ciborium::value::Value;
+use greentic_types::cbor::canonical;
+use greentic_types::schemas::component::v0_6_0::{
+    ComponentQaSpec, QaMode, QaPredicate, QuestionKind,
+};
+use greentic_types::schemas::pack::v0_6_0::{PackQaSpec, QaMode as PackQaMode};
+use greentic_types::{
+    AnswerErrorKind, CborBytes, QaSession, QaSessionError, SessionCursor, SessionData, TenantCtx,
+};
+
+use common::{option, question};
+
+fn spec() -> ComponentQaSpec {
+    let mut cert = question("cert", QuestionKind::Text, true);
+    cert.visible_if = Some(QaPredicate::Equals {
+        question: "tls".into(),
+        value: Value::Bool(true),
+    });
+    let mut region = question(
+        "region",
+        QuestionKind::Choice {
+            options: vec![option("eu", None), option("us", None)],
+        },
+        false,
+    );
+    region.default = Some(Value::Text("eu".into()));
+    common::spec(
+        QaMode::Setup,
+        vec![
+            question("name", QuestionKind::Text, true),
+            question("tls", QuestionKind::Bool, true),
+            cert,
+            region,
+        ],
+    )
+}
+
+fn next_id(session: &QaSession) -> Option<String> {
+    session
+        .next_question()
+        .map(|prompt| prompt.question.id.clone())
+}
+
+fn decode(bytes: &CborBytes) -> BTreeMap<String, Value> {
+    canonical::from_cbor(bytes.as_slice()).expect("decode answers")
+}
+
+#[test]
+fn walks_visible_questions_and_finishes_with_canonical_answers() {
+    let mut session = QaSession::new(spec(), QaMode::Setup);
+    assert_eq!(next_id(&session).as_deref(), Some("name"));
+    assert!(matches!(
+        session.answer("name", Value::Integer(1.into())),
+        Err(QaSessionError::Invalid(errors)) if matches!(errors[0].kind, AnswerErrorKind::TypeMismatch { .. })
+    ));
+    session
+        .answer("name", Value::Text("demo".into()))
+        .expect("name");
+    assert!(matches!(
+        session.answer("cert", Value::Text("pem".into())),
+        Err(QaSessionError::HiddenQuestion(_))
+    ));
+    session.answer("tls", Value::Bool(true)).expect("tls");
+
+    let prompt = session.next_question().expect("cert is now visible");
+    assert_eq!(prompt.question.id, "cert");
+    assert!(prompt.required);
+    assert!(matches!(
+        session.finish(),
+        Err(QaSessionError::Incomplete(id)) if id == "cert"
+    ));
+
+    // Going back re-opens `tls`; answering `false` hides `cert` again.
+    assert_eq!(
+        session.back().map(|question| question.id.as_str()),
+        Some("tls")
+    );
+    session.answer("tls", Value::Bool(false)).expect("tls");
+    let prompt = session.next_question().expect("region");
+    assert_eq!(prompt.question.id, "region");
+    assert_eq!(prompt.current, Some(&Value::Text("eu".into())));
+    session.answer("region", Value::Null).expect("skip region");
+    assert!(session.is_complete());
+
+    let answers = session.finish().expect("finish");
+    answers.ensure_canonical().expect("canonical answers");
+    assert_eq!(
+        decode(&answers),
+        BTreeMap::from([
+            ("name".to_string(), Value::Text("demo".into())),
+            ("region".to_string(), Value::Text("eu".into())),
+            ("tls".to_string(), Value::Bool(false)),
+        ])
+    );
+}
+
+#[test]
+fn update_mode_reasks_existing_answers_and_back_restores_them() {
+    let existing = CborBytes::new(
+        canonical::to_canonical_cbor(&BTreeMap::from([
+            ("name", Value::Text("old".into())),
+            ("tls", Value::Bool(false)),
+        ]))
+        .expect("encode existing"),
+    );
+
+    let setup = QaSession::new(spec(), QaMode::Setup)
+        .with_existing_answers(&existing)
+        .expect("existing");
+    assert_eq!(next_id(&setup).as_deref(), Some("region"));
+
+    let mut update = QaSession::new(spec(), QaMode::Update)
+        .with_existing_answers(&existing)
+        .expect("existing");
+    let prompt = update.next_question().expect("name");
+    assert_eq!(prompt.question.id, "name");
+    assert_eq!(prompt.current, Some(&Value::Text("old".into())));
+    update
+        .answer("name", Value::Text("new".into()))
+        .expect("name");
+    assert_eq!(update.answers()["name"], Value::Text("new".into()));
+    update.back();
+    assert_eq!(update.answers()["name"], Value::Text("old".into()));
+    assert_eq!(next_id(&update).as_deref(), Some("name"));
+}
+
+#[test]
+fn finish_drops_answers_to_questions_removed_from_the_spec() {
+    let existing = CborBytes::new(
+        canonical::to_canonical_cbor(&BTreeMap::from([
+            ("name", Value::Text("old".into())),
+            ("tls", Value::Bool(false)),
+            ("legacy", Value::Text("gone".into())),
+        ]))
+        .expect("encode existing"),
+    );
+    let mut update = QaSession::new(spec(), QaMode::Update)
+        .with_existing_answers(&existing)
+        .expect("existing");
+    assert_eq!(update.stale_answers(), ["legacy"]);
+    update
+        .answer("name", Value::Text("new".into()))
+        .expect("name");
+    update.answer("tls", Value::Bool(false)).expect("tls");
+    update
+        .answer("region", Value::Text("us".into()))
+        .expect("region");
+    assert!(update.is_complete());
+    let answers = decode(&update.finish().expect("finish"));
+    assert!(!answers.contains_key("legacy"));
+    assert_eq!(answers["name"], Value::Text("new".into()));
+}
+
+#[test]
+fn sessions_pause_in_session_data_and_resume() {
+    let mut session = QaSession::new(spec(), QaMode::Setup);
+    session
+        .answer("name", Value::Text("demo".into()))
+        .expect("name");
+
+    let data = SessionData {
+        tenant_ctx: TenantCtx::new(
+            "dev".parse().expect("env"),
+            "tenant".parse().expect("tenant"),
+        ),
+        flow_id: "setup".parse().expect("flow"),
+        pack_id: None,
+        cursor: SessionCursor::new("qa"),
+        context_json: serde_json::to_string(&session).expect("serialize session"),
+    };
+    let stored = serde_json::to_string(&data).expect("serialize session data");
+    let restored: SessionData = serde_json::from_str(&stored).expect("deserialize");
+    let mut resumed: QaSession =
+        serde_json::from_str(&restored.context_json).expect("deserialize session");
+    assert_eq!(resumed, session);
+    assert_eq!(next_id(&resumed).as_deref(), Some("tls"));
+    resumed.answer("tls", Value::Bool(false)).expect("tls");
+    resumed
+        .answer("region", Value::Text("us".into()))
+        .expect("region");
+    assert!(resumed.finish().is_ok());
+}
+
+#[test]
+fn pack_specs_drive_the_same_session() {
+    let component = spec();
+    let pack: PackQaSpec = canonical::from_cbor(
+        &canonical::to_canonical_cbor(&component).expect("encode component spec"),
+    )
+    .expect("decode pack spec");
+    let session = QaSession::for_pack(&pack, PackQaMode::Setup).expect("pack session");
+    assert_eq!(session.spec(), &component);
+    assert_eq!(session.mode(), &QaMode::Setup);
+    assert!(matches!(
+        QaSession::new(component, QaMode::Setup).answer("missing", Value::Null),
+        Err(QaSessionError::UnknownQuestion(_))
+    ));
+}
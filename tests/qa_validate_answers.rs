@@ -0,0 +1,173 @@
+mod common;
+
+use std::collections::BTreeMap;
+
+use ciborium::value::Value;
+use greentic_types::cbor::canonical;
+use greentic_types::schemas::component::v0_6_0::{QaMode, QuestionKind};
+use greentic_types::{
+    AnswerError, AnswerErrorKind, CanonicalPolicy, CborBytes, SchemaSource, ValidateAnswersError,
+    schema_id_for_cbor, validate_answers, validate_answers_with,
+};
+
+use common::{answers, option, question, spec};
+
+fn spec_bytes() -> Vec<u8> {
+    let mut port = question("port", QuestionKind::Number, true);
+    port.default = Some(Value::Integer(8080.into()));
+    let mut spec = spec(
+        QaMode::Setup,
+        vec![
+            question("name", QuestionKind::Text, true),
+            question(
+                "region",
+                QuestionKind::Choice {
+                    options: vec![option("eu", None)],
+                },
+                false,
+            ),
+            question("debug", QuestionKind::Bool, false),
+            port,
+        ],
+    );
+    spec.defaults = BTreeMap::from([("debug".to_string(), Value::Bool(false))]);
+    canonical::to_canonical_cbor(&spec).expect("encode spec")
+}
+
+fn decode(bytes: &CborBytes) -> BTreeMap<String, Value> {
+    canonical::from_cbor(bytes.as_slice()).expect("decode answers")
+}
+
+fn errors(result: Result<CborBytes, ValidateAnswersError>) -> Vec<AnswerError> {
+    match result {
+        Err(ValidateAnswersError::Invalid(errors)) => errors,
+        other => panic!("expected invalid answers, got {other:?}"),
+    }
+}
+
+#[test]
+fn applies_defaults_and_returns_canonical_answers() {
+    let schema = SchemaSource::InlineCbor(CborBytes::new(spec_bytes()));
+    let input = answers(&[
+        ("name", Value::Text("demo".into())),
+        ("region", Value::Text("eu".into())),
+    ]);
+
+    let output =
+        validate_answers(&schema, &input, CanonicalPolicy::RequireCanonical).expect("valid");
+    output.ensure_canonical().expect("canonical output");
+    let decoded = decode(&output);
+    assert_eq!(decoded["port"], Value::Integer(8080.into()));
+    assert_eq!(decoded["debug"], Value::Bool(false));
+    assert_eq!(decoded["name"], Value::Text("demo".into()));
+}
+
+#[test]
+fn reports_structured_errors_per_question() {
+    let schema = SchemaSource::InlineCbor(CborBytes::new(spec_bytes()));
+    let input = answers(&[
+        ("region", Value::Text("us".into())),
+        ("debug", Value::Text("yes".into())),
+        ("colour", Value::Text("blue".into())),
+    ]);
+
+    let errors = errors(validate_answers(&schema, &input, CanonicalPolicy::Off));
+    let expect = |id: &str, kind: AnswerErrorKind| AnswerError {
+        question_id: id.into(),
+        kind,
+    };
+    assert_eq!(
+        errors,
+        vec![
+            expect("colour", AnswerErrorKind::UnknownKey),
+            expect("name", AnswerErrorKind::Missing),
+            expect(
+                "region",
+                AnswerErrorKind::InvalidChoice { value: "us".into() }
+            ),
+            expect(
+                "debug",
+                AnswerErrorKind::TypeMismatch {
+                    expected: "boolean".into(),
+                    found: "string".into(),
+                }
+            ),
+        ]
+    );
+}
+
+#[test]
+fn resolves_schema_ids_through_resolver() {
+    let bytes = spec_bytes();
+    let id = schema_id_for_cbor(&bytes).expect("schema id");
+    let schema = SchemaSource::CborSchemaId(id.clone());
+    let input = answers(&[("name", Value::Text("demo".into()))]);
+
+    // Without a resolver, referenced schemas keep the map-only check.
+    let unchecked = answers(&[("bogus", Value::Bool(true))]);
+    assert_eq!(
+        validate_answers(&schema, &unchecked, CanonicalPolicy::Off).expect("map"),
+        unchecked
+    );
+    assert!(matches!(
+        validate_answers(
+            &schema,
+            &CborBytes::new(canonical::to_canonical_cbor(&"text").expect("encode")),
+            CanonicalPolicy::Off
+        ),
+        Err(ValidateAnswersError::NotMap)
+    ));
+
+    let mut registry = BTreeMap::new();
+    assert!(matches!(
+        validate_answers_with(&schema, &unchecked, CanonicalPolicy::Off, &registry),
+        Err(ValidateAnswersError::UnresolvedSchema(_))
+    ));
+    registry.insert(id.to_string(), CborBytes::new(bytes));
+    validate_answers_with(&schema, &input, CanonicalPolicy::Off, &registry).expect("valid");
+
+    registry.insert(
+        id.to_string(),
+        CborBytes::new(canonical::to_canonical_cbor(&"other").expect("encode")),
+    );
+    assert!(matches!(
+        validate_answers_with(&schema, &input, CanonicalPolicy::Off, &registry),
+        Err(ValidateAnswersError::SchemaIdMismatch(_))
+    ));
+}
+
+#[test]
+fn validates_against_schema_ir() {
+    use greentic_types::schemas::common::schema_ir::{AdditionalProperties, SchemaIr};
+
+    let schema = SchemaIr::Object {
+        properties: BTreeMap::from([(
+            "retries".to_string(),
+            SchemaIr::Int {
+                min: Some(0),
+                max: Some(3),
+            },
+        )]),
+        required: vec!["retries".into()],
+        additional: AdditionalProperties::Forbid,
+    };
+    let source = SchemaSource::RefPackPath("schemas/config.cbor".into());
+    let registry = BTreeMap::from([(
+        "schemas/config.cbor".to_string(),
+        CborBytes::new(canonical::to_canonical_cbor(&schema).expect("encode schema")),
+    )]);
+
+    let input = answers(&[("retries", Value::Integer(9.into()))]);
+    let errors = errors(validate_answers_with(
+        &source,
+        &input,
+        CanonicalPolicy::Off,
+        &registry,
+    ));
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].question_id, "retries");
+    assert!(matches!(
+        &errors[0].kind,
+        AnswerErrorKind::Schema { code, pointer, .. } if code == "SCHEMA_MAXIMUM" && pointer == "/retries"
+    ));
+}
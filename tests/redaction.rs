@@ -0,0 +1,229 @@
+use std::collections::BTreeMap;
+
+use ciborium::value::Value;
+use greentic_types::redaction::{
+    PayloadDirection, REDACTED, Redacted, Redactor, rules_from_schema_with,
+};
+use greentic_types::schemas::common::definitions::SchemaDefinitions;
+use greentic_types::schemas::common::schema_ir::{AdditionalProperties, SchemaIr};
+use greentic_types::schemas::component::v0_6_0::{
+    ComponentOperation, ComponentRunInput, ComponentRunOutput,
+};
+use greentic_types::{
+    ComponentRedactionKind as RedactionKind, ComponentRedactionRule as RedactionRule,
+    InvocationEnvelope, NodeFailure, Redact, TenantCtx,
+};
+use serde_json::json;
+
+fn rule(pointer: &str, kind: RedactionKind) -> RedactionRule {
+    RedactionRule {
+        json_pointer: pointer.into(),
+        kind,
+    }
+}
+
+fn secret_string() -> SchemaIr {
+    SchemaIr::String {
+        min_len: None,
+        max_len: None,
+        regex: None,
+        format: Some("password".into()),
+    }
+}
+
+#[test]
+fn applies_rules_with_wildcards_to_json() {
+    let redactor = Redactor::new(vec![
+        rule("/api_key", RedactionKind::Secret),
+        rule("/card", RedactionKind::Mask),
+        rule("/pin", RedactionKind::Mask),
+        rule("/users/*/token", RedactionKind::Secret),
+        rule("/users/*/internal", RedactionKind::Drop),
+        rule("/headers/*", RedactionKind::Mask),
+        // Stronger rules win over weaker ones on the same value.
+        rule("/api_key", RedactionKind::Mask),
+        rule("/missing/path", RedactionKind::Drop),
+    ]);
+    let value = redactor.redacted_json(&json!({
+        "api_key": "sk-live-123",
+        "card": "4111111111111111",
+        "pin": 1234,
+        "users": [
+            { "name": "ada", "token": "a", "internal": true },
+            { "name": "bob", "token": "b" }
+        ],
+        "headers": { "authorization": "Bearer xyz", "x-trace": "t" }
+    }));
+    assert_eq!(
+        value,
+        json!({
+            "api_key": "[REDACTED]",
+            "card": "****1111",
+            "pin": "****",
+            "users": [
+                { "name": "ada", "token": "[REDACTED]" },
+                { "name": "bob", "token": "[REDACTED]" }
+            ],
+            "headers": { "authorization": "****", "x-trace": "****" }
+        })
+    );
+}
+
+#[test]
+fn applies_rules_to_cbor() {
+    let redactor = Redactor::new(vec![
+        rule("/items/1", RedactionKind::Drop),
+        rule("/items/*/secret", RedactionKind::Secret),
+    ]);
+    let item = |secret: &str| Value::Map(vec![(Value::Text("secret".into()), secret.into())]);
+    let value = Value::Map(vec![(
+        Value::Text("items".into()),
+        Value::Array(vec![item("a"), item("b"), item("c")]),
+    )]);
+    let redacted = redactor.redacted_cbor(&value);
+    let expected_item = Value::Map(vec![(Value::Text("secret".into()), REDACTED.into())]);
+    assert_eq!(
+        redacted,
+        Value::Map(vec![(
+            Value::Text("items".into()),
+            Value::Array(vec![expected_item.clone(), expected_item]),
+        )])
+    );
+}
+
+#[test]
+fn derives_rules_from_schema_formats() {
+    let credentials = SchemaIr::Object {
+        properties: BTreeMap::from([
+            ("password".to_string(), secret_string()),
+            ("user".to_string(), SchemaIr::Null),
+        ]),
+        required: Vec::new(),
+        additional: AdditionalProperties::Allow,
+    };
+    let schema = SchemaIr::Object {
+        properties: BTreeMap::from([
+            (
+                "accounts".to_string(),
+                SchemaIr::Array {
+                    items: Box::new(SchemaIr::Ref {
+                        id: "credentials".into(),
+                    }),
+                    min_items: None,
+                    max_items: None,
+                },
+            ),
+            (
+                "token".to_string(),
+                SchemaIr::OneOf {
+                    variants: vec![secret_string(), SchemaIr::Null],
+                },
+            ),
+        ]),
+        required: Vec::new(),
+        additional: AdditionalProperties::Schema(Box::new(secret_string())),
+    };
+    assert_eq!(
+        Redactor::from_schema(&schema).rules(),
+        &[
+            rule("/token", RedactionKind::Secret),
+            rule("/*", RedactionKind::Secret),
+        ]
+    );
+    let defs = SchemaDefinitions::new().with("credentials", credentials);
+    assert_eq!(
+        rules_from_schema_with(&schema, &defs),
+        vec![
+            rule("/accounts/*/password", RedactionKind::Secret),
+            rule("/token", RedactionKind::Secret),
+            rule("/*", RedactionKind::Secret),
+        ]
+    );
+}
+
+#[test]
+fn operation_redactors_use_the_schema_of_their_payload() {
+    let object = |field: &str| SchemaIr::Object {
+        properties: BTreeMap::from([(field.to_string(), secret_string())]),
+        required: Vec::new(),
+        additional: AdditionalProperties::Allow,
+    };
+    let operation = ComponentOperation {
+        id: "login".into(),
+        display_name: None,
+        input: ComponentRunInput {
+            schema: object("password"),
+        },
+        output: ComponentRunOutput {
+            schema: object("session"),
+        },
+        defaults: BTreeMap::new(),
+        redactions: vec![rule("/trace", RedactionKind::Drop)],
+        constraints: BTreeMap::new(),
+        schema_hash: String::new(),
+    };
+    let payload = json!({ "password": "hunter2", "session": "s3ss", "trace": "t" });
+
+    let input = Redactor::for_operation(&operation, PayloadDirection::Input);
+    assert_eq!(
+        input.redacted_json(&payload),
+        json!({ "password": REDACTED, "session": "s3ss" })
+    );
+    let output = Redactor::for_operation(&operation, PayloadDirection::Output);
+    assert_eq!(
+        output.redacted_json(&payload),
+        json!({ "password": "hunter2", "session": REDACTED })
+    );
+}
+
+#[test]
+fn redacts_envelopes_and_failures_for_logging() {
+    let redactor = Redactor::new(vec![
+        rule("/password", RedactionKind::Secret),
+        rule("/dsn", RedactionKind::Drop),
+    ]);
+    let mut payload = Vec::new();
+    ciborium::ser::into_writer(
+        &json!({ "user": "ada", "password": "hunter2" }),
+        &mut payload,
+    )
+    .expect("encode payload");
+    let envelope = InvocationEnvelope {
+        ctx: TenantCtx::new(
+            "prod".parse().expect("env"),
+            "tenant-1".parse().expect("tenant"),
+        ),
+        flow_id: "login".into(),
+        node_id: Some("auth".into()),
+        op: "on_message".into(),
+        payload,
+        metadata: Vec::new(),
+    };
+    let rendered = Redacted::new(&envelope, &redactor).to_string();
+    assert_eq!(
+        rendered,
+        r#"login on_message node=auth payload={"password":"[REDACTED]","user":"ada"}"#
+    );
+    assert!(!format!("{:?}", Redacted::new(&envelope, &redactor)).contains("hunter2"));
+
+    let mut opaque = envelope.clone();
+    opaque.payload = vec![0xff, 0x00, 0x13];
+    opaque.redact(&redactor);
+    assert_eq!(opaque.payload, REDACTED.as_bytes());
+
+    let failure = NodeFailure {
+        code: "DB_CONNECT".into(),
+        message: "connection refused".into(),
+        details: BTreeMap::from([
+            ("dsn".to_string(), "postgres://u:p@db".to_string()),
+            ("password".to_string(), "hunter2".to_string()),
+            ("host".to_string(), "db".to_string()),
+        ]),
+        transcript_offsets: Vec::new(),
+        log_paths: Vec::new(),
+    };
+    assert_eq!(
+        Redacted::new(&failure, &redactor).to_string(),
+        "DB_CONNECT: connection refused host=db password=[REDACTED]"
+    );
+}
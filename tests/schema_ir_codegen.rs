@@ -0,0 +1,301 @@
+use std::collections::BTreeMap;
+
+use ciborium::value::Value;
+use greentic_types::schemas::common::codegen::TypeGenerator;
+use greentic_types::schemas::common::definitions::SchemaDefinitions;
+use greentic_types::schemas::common::schema_ir::{AdditionalProperties, SchemaIr};
+use serde_json::json;
+
+fn text() -> SchemaIr {
+    SchemaIr::String {
+        min_len: None,
+        max_len: None,
+        regex: None,
+        format: None,
+    }
+}
+
+fn obj(
+    properties: Vec<(&str, SchemaIr)>,
+    required: &[&str],
+    additional: AdditionalProperties,
+) -> SchemaIr {
+    SchemaIr::Object {
+        properties: properties
+            .into_iter()
+            .map(|(name, schema)| (name.to_string(), schema))
+            .collect::<BTreeMap<_, _>>(),
+        required: required.iter().map(|name| name.to_string()).collect(),
+        additional,
+    }
+}
+
+fn tag(value: &str) -> SchemaIr {
+    SchemaIr::Enum {
+        values: vec![Value::Text(value.into())],
+    }
+}
+
+fn generator_inputs() -> (SchemaIr, SchemaIr, SchemaDefinitions) {
+    let node = obj(
+        vec![
+            ("name", text()),
+            (
+                "children",
+                SchemaIr::Array {
+                    items: Box::new(SchemaIr::Ref {
+                        id: "tree-node".into(),
+                    }),
+                    min_items: None,
+                    max_items: None,
+                },
+            ),
+            (
+                "parent",
+                SchemaIr::OneOf {
+                    variants: vec![
+                        SchemaIr::Ref {
+                            id: "tree-node".into(),
+                        },
+                        SchemaIr::Null,
+                    ],
+                },
+            ),
+        ],
+        &["name"],
+        AdditionalProperties::Forbid,
+    );
+    let defs = SchemaDefinitions::new().with("tree-node", node);
+    let shape = SchemaIr::OneOf {
+        variants: vec![
+            obj(
+                vec![
+                    ("kind", tag("circle")),
+                    (
+                        "radius",
+                        SchemaIr::Float {
+                            min: None,
+                            max: None,
+                        },
+                    ),
+                ],
+                &["kind", "radius"],
+                AdditionalProperties::Forbid,
+            ),
+            obj(
+                vec![
+                    ("kind", tag("rect")),
+                    (
+                        "width",
+                        SchemaIr::Int {
+                            min: None,
+                            max: None,
+                        },
+                    ),
+                    (
+                        "height",
+                        SchemaIr::Int {
+                            min: None,
+                            max: None,
+                        },
+                    ),
+                ],
+                &["kind", "width"],
+                AdditionalProperties::Forbid,
+            ),
+        ],
+    };
+    let config = obj(
+        vec![
+            ("api-key", text()),
+            (
+                "type",
+                SchemaIr::Enum {
+                    values: vec![Value::Text("fast".into()), Value::Text("safe-mode".into())],
+                },
+            ),
+            (
+                "level",
+                SchemaIr::Enum {
+                    values: vec![Value::Integer(1.into()), Value::Integer(2.into())],
+                },
+            ),
+            (
+                "timeout",
+                SchemaIr::OneOf {
+                    variants: vec![
+                        SchemaIr::Int {
+                            min: None,
+                            max: None,
+                        },
+                        SchemaIr::Null,
+                    ],
+                },
+            ),
+            ("shape", shape),
+            (
+                "value",
+                SchemaIr::OneOf {
+                    variants: vec![
+                        text(),
+                        SchemaIr::Int {
+                            min: None,
+                            max: None,
+                        },
+                        obj(
+                            vec![("x", SchemaIr::Bool)],
+                            &["x"],
+                            AdditionalProperties::Allow,
+                        ),
+                    ],
+                },
+            ),
+            (
+                "labels",
+                obj(vec![], &[], AdditionalProperties::Schema(Box::new(text()))),
+            ),
+            (
+                "tree",
+                SchemaIr::Ref {
+                    id: "tree-node".into(),
+                },
+            ),
+            ("blob", SchemaIr::Bytes),
+        ],
+        &["api-key", "type", "shape"],
+        AdditionalProperties::Forbid,
+    );
+    let run_input = SchemaIr::Array {
+        items: Box::new(text()),
+        min_items: None,
+        max_items: None,
+    };
+    (config, run_input, defs)
+}
+
+#[allow(dead_code)]
+mod generated {
+    include!("../fixtures/schema_ir/codegen/config.rs");
+}
+
+#[test]
+fn renders_typescript_and_rust_fixtures() {
+    let (config, run_input, defs) = generator_inputs();
+    let generator = TypeGenerator::new()
+        .with_root("Config", &config)
+        .with_root("run_input", &run_input)
+        .with_definitions(&defs);
+    assert_eq!(
+        generator.typescript().expect("typescript"),
+        include_str!("../fixtures/schema_ir/codegen/config.ts")
+    );
+    assert_eq!(
+        generator.rust().expect("rust"),
+        include_str!("../fixtures/schema_ir/codegen/config.rs")
+    );
+    // Output is stable across runs.
+    assert_eq!(generator.rust(), generator.rust());
+}
+
+#[test]
+fn generated_rust_roundtrips_valid_values() {
+    let (config, _, defs) = generator_inputs();
+    let value = json!({
+        "api-key": "k",
+        "type": "safe-mode",
+        "shape": { "kind": "rect", "width": 3 },
+        "value": { "x": true, "note": "extra" },
+        "labels": { "team": "ops" },
+        "tree": { "name": "root", "children": [{ "name": "leaf" }] },
+        "timeout": null
+    });
+    assert_eq!(config.validate_json_with(&value, &defs), Ok(()));
+    let typed: generated::Config = serde_json::from_value(value).expect("deserialize config");
+    assert_eq!(typed.r#type, generated::ConfigType::SafeMode);
+    assert!(matches!(
+        typed.shape,
+        generated::ConfigShape::Rect(generated::ConfigShapeRect { width: 3, .. })
+    ));
+    assert!(matches!(
+        typed.value,
+        Some(generated::ConfigValue::Variant3(_))
+    ));
+    let encoded = serde_json::to_value(&typed).expect("serialize config");
+    assert_eq!(encoded["shape"], json!({ "kind": "rect", "width": 3 }));
+    assert_eq!(encoded["tree"]["children"][0]["name"], "leaf");
+    assert!(serde_json::from_value::<generated::Config>(json!({ "bogus": 1 })).is_err());
+}
+
+#[test]
+fn generated_bytes_fields_use_the_schema_wire_format() {
+    let (config, _, defs) = generator_inputs();
+    let typed: generated::Config = serde_json::from_value(json!({
+        "api-key": "k",
+        "type": "safe-mode",
+        "shape": { "kind": "rect", "width": 3 },
+        "tree": { "name": "root" },
+        "blob": "AQI="
+    }))
+    .expect("deserialize config");
+    assert_eq!(typed.blob, Some(generated::ByteString(vec![1, 2])));
+
+    // JSON carries bytes as base64 text.
+    let json = serde_json::to_value(&typed).expect("serialize json");
+    assert_eq!(json["blob"], "AQI=");
+    assert_eq!(config.validate_json_with(&json, &defs), Ok(()));
+
+    // CBOR carries bytes as a byte string, not an array of integers.
+    let mut cbor = Vec::new();
+    ciborium::ser::into_writer(&typed, &mut cbor).expect("serialize cbor");
+    let value: ciborium::value::Value =
+        ciborium::de::from_reader(cbor.as_slice()).expect("decode cbor");
+    assert_eq!(config.validate_with(&value, &defs), Ok(()));
+    let back: generated::Config =
+        ciborium::de::from_reader(cbor.as_slice()).expect("deserialize cbor");
+    assert_eq!(back, typed);
+}
+
+#[test]
+fn unresolved_refs_are_errors() {
+    let schema = SchemaIr::Ref {
+        id: "missing".into(),
+    };
+    let generator = TypeGenerator::new().with_root("Root", &schema);
+    assert!(generator.typescript().is_err());
+    let defs = SchemaDefinitions::new().with("other", SchemaIr::Bool);
+    assert!(generator.with_definitions(&defs).rust().is_err());
+}
+
+fn colliding_inputs() -> SchemaIr {
+    obj(
+        vec![("api-key", text()), ("api_key", text()), ("extra", text())],
+        &["api-key"],
+        AdditionalProperties::Allow,
+    )
+}
+
+#[allow(dead_code)]
+mod colliding {
+    include!("../fixtures/schema_ir/codegen/colliding.rs");
+}
+
+#[test]
+fn colliding_property_names_get_distinct_fields() {
+    let schema = colliding_inputs();
+    let generator = TypeGenerator::new().with_root("Colliding", &schema);
+    assert_eq!(
+        generator.rust().expect("rust"),
+        include_str!("../fixtures/schema_ir/codegen/colliding.rs")
+    );
+    let typed: colliding::Colliding = serde_json::from_value(json!({
+        "api-key": "a",
+        "api_key": "b",
+        "extra": "c",
+        "other": 1
+    }))
+    .expect("deserialize");
+    assert_eq!(typed.api_key, "a");
+    assert_eq!(typed.api_key_2.as_deref(), Some("b"));
+    assert_eq!(typed.extra.as_deref(), Some("c"));
+    assert_eq!(typed.extra_2["other"], json!(1));
+}
@@ -0,0 +1,240 @@
+use std::collections::BTreeMap;
+
+use ciborium::value::Value;
+use greentic_types::schemas::common::compat::{CompatImpact, Compatibility, compat_describe};
+use greentic_types::schemas::common::definitions::SchemaDefinitions;
+use greentic_types::schemas::common::schema_ir::{
+    AdditionalProperties, SchemaIr, compat, compat_with,
+};
+use greentic_types::schemas::component::v0_6_0::{
+    ComponentDescribe, ComponentInfo, ComponentOperation, ComponentRunInput, ComponentRunOutput,
+};
+use greentic_types::validate::Severity;
+use semver::Version;
+
+fn int(min: Option<i64>, max: Option<i64>) -> SchemaIr {
+    SchemaIr::Int { min, max }
+}
+
+fn text() -> SchemaIr {
+    SchemaIr::String {
+        min_len: None,
+        max_len: None,
+        regex: None,
+        format: None,
+    }
+}
+
+fn modes(values: &[&str]) -> SchemaIr {
+    SchemaIr::Enum {
+        values: values.iter().map(|v| Value::Text((*v).into())).collect(),
+    }
+}
+
+fn object(
+    properties: Vec<(&str, SchemaIr)>,
+    required: &[&str],
+    additional: AdditionalProperties,
+) -> SchemaIr {
+    SchemaIr::Object {
+        properties: properties
+            .into_iter()
+            .map(|(name, schema)| (name.to_string(), schema))
+            .collect::<BTreeMap<_, _>>(),
+        required: required.iter().map(|name| name.to_string()).collect(),
+        additional,
+    }
+}
+
+fn codes(report: &greentic_types::schemas::common::compat::CompatReport) -> Vec<(&str, &str)> {
+    report
+        .findings
+        .iter()
+        .map(|f| (f.path.as_str(), f.code.as_str()))
+        .collect()
+}
+
+#[test]
+fn identical_and_widening_changes() {
+    let old = object(
+        vec![
+            ("retries", int(Some(0), Some(5))),
+            ("mode", modes(&["fast"])),
+        ],
+        &["retries"],
+        AdditionalProperties::Forbid,
+    );
+    assert_eq!(compat(&old, &old).compatibility, Compatibility::Full);
+
+    let new = object(
+        vec![
+            ("retries", int(Some(0), Some(10))),
+            ("mode", modes(&["fast", "safe"])),
+        ],
+        &[],
+        AdditionalProperties::Allow,
+    );
+    let report = compat(&old, &new);
+    assert_eq!(report.compatibility, Compatibility::Backward);
+    assert!(report.is_backward_compatible());
+    assert!(!report.is_forward_compatible());
+    assert_eq!(
+        codes(&report),
+        vec![
+            ("", "COMPAT_ADDITIONAL_WIDENED"),
+            ("/mode", "COMPAT_ENUM_VALUES_ADDED"),
+            ("/retries", "COMPAT_RANGE_WIDENED"),
+            ("/retries", "COMPAT_REQUIRED_REMOVED"),
+        ]
+    );
+}
+
+#[test]
+fn narrowing_changes_break_backward_compatibility() {
+    let old = object(
+        vec![
+            ("retries", int(Some(0), Some(10))),
+            ("mode", modes(&["fast", "safe"])),
+        ],
+        &[],
+        AdditionalProperties::Allow,
+    );
+    let new = object(
+        vec![
+            ("retries", int(Some(1), Some(10))),
+            ("mode", modes(&["fast"])),
+            ("region", text()),
+        ],
+        &["region"],
+        AdditionalProperties::Forbid,
+    );
+    let report = compat(&old, &new);
+    assert_eq!(report.compatibility, Compatibility::Forward);
+    assert_eq!(
+        codes(&report),
+        vec![
+            ("", "COMPAT_ADDITIONAL_NARROWED"),
+            ("/mode", "COMPAT_ENUM_VALUES_REMOVED"),
+            ("/region", "COMPAT_REQUIRED_ADDED"),
+            ("/retries", "COMPAT_RANGE_NARROWED"),
+        ]
+    );
+    assert_eq!(report.findings[1].message, "Enum values removed: `safe`.");
+}
+
+#[test]
+fn type_changes_and_unions() {
+    assert_eq!(
+        compat(&text(), &int(None, None)).compatibility,
+        Compatibility::Breaking
+    );
+    assert_eq!(
+        compat(
+            &int(None, None),
+            &SchemaIr::Float {
+                min: None,
+                max: None
+            }
+        )
+        .compatibility,
+        Compatibility::Backward
+    );
+
+    // Making a field nullable only widens it.
+    let nullable = SchemaIr::OneOf {
+        variants: vec![text(), SchemaIr::Null],
+    };
+    let report = compat(&text(), &nullable);
+    assert_eq!(report.compatibility, Compatibility::Backward);
+    assert_eq!(codes(&report), vec![("", "COMPAT_VARIANT_ADDED")]);
+    assert_eq!(
+        compat(&nullable, &text()).compatibility,
+        Compatibility::Forward
+    );
+
+    // Replacing a string with an enum narrows it.
+    assert_eq!(
+        compat(&text(), &modes(&["a"])).findings[0].impact,
+        CompatImpact::Narrowing
+    );
+}
+
+#[test]
+fn resolves_references_on_both_sides() {
+    let root = object(
+        vec![("child", SchemaIr::Ref { id: "node".into() })],
+        &[],
+        AdditionalProperties::Allow,
+    );
+    let old_defs = SchemaDefinitions::new().with("node", root.clone());
+    let new_defs = SchemaDefinitions::new().with(
+        "node",
+        object(
+            vec![
+                ("child", SchemaIr::Ref { id: "node".into() }),
+                ("size", int(Some(0), None)),
+            ],
+            &["size"],
+            AdditionalProperties::Allow,
+        ),
+    );
+    let report = compat_with(&root, &old_defs, &root, &new_defs);
+    assert_eq!(report.compatibility, Compatibility::Forward);
+    assert_eq!(
+        codes(&report),
+        vec![("/child/size", "COMPAT_REQUIRED_ADDED")]
+    );
+}
+
+fn describe(input: SchemaIr, output: SchemaIr) -> ComponentDescribe {
+    ComponentDescribe {
+        info: ComponentInfo {
+            id: "demo".into(),
+            version: "1.0.0".into(),
+            role: "tool".into(),
+            display_name: None,
+        },
+        provided_capabilities: Vec::new(),
+        required_capabilities: Vec::new(),
+        metadata: BTreeMap::new(),
+        operations: vec![ComponentOperation {
+            id: "run".into(),
+            display_name: None,
+            input: ComponentRunInput { schema: input },
+            output: ComponentRunOutput { schema: output },
+            defaults: BTreeMap::new(),
+            redactions: Vec::new(),
+            constraints: BTreeMap::new(),
+            schema_hash: String::new(),
+        }],
+        config_schema: SchemaIr::Null,
+    }
+}
+
+#[test]
+fn describe_compat_inverts_outputs_and_checks_version_bumps() {
+    let old = describe(modes(&["a"]), modes(&["x", "y"]));
+    // Widened input and narrowed output are both safe for existing callers.
+    let new = describe(modes(&["a", "b"]), modes(&["x"]));
+    let report = compat_describe(&old, &new);
+    assert_eq!(report.compatibility, Compatibility::Backward);
+
+    let v1 = Version::new(1, 2, 0);
+    let patch = report.check_version_bump(&v1, &Version::new(1, 2, 1));
+    assert!(patch.iter().all(|d| d.severity == Severity::Error));
+    assert_eq!(
+        patch[0].code, "SCHEMA_COMPAT_ENUM_VALUES_ADDED",
+        "{patch:?}"
+    );
+    assert_eq!(patch[0].path.as_deref(), Some("/operations/run/input"));
+    let minor = report.check_version_bump(&v1, &Version::new(1, 3, 0));
+    assert!(minor.iter().all(|d| d.severity == Severity::Info));
+
+    let breaking = compat_describe(&new, &old);
+    assert_eq!(breaking.compatibility, Compatibility::Forward);
+    let diags = breaking.check_version_bump(&v1, &Version::new(1, 3, 0));
+    assert!(diags.iter().any(|d| d.severity == Severity::Error));
+    // Below 1.0.0 a minor bump is enough for breaking changes.
+    let diags = breaking.check_version_bump(&Version::new(0, 4, 0), &Version::new(0, 5, 0));
+    assert!(diags.iter().all(|d| d.severity == Severity::Info));
+}
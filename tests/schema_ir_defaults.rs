@@ -0,0 +1,199 @@
+mod common;
+
+use std::collections::BTreeMap;
+
+use ciborium::value::Value;
+use greentic_types::schemas::common::defaults::{
+    Coercion, DefaultsApplier, ValueChange, ValueChangeKind,
+};
+use greentic_types::schemas::common::schema_ir::{AdditionalProperties, SchemaIr};
+use greentic_types::schemas::component::v0_6_0::{QaMode, QuestionKind};
+use serde_json::json;
+
+fn config_schema() -> SchemaIr {
+    let db = SchemaIr::Object {
+        properties: BTreeMap::from([
+            (
+                "host".to_string(),
+                SchemaIr::String {
+                    min_len: None,
+                    max_len: None,
+                    regex: None,
+                    format: None,
+                },
+            ),
+            (
+                "port".to_string(),
+                SchemaIr::Int {
+                    min: Some(1),
+                    max: Some(65535),
+                },
+            ),
+        ]),
+        required: vec!["host".into(), "port".into()],
+        additional: AdditionalProperties::Forbid,
+    };
+    SchemaIr::Object {
+        properties: BTreeMap::from([
+            ("db".to_string(), db),
+            ("debug".to_string(), SchemaIr::Bool),
+            (
+                "ratio".to_string(),
+                SchemaIr::Float {
+                    min: None,
+                    max: None,
+                },
+            ),
+            (
+                "tags".to_string(),
+                SchemaIr::Array {
+                    items: Box::new(SchemaIr::Int {
+                        min: None,
+                        max: None,
+                    }),
+                    min_items: None,
+                    max_items: None,
+                },
+            ),
+            (
+                "level".to_string(),
+                SchemaIr::Enum {
+                    values: vec![Value::Integer(1.into()), Value::Integer(2.into())],
+                },
+            ),
+            (
+                "timeout".to_string(),
+                SchemaIr::OneOf {
+                    variants: vec![
+                        SchemaIr::Null,
+                        SchemaIr::Int {
+                            min: Some(0),
+                            max: None,
+                        },
+                    ],
+                },
+            ),
+        ]),
+        required: vec!["db".into(), "debug".into()],
+        additional: AdditionalProperties::Forbid,
+    }
+}
+
+fn coerced(pointer: &str, from: &str, to: &str) -> ValueChange {
+    ValueChange {
+        pointer: pointer.into(),
+        kind: ValueChangeKind::Coerced {
+            from: from.into(),
+            to: to.into(),
+        },
+    }
+}
+
+fn filled(pointer: &str) -> ValueChange {
+    ValueChange {
+        pointer: pointer.into(),
+        kind: ValueChangeKind::DefaultFilled,
+    }
+}
+
+#[test]
+fn fills_defaults_and_coerces_cli_strings() {
+    let schema = config_schema();
+    let defaults = BTreeMap::from([
+        ("debug".to_string(), Value::Bool(false)),
+        ("/db/host".to_string(), Value::Text("localhost".into())),
+        ("/db/port".to_string(), Value::Text("5432".into())),
+    ]);
+    let input = json!({
+        "ratio": "0.25",
+        "tags": "1, 2,3",
+        "level": "2",
+        "timeout": "30"
+    });
+
+    let applied = DefaultsApplier::new(&schema)
+        .with_defaults(&defaults)
+        .apply_json(&input);
+    assert_eq!(
+        applied.value,
+        json!({
+            "ratio": 0.25,
+            "tags": [1, 2, 3],
+            "level": 2,
+            "timeout": 30,
+            "db": { "host": "localhost", "port": 5432 },
+            "debug": false
+        })
+    );
+    assert_eq!(
+        applied.changes,
+        vec![
+            coerced("/level", "string", "integer"),
+            coerced("/ratio", "string", "number"),
+            coerced("/tags", "string", "array"),
+            coerced("/tags/0", "string", "integer"),
+            coerced("/tags/1", "string", "integer"),
+            coerced("/tags/2", "string", "integer"),
+            coerced("/timeout", "string", "integer"),
+            filled("/db/host"),
+            filled("/db/port"),
+            coerced("/db/port", "string", "integer"),
+            filled("/debug"),
+        ]
+    );
+    assert_eq!(schema.validate_json(&applied.value), Ok(()));
+}
+
+#[test]
+fn strict_mode_only_fills_defaults() {
+    let schema = config_schema();
+    let defaults = BTreeMap::from([("debug".to_string(), Value::Text("yes".into()))]);
+    let applied = DefaultsApplier::new(&schema)
+        .with_defaults(&defaults)
+        .with_coercion(Coercion::Strict)
+        .apply_json(&json!({ "ratio": "0.5" }));
+    assert_eq!(applied.value, json!({ "ratio": "0.5", "debug": "yes" }));
+    assert_eq!(applied.changes, vec![filled("/debug")]);
+
+    let lenient = DefaultsApplier::new(&schema)
+        .with_defaults(&defaults)
+        .apply_json(&json!({}));
+    assert_eq!(lenient.value, json!({ "debug": true }));
+}
+
+#[test]
+fn leaves_uncoercible_values_for_validation() {
+    let schema = config_schema();
+    let applied = DefaultsApplier::new(&schema).apply_json(&json!({
+        "debug": "maybe",
+        "timeout": "soon",
+        "level": "3"
+    }));
+    assert!(applied.changes.is_empty());
+    let codes: Vec<String> = schema
+        .validate_json(&applied.value)
+        .unwrap_err()
+        .into_iter()
+        .map(|violation| format!("{} {}", violation.pointer, violation.code))
+        .collect();
+    assert!(codes.contains(&"/debug SCHEMA_TYPE_MISMATCH".to_string()));
+    assert!(codes.contains(&"/timeout SCHEMA_ONE_OF_NO_MATCH".to_string()));
+    assert!(codes.contains(&"/level SCHEMA_ENUM".to_string()));
+}
+
+#[test]
+fn qa_answer_defaults_prefer_question_defaults() {
+    let mut region = common::question("region", QuestionKind::Text, false);
+    region.default = Some(Value::Text("eu".into()));
+    let mut spec = common::spec(
+        QaMode::Setup,
+        vec![region, common::question("tier", QuestionKind::Text, false)],
+    );
+    spec.defaults = BTreeMap::from([
+        ("region".to_string(), Value::Text("us".into())),
+        ("tier".to_string(), Value::Text("free".into())),
+    ]);
+    let defaults = spec.answer_defaults();
+    assert_eq!(defaults["region"], Value::Text("eu".into()));
+    assert_eq!(defaults["tier"], Value::Text("free".into()));
+}
@@ -0,0 +1,154 @@
+use std::collections::BTreeMap;
+
+use greentic_types::schemas::common::definitions::{SchemaDefinitions, SchemaRefError};
+use greentic_types::schemas::common::schema_ir::{AdditionalProperties, SchemaIr};
+use greentic_types::schemas::component::v0_6_0::{schema_hash, schema_hash_with};
+use serde_json::json;
+
+fn reference(id: &str) -> SchemaIr {
+    SchemaIr::Ref { id: id.into() }
+}
+
+fn string() -> SchemaIr {
+    SchemaIr::String {
+        min_len: None,
+        max_len: None,
+        regex: None,
+        format: None,
+    }
+}
+
+/// `node = { name: string, children?: [node] }`
+fn tree() -> SchemaDefinitions {
+    SchemaDefinitions::new()
+        .with(
+            "node",
+            SchemaIr::Object {
+                properties: BTreeMap::from([
+                    ("name".to_string(), reference("name")),
+                    (
+                        "children".to_string(),
+                        SchemaIr::Array {
+                            items: Box::new(reference("node")),
+                            min_items: None,
+                            max_items: None,
+                        },
+                    ),
+                ]),
+                required: vec!["name".into()],
+                additional: AdditionalProperties::Forbid,
+            },
+        )
+        .with("name", reference("label"))
+        .with("label", string())
+}
+
+#[test]
+fn validates_recursive_schemas_through_definitions() {
+    let defs = tree();
+    assert_eq!(defs.check(), Ok(()));
+    assert_eq!(defs.resolve("name"), Ok(&string()));
+
+    let root = reference("node");
+    let valid = json!({ "name": "a", "children": [{ "name": "b", "children": [] }] });
+    assert_eq!(root.validate_json_with(&valid, &defs), Ok(()));
+
+    let invalid = json!({ "name": "a", "children": [{ "children": [{ "name": 1 }] }] });
+    let pointers: Vec<String> = root
+        .validate_json_with(&invalid, &defs)
+        .unwrap_err()
+        .into_iter()
+        .map(|violation| violation.pointer)
+        .collect();
+    assert_eq!(
+        pointers,
+        vec!["/children/0/children/0/name", "/children/0/name"]
+    );
+
+    // Without definitions the reference stays unresolved.
+    assert!(root.validate_json(&valid).is_err());
+}
+
+#[test]
+fn detects_unguarded_cycles_and_missing_definitions() {
+    let defs = SchemaDefinitions::new().with("a", reference("b")).with(
+        "b",
+        SchemaIr::OneOf {
+            variants: vec![SchemaIr::Null, reference("a")],
+        },
+    );
+    assert_eq!(
+        defs.check(),
+        Err(SchemaRefError::Cycle(vec![
+            "a".into(),
+            "b".into(),
+            "a".into()
+        ]))
+    );
+    let violations = reference("a")
+        .validate_json_with(&json!(1), &defs)
+        .unwrap_err();
+    assert!(
+        violations
+            .iter()
+            .any(|v| v.code == "SCHEMA_ONE_OF_NO_MATCH")
+    );
+
+    let dangling = SchemaDefinitions::new().with("a", reference("missing"));
+    assert_eq!(
+        dangling.check(),
+        Err(SchemaRefError::Unresolved("missing".into()))
+    );
+}
+
+#[test]
+fn dereferences_flat_schemas_and_rejects_recursive_ones() {
+    let defs = tree();
+    assert_eq!(reference("name").dereference(&defs), Ok(string()));
+    assert_eq!(
+        reference("node").dereference(&defs),
+        Err(SchemaRefError::Recursive("node".into()))
+    );
+}
+
+#[test]
+fn hash_covers_referenced_definitions() {
+    let input = reference("name");
+    let defs = tree();
+    let with_refs =
+        schema_hash_with(&input, &SchemaIr::Null, &SchemaIr::Null, &defs).expect("hash");
+    let changed = tree().with("label", SchemaIr::Bool);
+    let with_changed =
+        schema_hash_with(&input, &SchemaIr::Null, &SchemaIr::Null, &changed).expect("hash");
+    assert_ne!(with_refs, with_changed);
+
+    // Schemas without references hash exactly like `schema_hash`.
+    assert_eq!(
+        schema_hash_with(&string(), &SchemaIr::Null, &SchemaIr::Null, &defs).expect("hash"),
+        schema_hash(&string(), &SchemaIr::Null, &SchemaIr::Null).expect("hash")
+    );
+}
+
+#[test]
+fn json_schema_conversion_embeds_definitions() {
+    let defs = tree();
+    let exported = reference("node")
+        .to_json_schema_with(&defs)
+        .expect("convert");
+    assert!(exported.is_lossless(), "{:?}", exported.notes);
+    assert_eq!(exported.schema["$ref"], "#/$defs/node");
+    assert_eq!(
+        exported.schema["$defs"]["node"]["properties"]["children"]["items"]["$ref"],
+        "#/$defs/node"
+    );
+
+    let imported = SchemaIr::from_json_schema_with_definitions(&exported.schema);
+    assert!(imported.is_lossless(), "{:?}", imported.notes);
+    let (root, imported_defs) = imported.schema;
+    assert_eq!(root, reference("node"));
+    assert_eq!(imported_defs, defs);
+
+    let flat = SchemaIr::from_json_schema(&exported.schema);
+    assert_eq!(flat.notes.len(), 1);
+    assert_eq!(flat.notes[0].pointer, "/$defs");
+}
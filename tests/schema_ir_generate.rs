@@ -0,0 +1,260 @@
+use std::collections::BTreeMap;
+
+use ciborium::value::Value;
+use greentic_types::CborBytes;
+use greentic_types::schemas::common::definitions::SchemaDefinitions;
+use greentic_types::schemas::common::generate::{GenerateError, SampleGenerator};
+use greentic_types::schemas::common::schema_ir::{AdditionalProperties, SchemaIr};
+use greentic_types::schemas::component::v0_6_0::{ComponentDescribe, schema_hash};
+
+fn text(min_len: Option<u64>, max_len: Option<u64>, regex: Option<&str>) -> SchemaIr {
+    SchemaIr::String {
+        min_len,
+        max_len,
+        regex: regex.map(String::from),
+        format: None,
+    }
+}
+
+fn config_schema() -> SchemaIr {
+    SchemaIr::Object {
+        properties: BTreeMap::from([
+            ("name".to_string(), text(Some(3), Some(8), None)),
+            (
+                "port".to_string(),
+                SchemaIr::Int {
+                    min: Some(1),
+                    max: Some(65535),
+                },
+            ),
+            (
+                "ratio".to_string(),
+                SchemaIr::Float {
+                    min: Some(0.5),
+                    max: None,
+                },
+            ),
+            (
+                "tags".to_string(),
+                SchemaIr::Array {
+                    items: Box::new(text(None, None, Some("^[a-z]+$"))),
+                    min_items: Some(1),
+                    max_items: Some(3),
+                },
+            ),
+            (
+                "mode".to_string(),
+                SchemaIr::Enum {
+                    values: vec![Value::Text("fast".into()), Value::Text("safe".into())],
+                },
+            ),
+            (
+                "timeout".to_string(),
+                SchemaIr::OneOf {
+                    variants: vec![
+                        SchemaIr::Null,
+                        SchemaIr::Int {
+                            min: Some(0),
+                            max: None,
+                        },
+                    ],
+                },
+            ),
+            (
+                "contact".to_string(),
+                SchemaIr::String {
+                    min_len: None,
+                    max_len: None,
+                    regex: None,
+                    format: Some("email".into()),
+                },
+            ),
+        ]),
+        required: vec!["name".into(), "port".into(), "tags".into()],
+        additional: AdditionalProperties::Forbid,
+    }
+}
+
+fn field<'a>(value: &'a Value, name: &str) -> Option<&'a Value> {
+    match value {
+        Value::Map(entries) => entries
+            .iter()
+            .find(|(key, _)| key == &Value::Text(name.into()))
+            .map(|(_, value)| value),
+        _ => None,
+    }
+}
+
+#[test]
+fn minimal_and_maximal_respect_bounds() {
+    let schema = config_schema();
+    let generator = SampleGenerator::new(&schema);
+
+    let minimal = generator.minimal().expect("minimal");
+    let Value::Map(entries) = &minimal else {
+        panic!("expected map");
+    };
+    assert_eq!(entries.len(), 3);
+    assert_eq!(field(&minimal, "name"), Some(&Value::Text("aaa".into())));
+    assert_eq!(field(&minimal, "port"), Some(&Value::Integer(1.into())));
+    assert_eq!(
+        field(&minimal, "tags"),
+        Some(&Value::Array(vec![Value::Text("a".into())]))
+    );
+
+    let maximal = generator.maximal().expect("maximal");
+    assert_eq!(
+        field(&maximal, "name"),
+        Some(&Value::Text("zzzzzzzz".into()))
+    );
+    assert_eq!(field(&maximal, "port"), Some(&Value::Integer(65535.into())));
+    assert_eq!(field(&maximal, "mode"), Some(&Value::Text("safe".into())));
+    assert_eq!(
+        field(&maximal, "contact"),
+        Some(&Value::Text("user@example.com".into()))
+    );
+    let Some(Value::Array(tags)) = field(&maximal, "tags") else {
+        panic!("expected tags");
+    };
+    assert_eq!(tags.len(), 3);
+    assert_eq!(schema.validate(&maximal), Ok(()));
+}
+
+#[test]
+fn format_samples_pass_format_validation() {
+    for format in [
+        "email",
+        "uri",
+        "url",
+        "uuid",
+        "date",
+        "time",
+        "date-time",
+        "hostname",
+        "ipv4",
+        "ipv6",
+    ] {
+        let schema = SchemaIr::String {
+            min_len: None,
+            max_len: None,
+            regex: None,
+            format: Some(format.into()),
+        };
+        let generator = SampleGenerator::new(&schema);
+        for sample in [
+            generator.minimal(),
+            generator.maximal(),
+            generator.random(7),
+        ] {
+            let sample = sample.unwrap_or_else(|err| panic!("{format}: {err}"));
+            assert_eq!(schema.validate(&sample), Ok(()), "{format}");
+        }
+    }
+}
+
+#[test]
+fn random_samples_are_seeded_and_valid() {
+    let schema = config_schema();
+    let generator = SampleGenerator::new(&schema);
+    for seed in 0..200 {
+        let sample = generator.random(seed).expect("random sample");
+        assert_eq!(schema.validate(&sample), Ok(()), "seed {seed}");
+        assert_eq!(generator.random(seed), Ok(sample));
+    }
+    assert_ne!(generator.random(1), generator.random(2));
+}
+
+#[test]
+fn recursive_refs_terminate_and_unsatisfiable_schemas_fail() {
+    let node = SchemaIr::Object {
+        properties: BTreeMap::from([
+            (
+                "value".to_string(),
+                SchemaIr::Int {
+                    min: None,
+                    max: None,
+                },
+            ),
+            (
+                "children".to_string(),
+                SchemaIr::Array {
+                    items: Box::new(SchemaIr::Ref { id: "node".into() }),
+                    min_items: None,
+                    max_items: None,
+                },
+            ),
+        ]),
+        required: vec!["value".into()],
+        additional: AdditionalProperties::Forbid,
+    };
+    let defs = SchemaDefinitions::new().with("node", node);
+    let root = SchemaIr::Ref { id: "node".into() };
+    let generator = SampleGenerator::new(&root).with_definitions(&defs);
+    let maximal = generator.maximal().expect("maximal");
+    assert_eq!(root.validate_with(&maximal, &defs), Ok(()));
+    for seed in 0..50 {
+        generator.random(seed).expect("random sample");
+    }
+
+    let missing = SchemaIr::Ref { id: "nope".into() };
+    assert!(matches!(
+        SampleGenerator::new(&missing).minimal(),
+        Err(GenerateError::Unsatisfiable { .. })
+    ));
+    let impossible = text(Some(2), Some(2), Some("^[0-9]{5}$"));
+    assert!(matches!(
+        SampleGenerator::new(&impossible).minimal(),
+        Err(GenerateError::Unsatisfiable { .. })
+    ));
+    let ambiguous = SchemaIr::OneOf {
+        variants: vec![SchemaIr::Bool, SchemaIr::Bool],
+    };
+    assert!(SampleGenerator::new(&ambiguous).minimal().is_err());
+}
+
+#[test]
+fn fixture_schemas_accept_their_own_examples() {
+    let bytes = include_bytes!("../fixtures/component/describe_v0_6_0.cbor");
+    let describe = CborBytes::new(bytes.to_vec())
+        .decode::<ComponentDescribe>()
+        .expect("decode component describe");
+    for op in &describe.operations {
+        let hash = schema_hash(&op.input.schema, &op.output.schema, &describe.config_schema)
+            .expect("schema hash");
+        assert_eq!(hash, op.schema_hash);
+
+        let examples = SampleGenerator::new(&op.input.schema)
+            .op_examples()
+            .expect("op examples");
+        assert!(!examples.is_empty());
+        for example in examples {
+            let value = example
+                .input_cbor
+                .decode::<Value>()
+                .expect("decode example");
+            assert_eq!(
+                op.input.schema.validate(&value),
+                Ok(()),
+                "{}",
+                example.title
+            );
+        }
+        for schema in [&op.output.schema, &describe.config_schema] {
+            let sample = SampleGenerator::new(schema).random(7).expect("sample");
+            assert_eq!(schema.validate(&sample), Ok(()));
+        }
+    }
+}
+
+#[cfg(feature = "proptest")]
+mod strategies {
+    use super::*;
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn strategy_values_validate(value in SampleGenerator::new(&config_schema()).strategy()) {
+            prop_assert_eq!(config_schema().validate(&value), Ok(()));
+        }
+    }
+}
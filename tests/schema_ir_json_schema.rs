@@ -0,0 +1,173 @@
+use std::collections::BTreeMap;
+
+use ciborium::value::Value;
+use greentic_types::schemas::common::json_schema::JSON_SCHEMA_DIALECT;
+use greentic_types::schemas::common::schema_ir::{AdditionalProperties, SchemaIr};
+use serde_json::json;
+
+fn sample() -> SchemaIr {
+    SchemaIr::Object {
+        properties: BTreeMap::from([
+            (
+                "name".to_string(),
+                SchemaIr::String {
+                    min_len: Some(1),
+                    max_len: Some(32),
+                    regex: Some("^[a-z]+$".into()),
+                    format: None,
+                },
+            ),
+            (
+                "retries".to_string(),
+                SchemaIr::Int {
+                    min: Some(0),
+                    max: Some(5),
+                },
+            ),
+            (
+                "ratio".to_string(),
+                SchemaIr::Float {
+                    min: Some(0.0),
+                    max: Some(1.5),
+                },
+            ),
+            (
+                "tags".to_string(),
+                SchemaIr::Array {
+                    items: Box::new(SchemaIr::Bool),
+                    min_items: Some(1),
+                    max_items: None,
+                },
+            ),
+            (
+                "mode".to_string(),
+                SchemaIr::Enum {
+                    values: vec![Value::Text("fast".into()), Value::Integer(2.into())],
+                },
+            ),
+            (
+                "target".to_string(),
+                SchemaIr::OneOf {
+                    variants: vec![SchemaIr::Null, SchemaIr::Ref { id: "node".into() }],
+                },
+            ),
+        ]),
+        required: vec!["name".into()],
+        additional: AdditionalProperties::Schema(Box::new(SchemaIr::Bool)),
+    }
+}
+
+#[test]
+fn exports_draft_2020_12() {
+    let converted = sample().to_json_schema();
+    assert!(converted.is_lossless(), "{:?}", converted.notes);
+    let schema = converted.schema;
+    assert_eq!(schema["$schema"], JSON_SCHEMA_DIALECT);
+    assert_eq!(schema["type"], "object");
+    assert_eq!(schema["required"], json!(["name"]));
+    assert_eq!(schema["additionalProperties"], json!({ "type": "boolean" }));
+    assert_eq!(
+        schema["properties"]["name"],
+        json!({ "type": "string", "minLength": 1, "maxLength": 32, "pattern": "^[a-z]+$" })
+    );
+    assert_eq!(schema["properties"]["mode"], json!({ "enum": ["fast", 2] }));
+    assert_eq!(
+        schema["properties"]["target"]["oneOf"][1],
+        json!({ "$ref": "#/$defs/node" })
+    );
+}
+
+#[test]
+fn roundtrips_representable_schemas() {
+    let original = sample();
+    let exported = original.to_json_schema().schema;
+    let imported = SchemaIr::from_json_schema(&exported);
+    assert!(imported.is_lossless(), "{:?}", imported.notes);
+    assert_eq!(imported.schema, original);
+}
+
+#[test]
+fn bytes_export_is_reported_and_roundtrips() {
+    let exported = SchemaIr::Bytes.to_json_schema();
+    assert_eq!(exported.notes.len(), 1);
+    assert_eq!(exported.schema["contentEncoding"], "base64");
+    assert_eq!(
+        SchemaIr::from_json_schema(&exported.schema).schema,
+        SchemaIr::Bytes
+    );
+}
+
+#[test]
+fn imports_tool_schemas_and_reports_unsupported_features() {
+    let tool = json!({
+        "title": "search",
+        "properties": {
+            "query": { "type": "string", "description": "text" },
+            "limit": { "type": "integer", "exclusiveMinimum": 0, "exclusiveMaximum": 101 },
+            "filter": { "type": ["string", "null"] },
+            "payload": {},
+            "kind": { "const": "web" },
+            "extra": { "anyOf": [{ "type": "number" }, { "type": "boolean" }], "default": 1 }
+        },
+        "required": ["query"],
+        "additionalProperties": false,
+        "if": { "required": ["limit"] }
+    });
+    let converted = SchemaIr::from_json_schema(&tool);
+    let SchemaIr::Object {
+        properties,
+        required,
+        additional,
+    } = &converted.schema
+    else {
+        panic!("expected object, got {:?}", converted.schema);
+    };
+    assert_eq!(required, &vec!["query".to_string()]);
+    assert_eq!(additional, &AdditionalProperties::Forbid);
+    assert_eq!(
+        properties["limit"],
+        SchemaIr::Int {
+            min: Some(1),
+            max: Some(100)
+        }
+    );
+    assert!(matches!(
+        &properties["filter"],
+        SchemaIr::OneOf { variants } if variants.len() == 2 && variants[1] == SchemaIr::Null
+    ));
+    assert_eq!(
+        properties["kind"],
+        SchemaIr::Enum {
+            values: vec![Value::Text("web".into())]
+        }
+    );
+
+    let pointers: Vec<&str> = converted
+        .notes
+        .iter()
+        .map(|note| note.pointer.as_str())
+        .collect();
+    assert_eq!(
+        pointers,
+        vec![
+            "/properties/extra/anyOf",
+            "/properties/extra/default",
+            "/properties/payload",
+            "/if",
+        ]
+    );
+
+    // The imported schema validates like the original.
+    assert!(
+        converted
+            .schema
+            .validate_json(&json!({ "query": "rust", "limit": 10, "filter": null, "kind": "web" }))
+            .is_ok()
+    );
+    assert!(
+        converted
+            .schema
+            .validate_json(&json!({ "query": "rust", "limit": 0 }))
+            .is_err()
+    );
+}
@@ -0,0 +1,82 @@
+use greentic_types::schemas::common::schema_ir::SchemaIr;
+use greentic_types::schemas::component::v0_6_0::{schema_hash, schema_hash_normalized};
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+struct HashFixture {
+    name: String,
+    variants: Vec<SchemaIr>,
+    hash: String,
+}
+
+fn fixtures() -> Vec<HashFixture> {
+    serde_json::from_str(include_str!("../fixtures/schema_ir/normalized_hashes.json"))
+        .expect("parse normalized hash fixtures")
+}
+
+fn hash(schema: &SchemaIr) -> String {
+    schema_hash_normalized(schema, &SchemaIr::Null, &SchemaIr::Null).expect("schema hash")
+}
+
+#[test]
+fn normalized_hashes_are_locked() {
+    for fixture in fixtures() {
+        for variant in &fixture.variants {
+            assert_eq!(hash(variant), fixture.hash, "{}", fixture.name);
+        }
+    }
+}
+
+#[test]
+fn raw_hashes_differ_for_equivalent_spellings() {
+    for fixture in fixtures() {
+        let raw: Vec<String> = fixture
+            .variants
+            .iter()
+            .map(|schema| schema_hash(schema, &SchemaIr::Null, &SchemaIr::Null).expect("hash"))
+            .collect();
+        assert_ne!(raw[0], raw[1], "{}", fixture.name);
+    }
+}
+
+#[test]
+fn normalization_is_idempotent() {
+    for fixture in fixtures() {
+        for variant in &fixture.variants {
+            let once = variant.normalized();
+            assert_eq!(once.normalized(), once, "{}", fixture.name);
+            assert_eq!(once, fixture.variants[1].normalized(), "{}", fixture.name);
+        }
+    }
+}
+
+#[test]
+fn normalization_keeps_one_of_exactly_one_semantics() {
+    let schema: SchemaIr = serde_json::from_value(serde_json::json!({
+        "type": "one_of",
+        "variants": [{ "type": "string" }, { "type": "int" }, { "type": "int" }]
+    }))
+    .expect("schema");
+    let normalized = schema.normalized();
+    for value in [serde_json::json!(3), serde_json::json!("x")] {
+        assert_eq!(
+            schema.validate_json(&value).is_ok(),
+            normalized.validate_json(&value).is_ok(),
+            "{value}"
+        );
+    }
+    // Duplicate variants make every integer ambiguous, before and after normalization.
+    assert!(normalized.validate_json(&serde_json::json!(3)).is_err());
+    assert_ne!(
+        hash(&schema),
+        hash(&SchemaIr::OneOf {
+            variants: vec![
+                SchemaIr::Int {
+                    min: None,
+                    max: None
+                },
+                serde_json::from_value(serde_json::json!({ "type": "string" })).expect("string"),
+            ],
+        })
+    );
+}
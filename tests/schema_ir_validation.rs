@@ -0,0 +1,220 @@
+use std::collections::BTreeMap;
+
+use ciborium::value::Value;
+use greentic_types::schemas::common::schema_ir::{AdditionalProperties, SchemaIr};
+use serde_json::json;
+
+fn string() -> SchemaIr {
+    SchemaIr::String {
+        min_len: None,
+        max_len: None,
+        regex: None,
+        format: None,
+    }
+}
+
+fn config_schema() -> SchemaIr {
+    SchemaIr::Object {
+        properties: BTreeMap::from([
+            (
+                "name".to_string(),
+                SchemaIr::String {
+                    min_len: Some(2),
+                    max_len: Some(8),
+                    regex: Some("^[a-z]+$".into()),
+                    format: None,
+                },
+            ),
+            (
+                "email".to_string(),
+                SchemaIr::String {
+                    min_len: None,
+                    max_len: None,
+                    regex: None,
+                    format: Some("email".into()),
+                },
+            ),
+            (
+                "retries".to_string(),
+                SchemaIr::Int {
+                    min: Some(0),
+                    max: Some(5),
+                },
+            ),
+            (
+                "ratio".to_string(),
+                SchemaIr::Float {
+                    min: Some(0.0),
+                    max: Some(1.0),
+                },
+            ),
+            (
+                "tags".to_string(),
+                SchemaIr::Array {
+                    items: Box::new(string()),
+                    min_items: Some(1),
+                    max_items: Some(2),
+                },
+            ),
+            (
+                "mode".to_string(),
+                SchemaIr::Enum {
+                    values: vec![Value::Text("fast".into()), Value::Text("safe".into())],
+                },
+            ),
+            (
+                "target".to_string(),
+                SchemaIr::OneOf {
+                    variants: vec![
+                        string(),
+                        SchemaIr::Int {
+                            min: None,
+                            max: None,
+                        },
+                    ],
+                },
+            ),
+        ]),
+        required: vec!["name".into(), "retries".into()],
+        additional: AdditionalProperties::Forbid,
+    }
+}
+
+fn violations(schema: &SchemaIr, value: serde_json::Value) -> Vec<(String, String)> {
+    match schema.validate_json(&value) {
+        Ok(()) => Vec::new(),
+        Err(violations) => violations
+            .into_iter()
+            .map(|violation| (violation.pointer, violation.code))
+            .collect(),
+    }
+}
+
+#[test]
+fn accepts_valid_json_and_cbor() {
+    let schema = config_schema();
+    let value = json!({
+        "name": "demo",
+        "email": "ops@example.com",
+        "retries": 3,
+        "ratio": 1,
+        "tags": ["a"],
+        "mode": "safe",
+        "target": 7
+    });
+    assert_eq!(schema.validate_json(&value), Ok(()));
+
+    let cbor = Value::Map(vec![
+        (Value::Text("name".into()), Value::Text("demo".into())),
+        (Value::Text("retries".into()), Value::Integer(0.into())),
+        (Value::Text("ratio".into()), Value::Float(0.5)),
+    ]);
+    assert_eq!(schema.validate(&cbor), Ok(()));
+}
+
+#[test]
+fn reports_every_violation_with_pointers() {
+    let schema = config_schema();
+    let found = violations(
+        &schema,
+        json!({
+            "name": "X",
+            "email": "not-an-email",
+            "ratio": 1.5,
+            "tags": [1, "b", "c"],
+            "mode": "slow",
+            "target": true,
+            "extra/key": null
+        }),
+    );
+    let expected = [
+        ("/name", "SCHEMA_MIN_LENGTH"),
+        ("/name", "SCHEMA_PATTERN"),
+        ("/email", "SCHEMA_FORMAT"),
+        ("/ratio", "SCHEMA_MAXIMUM"),
+        ("/tags", "SCHEMA_MAX_ITEMS"),
+        ("/tags/0", "SCHEMA_TYPE_MISMATCH"),
+        ("/mode", "SCHEMA_ENUM"),
+        ("/target", "SCHEMA_ONE_OF_NO_MATCH"),
+        ("/extra~1key", "SCHEMA_ADDITIONAL_PROPERTY"),
+        ("/retries", "SCHEMA_REQUIRED"),
+    ];
+    for (pointer, code) in expected {
+        assert!(
+            found.contains(&(pointer.to_string(), code.to_string())),
+            "missing {pointer} {code} in {found:?}"
+        );
+    }
+    assert_eq!(found.len(), expected.len(), "{found:?}");
+}
+
+#[test]
+fn one_of_requires_exactly_one_match() {
+    let schema = SchemaIr::OneOf {
+        variants: vec![
+            SchemaIr::Int {
+                min: None,
+                max: None,
+            },
+            SchemaIr::Float {
+                min: None,
+                max: None,
+            },
+        ],
+    };
+    assert_eq!(
+        violations(&schema, json!(3)),
+        vec![(String::new(), "SCHEMA_ONE_OF_AMBIGUOUS".to_string())]
+    );
+    assert_eq!(schema.validate_json(&json!(3.5)), Ok(()));
+}
+
+#[test]
+fn checks_well_known_formats() {
+    let format = |name: &str| SchemaIr::String {
+        min_len: None,
+        max_len: None,
+        regex: None,
+        format: Some(name.into()),
+    };
+    let cases = [
+        ("uuid", "123e4567-e89b-12d3-a456-426614174000", true),
+        ("uuid", "123e4567", false),
+        ("date-time", "2026-01-31T12:30:00Z", true),
+        ("date-time", "2026-01-31T12:30:00+02:00", true),
+        ("date-time", "2026-13-31T12:30:00Z", false),
+        ("date", "2026-02-01", true),
+        ("uri", "https://example.com/x", true),
+        ("uri", "no scheme", false),
+        ("ipv4", "10.0.0.1", true),
+        ("ipv6", "::1", true),
+        ("hostname", "api.example.com", true),
+        ("hostname", "-bad.example", false),
+        ("secret", "anything goes", true),
+    ];
+    for (name, text, valid) in cases {
+        assert_eq!(
+            format(name).validate_json(&json!(text)).is_ok(),
+            valid,
+            "{name}: {text}"
+        );
+    }
+}
+
+#[test]
+fn bytes_accept_strings_only_in_json() {
+    assert_eq!(SchemaIr::Bytes.validate_json(&json!("aGVsbG8=")), Ok(()));
+    assert!(SchemaIr::Bytes.validate(&Value::Text("x".into())).is_err());
+    assert_eq!(SchemaIr::Bytes.validate(&Value::Bytes(vec![1, 2])), Ok(()));
+}
+
+#[test]
+fn unresolved_refs_are_reported() {
+    let schema = SchemaIr::Ref {
+        id: "missing".into(),
+    };
+    assert_eq!(
+        violations(&schema, json!({})),
+        vec![(String::new(), "SCHEMA_UNRESOLVED_REF".to_string())]
+    );
+}
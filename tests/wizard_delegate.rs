@@ -0,0 +1,227 @@
+use std::collections::BTreeMap;
+
+use greentic_types::{
+    DelegateRequest, ResolvedWizard, WizardDelegateError, WizardId, WizardMode, WizardPlan,
+    WizardPlanMeta, WizardStep, WizardTarget, flatten_plan,
+};
+use serde_json::{Value, json};
+
+fn plan(id: &str, target: WizardTarget, steps: Vec<WizardStep>) -> WizardPlan {
+    WizardPlan {
+        meta: WizardPlanMeta {
+            id: id.into(),
+            target,
+            mode: WizardMode::Scaffold,
+        },
+        steps,
+    }
+}
+
+fn write(path: &str, content: &str) -> WizardStep {
+    WizardStep::WriteFiles {
+        files: BTreeMap::from([(path.to_string(), content.to_string())]),
+    }
+}
+
+fn delegate(
+    target: WizardTarget,
+    id: &str,
+    answers: &[(&str, Value)],
+    output_map: &[(&str, &str)],
+) -> WizardStep {
+    WizardStep::Delegate {
+        target,
+        id: id.into(),
+        mode: WizardMode::Setup,
+        prefilled_answers: answers
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.clone()))
+            .collect(),
+        output_map: output_map
+            .iter()
+            .map(|(from, to)| (from.to_string(), to.to_string()))
+            .collect(),
+    }
+}
+
+#[test]
+fn flattens_component_delegates_into_the_pack_plan() {
+    let pack = plan(
+        "pack-init",
+        WizardTarget::Pack,
+        vec![
+            write("pack.yaml", "id: demo\n"),
+            delegate(
+                WizardTarget::Component,
+                "component-setup",
+                &[("name", json!("hello"))],
+                &[("component_id", "components.hello")],
+            ),
+            delegate(
+                WizardTarget::Component,
+                "component-setup",
+                &[("name", json!("world"))],
+                &[("component_id", "components.world")],
+            ),
+        ],
+    );
+
+    // The component wizard renders its plan from the prefilled answers and nests itself under
+    // `components/<name>`; its own `docs` delegate keeps the default id prefix.
+    let resolver = |request: &DelegateRequest<'_>| -> Option<ResolvedWizard> {
+        match request.id.as_str() {
+            "component-setup" => {
+                let name = request.answers.get("name")?.as_str()?;
+                Some(ResolvedWizard {
+                    plan: plan(
+                        "component-setup",
+                        WizardTarget::Component,
+                        vec![
+                            WizardStep::EnsureDir {
+                                paths: vec![".".into(), "src".into()],
+                            },
+                            write("component.json", &format!("{{\"name\":\"{name}\"}}")),
+                            delegate(WizardTarget::Dev, "docs", &[], &[]),
+                        ],
+                    ),
+                    outputs: BTreeMap::from([(
+                        "component_id".to_string(),
+                        json!(format!("demo.{name}")),
+                    )]),
+                    prefix: Some(format!("components/{name}")),
+                })
+            }
+            "docs" => Some(
+                plan(
+                    "docs",
+                    WizardTarget::Dev,
+                    vec![write("README.md", "# docs\n")],
+                )
+                .into(),
+            ),
+            _ => None,
+        }
+    };
+
+    let flattened = flatten_plan(&pack, &resolver).expect("flatten");
+    assert_eq!(flattened.plan.meta, pack.meta);
+    assert_eq!(
+        flattened.plan.steps,
+        vec![
+            write("pack.yaml", "id: demo\n"),
+            WizardStep::EnsureDir {
+                paths: vec!["components/hello".into(), "components/hello/src".into()],
+            },
+            write("components/hello/component.json", "{\"name\":\"hello\"}"),
+            write("components/hello/docs/README.md", "# docs\n"),
+            WizardStep::EnsureDir {
+                paths: vec!["components/world".into(), "components/world/src".into()],
+            },
+            write("components/world/component.json", "{\"name\":\"world\"}"),
+            write("components/world/docs/README.md", "# docs\n"),
+        ]
+    );
+    assert_eq!(
+        flattened.outputs,
+        BTreeMap::from([
+            ("components.hello".to_string(), json!("demo.hello")),
+            ("components.world".to_string(), json!("demo.world")),
+        ])
+    );
+}
+
+#[test]
+fn reports_unknown_delegates_and_cycles() {
+    let plans: BTreeMap<WizardId, WizardPlan> = [
+        plan(
+            "a",
+            WizardTarget::Flow,
+            vec![delegate(WizardTarget::Flow, "b", &[], &[])],
+        ),
+        plan(
+            "b",
+            WizardTarget::Flow,
+            vec![delegate(WizardTarget::Flow, "a", &[], &[])],
+        ),
+    ]
+    .into_iter()
+    .map(|plan| (plan.meta.id.clone(), plan))
+    .collect();
+
+    let root = plan(
+        "root",
+        WizardTarget::Pack,
+        vec![delegate(WizardTarget::Flow, "a", &[], &[])],
+    );
+    assert_eq!(
+        flatten_plan(&root, &plans),
+        Err(WizardDelegateError::Cycle {
+            chain: vec!["a".into(), "b".into(), "a".into()],
+        })
+    );
+
+    let missing = plan(
+        "root",
+        WizardTarget::Pack,
+        vec![delegate(WizardTarget::Component, "missing", &[], &[])],
+    );
+    let err = flatten_plan(&missing, &plans).expect_err("unknown");
+    assert_eq!(
+        err,
+        WizardDelegateError::UnknownDelegate {
+            target: WizardTarget::Component,
+            id: "missing".into(),
+            mode: WizardMode::Setup,
+        }
+    );
+    assert_eq!(
+        err.to_string(),
+        "unknown delegate wizard `missing` (Component, Setup)"
+    );
+}
+
+#[test]
+fn commands_are_rejected_under_a_delegate_prefix() {
+    let cargo_init = WizardStep::RunCli {
+        command: "cargo".into(),
+        args: vec!["init".into()],
+    };
+    let root = plan(
+        "pack-init",
+        WizardTarget::Pack,
+        vec![delegate(
+            WizardTarget::Component,
+            "component-init",
+            &[],
+            &[],
+        )],
+    );
+    let resolver = |prefix: Option<&'static str>| {
+        let steps = vec![cargo_init.clone()];
+        move |_: &DelegateRequest<'_>| -> Option<ResolvedWizard> {
+            Some(ResolvedWizard {
+                plan: plan("component-init", WizardTarget::Component, steps.clone()),
+                outputs: BTreeMap::new(),
+                prefix: prefix.map(str::to_string),
+            })
+        }
+    };
+
+    let err = flatten_plan(&root, &resolver(None)).expect_err("nested command");
+    assert_eq!(
+        err,
+        WizardDelegateError::CommandUnderPrefix {
+            id: "component-init".into(),
+            command: "cargo".into(),
+            prefix: "component-init".into(),
+        }
+    );
+    assert_eq!(
+        err.to_string(),
+        "delegate `component-init` runs `cargo` but its paths are nested under `component-init`"
+    );
+
+    // With an empty prefix the delegate owns root-relative paths and may run commands.
+    let flattened = flatten_plan(&root, &resolver(Some(""))).expect("flatten");
+    assert_eq!(flattened.plan.steps, vec![cargo_init.clone()]);
+}
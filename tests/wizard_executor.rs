@@ -0,0 +1,223 @@
+#![cfg(feature = "std")]
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+use greentic_types::{
+    FileChange, FileChangeKind, StepOutcome, WizardExecError, WizardExecutor, WizardMode,
+    WizardPlan, WizardPlanMeta, WizardStep, WizardTarget,
+};
+
+fn scratch_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("greentic-wizard-{name}-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).expect("create scratch dir");
+    dir
+}
+
+fn plan(steps: Vec<WizardStep>) -> WizardPlan {
+    WizardPlan {
+        meta: WizardPlanMeta {
+            id: "pack-init".into(),
+            target: WizardTarget::Pack,
+            mode: WizardMode::Scaffold,
+        },
+        steps,
+    }
+}
+
+fn write_files(files: &[(&str, &str)]) -> WizardStep {
+    WizardStep::WriteFiles {
+        files: files
+            .iter()
+            .map(|(path, content)| (path.to_string(), content.to_string()))
+            .collect::<BTreeMap<_, _>>(),
+    }
+}
+
+#[test]
+fn dry_run_reports_changes_with_diffs_and_writes_nothing() {
+    let root = scratch_dir("dry-run");
+    fs::write(root.join("README.md"), "# demo\nold line\n").expect("seed");
+    let plan = plan(vec![
+        WizardStep::EnsureDir {
+            paths: vec!["flows".into(), "./flows".into()],
+        },
+        write_files(&[
+            ("README.md", "# demo\nnew line\n"),
+            ("flows/main.ygtc", "id: main\n"),
+            ("components/hello/component.json", "{}\n"),
+        ]),
+    ]);
+
+    let report = WizardExecutor::new(&root)
+        .with_dry_run(true)
+        .execute(&plan)
+        .expect("dry run");
+    assert!(report.dry_run);
+    assert!(
+        report
+            .steps
+            .iter()
+            .all(|step| step.outcome == StepOutcome::Planned)
+    );
+    let summary: Vec<(&str, FileChangeKind)> = report
+        .changes
+        .iter()
+        .map(|change| (change.path.as_str(), change.kind))
+        .collect();
+    assert_eq!(
+        summary,
+        [
+            ("flows", FileChangeKind::CreateDir),
+            ("README.md", FileChangeKind::ModifyFile),
+            ("components", FileChangeKind::CreateDir),
+            ("components/hello", FileChangeKind::CreateDir),
+            (
+                "components/hello/component.json",
+                FileChangeKind::CreateFile
+            ),
+            ("flows/main.ygtc", FileChangeKind::CreateFile),
+        ]
+    );
+    assert_eq!(
+        report.changes[1].diff.as_deref(),
+        Some("--- a/README.md\n+++ b/README.md\n@@ -1,2 +1,2 @@\n # demo\n-old line\n+new line\n")
+    );
+    assert_eq!(
+        report.changes[5].diff.as_deref(),
+        Some("--- /dev/null\n+++ b/flows/main.ygtc\n@@ -0,0 +1 @@\n+id: main\n")
+    );
+    assert!(!root.join("flows").exists());
+    assert_eq!(
+        fs::read_to_string(root.join("README.md")).expect("readme"),
+        "# demo\nold line\n"
+    );
+
+    // A real run makes the same changes; running again is a no-op.
+    let executor = WizardExecutor::new(&root);
+    let applied = executor.execute(&plan).expect("apply");
+    assert_eq!(applied.changes, report.changes);
+    assert_eq!(
+        fs::read_to_string(root.join("flows/main.ygtc")).expect("flow"),
+        "id: main\n"
+    );
+    let again = executor.execute(&plan).expect("re-apply");
+    assert_eq!(again.changes, Vec::<FileChange>::new());
+    assert!(
+        again
+            .steps
+            .iter()
+            .all(|step| step.outcome == StepOutcome::Applied)
+    );
+    fs::remove_dir_all(&root).expect("cleanup");
+}
+
+#[test]
+fn diffs_large_files_and_skips_wholesale_rewrites() {
+    let root = scratch_dir("large-diff");
+    let old: String = (0..5000).map(|line| format!("line {line}\n")).collect();
+    fs::write(root.join("big.txt"), &old).expect("seed");
+    fs::write(root.join("rewrite.txt"), &old).expect("seed");
+    let edited = old
+        .replace("line 10\n", "line ten\n")
+        .replace("line 4000\n", "");
+    let rewritten: String = (0..5000).map(|line| format!("other {line}\n")).collect();
+    let plan = plan(vec![write_files(&[
+        ("big.txt", &edited),
+        ("rewrite.txt", &rewritten),
+    ])]);
+
+    let report = WizardExecutor::new(&root)
+        .with_dry_run(true)
+        .execute(&plan)
+        .expect("dry run");
+    assert_eq!(
+        report.changes[0].diff.as_deref(),
+        Some(
+            "--- a/big.txt\n+++ b/big.txt\n\
+             @@ -8,7 +8,7 @@\n line 7\n line 8\n line 9\n-line 10\n+line ten\n line 11\n line 12\n line 13\n\
+             @@ -3998,7 +3998,6 @@\n line 3997\n line 3998\n line 3999\n-line 4000\n line 4001\n line 4002\n line 4003\n"
+        )
+    );
+    // Too many changed lines to diff; the change is still reported.
+    assert_eq!(report.changes[1].kind, FileChangeKind::ModifyFile);
+    assert_eq!(report.changes[1].diff, None);
+}
+
+#[test]
+fn rejects_paths_outside_the_root_before_touching_the_tree() {
+    let root = scratch_dir("confined");
+    let executor = WizardExecutor::new(&root);
+    for bad in ["../escape.txt", "/etc/passwd", "a/../../b", ""] {
+        let err = executor
+            .execute(&plan(vec![
+                write_files(&[("ok.txt", "ok")]),
+                write_files(&[(bad, "x")]),
+            ]))
+            .expect_err(bad);
+        assert!(
+            matches!(err, WizardExecError::InvalidPath { step: 1, .. }),
+            "{bad}: {err}"
+        );
+    }
+    assert!(!root.join("ok.txt").exists());
+
+    #[cfg(unix)]
+    {
+        let outside = scratch_dir("outside");
+        std::os::unix::fs::symlink(&outside, root.join("link")).expect("symlink");
+        let err = executor
+            .execute(&plan(vec![write_files(&[("link/file.txt", "x")])]))
+            .expect_err("symlink escape");
+        assert!(matches!(err, WizardExecError::EscapesRoot { .. }), "{err}");
+        assert!(!outside.join("file.txt").exists());
+        fs::remove_dir_all(&outside).expect("cleanup");
+    }
+    fs::remove_dir_all(&root).expect("cleanup");
+}
+
+#[test]
+fn run_cli_requires_allowlist_and_delegates_must_be_resolved() {
+    let root = scratch_dir("cli");
+    let step = WizardStep::RunCli {
+        command: "cargo".into(),
+        args: vec!["--version".into()],
+    };
+    let err = WizardExecutor::new(&root)
+        .execute(&plan(vec![step.clone()]))
+        .expect_err("not allowed");
+    assert!(matches!(
+        err,
+        WizardExecError::CommandNotAllowed { step: 0, ref command } if command == "cargo"
+    ));
+
+    let executor = WizardExecutor::new(&root).with_allowed_command("cargo");
+    let report = executor
+        .clone()
+        .with_dry_run(true)
+        .execute(&plan(vec![step.clone()]))
+        .expect("dry run");
+    assert_eq!(report.steps[0].outcome, StepOutcome::Planned);
+    let report = executor.execute(&plan(vec![step])).expect("run");
+    assert!(matches!(
+        &report.steps[0].outcome,
+        StepOutcome::CommandCompleted { stdout, .. } if stdout.starts_with("cargo ")
+    ));
+
+    let err = executor
+        .execute(&plan(vec![WizardStep::Delegate {
+            target: WizardTarget::Component,
+            id: "component-setup".into(),
+            mode: WizardMode::Setup,
+            prefilled_answers: BTreeMap::new(),
+            output_map: BTreeMap::new(),
+        }]))
+        .expect_err("delegate");
+    assert!(matches!(
+        err,
+        WizardExecError::UnresolvedDelegate { step: 0, .. }
+    ));
+    fs::remove_dir_all(&root).expect("cleanup");
+}
@@ -0,0 +1,198 @@
+#![cfg(all(feature = "std", feature = "serde"))]
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+use greentic_types::{
+    JournalEntry, StepOutcome, WizardExecError, WizardExecutor, WizardJournal, WizardMode,
+    WizardPlan, WizardPlanMeta, WizardStep, WizardTarget, journal_path_for, plan_digest,
+    read_wizard_journal, write_wizard_journal,
+};
+
+fn scratch_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("greentic-journal-{name}-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).expect("create scratch dir");
+    dir
+}
+
+fn write_files(files: &[(&str, &str)]) -> WizardStep {
+    WizardStep::WriteFiles {
+        files: files
+            .iter()
+            .map(|(path, content)| (path.to_string(), content.to_string()))
+            .collect::<BTreeMap<_, _>>(),
+    }
+}
+
+fn wizard_plan(mode: WizardMode, steps: Vec<WizardStep>) -> WizardPlan {
+    WizardPlan {
+        meta: WizardPlanMeta {
+            id: "pack-init".into(),
+            target: WizardTarget::Pack,
+            mode,
+        },
+        steps,
+    }
+}
+
+#[test]
+fn failed_run_is_journaled_and_rolled_back() {
+    let root = scratch_dir("rollback");
+    fs::write(root.join("README.md"), "original\n").expect("seed");
+    let plan = wizard_plan(
+        WizardMode::Scaffold,
+        vec![
+            write_files(&[("README.md", "scaffolded\n"), ("src/lib.rs", "// lib\n")]),
+            WizardStep::RunCli {
+                command: "cargo".into(),
+                args: vec!["--no-such-flag".into()],
+            },
+        ],
+    );
+
+    let executor = WizardExecutor::new(&root).with_allowed_command("cargo");
+    let mut journal = WizardJournal::for_plan(&plan).expect("journal");
+    let err = executor
+        .execute_journaled(&plan, &mut journal)
+        .expect_err("cargo fails");
+    assert!(matches!(
+        err,
+        WizardExecError::CommandFailed { step: 1, .. }
+    ));
+    assert_eq!(journal.completed_steps, [0]);
+    assert_eq!(
+        journal.entries,
+        [
+            JournalEntry::ModifiedFile {
+                step: 0,
+                path: "README.md".into(),
+                previous: b"original\n".to_vec(),
+            },
+            JournalEntry::CreatedDir {
+                step: 0,
+                path: "src".into(),
+            },
+            JournalEntry::CreatedFile {
+                step: 0,
+                path: "src/lib.rs".into(),
+            },
+        ]
+    );
+
+    // The journal survives a round trip through the project directory.
+    let journal_path = journal_path_for(&root);
+    write_wizard_journal(&journal_path, &journal).expect("write journal");
+    let stored = read_wizard_journal(&journal_path).expect("read journal");
+    assert_eq!(stored, journal);
+    assert!(stored.is_for(&plan));
+
+    // A file added by someone else keeps its directory alive.
+    fs::write(root.join("src/notes.txt"), "mine").expect("foreign file");
+    let report = executor.rollback(&stored).expect("rollback");
+    assert_eq!(report.restored, ["README.md"]);
+    assert_eq!(report.removed, ["src/lib.rs"]);
+    assert_eq!(report.kept, ["src"]);
+    assert_eq!(
+        fs::read_to_string(root.join("README.md")).expect("readme"),
+        "original\n"
+    );
+    assert!(!root.join("src/lib.rs").exists());
+
+    fs::remove_file(root.join("src/notes.txt")).expect("remove foreign file");
+    let report = executor.rollback(&stored).expect("rollback again");
+    assert_eq!(report.removed, ["src"]);
+    assert!(!root.join("src").exists());
+    fs::remove_dir_all(&root).expect("cleanup");
+}
+
+#[test]
+fn update_mode_resumes_after_completed_steps() {
+    let root = scratch_dir("resume");
+    // A directory where a file should go makes step 1 fail.
+    fs::create_dir_all(root.join("flows/main.ygtc")).expect("blocker");
+    let plan = wizard_plan(
+        WizardMode::Update,
+        vec![
+            write_files(&[("pack.yaml", "id: demo\n")]),
+            write_files(&[("flows/main.ygtc", "id: main\n")]),
+        ],
+    );
+    let executor = WizardExecutor::new(&root);
+    let mut journal = WizardJournal::for_plan(&plan).expect("journal");
+    let err = executor
+        .execute_journaled(&plan, &mut journal)
+        .expect_err("blocked");
+    assert!(matches!(err, WizardExecError::Io { step: 1, .. }), "{err}");
+    assert_eq!(journal.completed_steps, [0]);
+
+    fs::remove_dir(root.join("flows/main.ygtc")).expect("unblock");
+    fs::write(root.join("pack.yaml"), "id: edited\n").expect("user edit");
+    let report = executor
+        .execute_journaled(&plan, &mut journal)
+        .expect("resume");
+    assert_eq!(report.steps[0].outcome, StepOutcome::AlreadyCompleted);
+    assert_eq!(report.steps[1].outcome, StepOutcome::Applied);
+    assert_eq!(journal.completed_steps, [0, 1]);
+    // The completed step was not re-applied over the user's edit.
+    assert_eq!(
+        fs::read_to_string(root.join("pack.yaml")).expect("pack"),
+        "id: edited\n"
+    );
+
+    // A different plan does not silently discard the pending undo entries.
+    let other = wizard_plan(
+        WizardMode::Update,
+        vec![write_files(&[("pack.yaml", "id: demo\n")])],
+    );
+    assert!(matches!(
+        executor.execute_journaled(&other, &mut journal),
+        Err(WizardExecError::JournalMismatch { entries: 2, .. })
+    ));
+    assert!(journal.is_for(&plan));
+    journal = WizardJournal::for_plan(&other).expect("journal");
+    executor
+        .execute_journaled(&other, &mut journal)
+        .expect("other plan");
+    assert!(journal.is_for(&other));
+    assert_eq!(journal.completed_steps, [0]);
+    fs::remove_dir_all(&root).expect("cleanup");
+}
+
+#[test]
+fn plan_digest_covers_delegate_details() {
+    let delegate = |name: &str| {
+        wizard_plan(
+            WizardMode::Scaffold,
+            vec![WizardStep::Delegate {
+                target: WizardTarget::Component,
+                id: "component-init".into(),
+                mode: WizardMode::Scaffold,
+                prefilled_answers: BTreeMap::from([("name".to_string(), serde_json::json!(name))]),
+                output_map: BTreeMap::new(),
+            }],
+        )
+    };
+    let journal = WizardJournal::for_plan(&delegate("a")).expect("journal");
+    assert!(journal.is_for(&delegate("a")));
+    assert!(!journal.is_for(&delegate("b")));
+
+    // List boundaries are part of the digest.
+    let split = wizard_plan(
+        WizardMode::Scaffold,
+        vec![WizardStep::EnsureDir {
+            paths: vec!["ab".into(), "c".into()],
+        }],
+    );
+    let joined = wizard_plan(
+        WizardMode::Scaffold,
+        vec![WizardStep::EnsureDir {
+            paths: vec!["a".into(), "bc".into()],
+        }],
+    );
+    assert_ne!(
+        plan_digest(&split).expect("digest"),
+        plan_digest(&joined).expect("digest")
+    );
+}
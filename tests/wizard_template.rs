@@ -0,0 +1,191 @@
+use std::collections::BTreeMap;
+
+use ciborium::value::Value;
+use greentic_types::{
+    AnswerErrorKind, WizardMode, WizardStep, WizardTemplate, WizardTemplateError,
+    render_wizard_template,
+};
+use serde_json::json;
+
+fn template() -> WizardTemplate {
+    serde_json::from_value(json!({
+        "meta": { "id": "component-scaffold", "target": "component", "mode": "scaffold" },
+        "qa": {
+            "mode": "setup",
+            "title": { "key": "qa.title" },
+            "questions": [
+                { "id": "name", "label": { "key": "qa.name" }, "kind": { "type": "text" }, "required": true },
+                { "id": "tls", "label": { "key": "qa.tls" }, "kind": { "type": "bool" }, "required": false, "default": false },
+                { "id": "lang", "label": { "key": "qa.lang" }, "required": true, "kind": {
+                    "type": "choice",
+                    "options": [
+                        { "value": "rust", "label": { "key": "qa.lang.rust" } },
+                        { "value": "go", "label": { "key": "qa.lang.go" } }
+                    ]
+                } },
+                { "id": "port", "label": { "key": "qa.port" }, "kind": { "type": "integer" }, "required": false, "default": 8080 }
+            ],
+            "defaults": {}
+        },
+        "steps": [
+            { "type": "ensure_dir", "paths": ["{{name}}", "{{name}}/{{#if lang == \"rust\"}}src{{else}}cmd{{/if}}"] },
+            { "type": "write_files", "files": {
+                "{{name}}/component.yaml": "name: {{name}}\nport: {{port}}\n{{#if tls}}tls: true\n{{/if}}",
+                "{{#if tls}}{{name}}/certs/README.md{{/if}}": "Place certificates here.\n",
+                "{{name}}/NOTES.md": "Use \\{{name}} in templates.\n"
+            } },
+            { "type": "run_cli", "when": { "op": "equals", "question": "lang", "value": "rust" },
+              "command": "cargo", "args": ["new", "--lib", "{{name}}"] },
+            { "type": "delegate", "target": "flow", "id": "flow-setup", "mode": "setup",
+              "prefilled_answers": { "component": "{{name}}", "ports": ["{{port}}"] } }
+        ]
+    }))
+    .expect("template")
+}
+
+fn answers(entries: &[(&str, Value)]) -> BTreeMap<String, Value> {
+    entries
+        .iter()
+        .map(|(key, value)| (key.to_string(), value.clone()))
+        .collect()
+}
+
+fn files(step: &WizardStep) -> &BTreeMap<String, String> {
+    match step {
+        WizardStep::WriteFiles { files } => files,
+        other => panic!("expected write_files, got {other:?}"),
+    }
+}
+
+#[test]
+fn renders_paths_contents_and_conditions_from_answers() {
+    let template = template();
+    let plan = render_wizard_template(
+        &template,
+        &answers(&[
+            ("name", Value::Text("hello".into())),
+            ("lang", Value::Text("rust".into())),
+            ("tls", Value::Bool(true)),
+        ]),
+    )
+    .expect("render");
+    assert_eq!(plan.meta, template.meta);
+    assert_eq!(plan.meta.mode, WizardMode::Scaffold);
+    assert_eq!(
+        plan.steps[0],
+        WizardStep::EnsureDir {
+            paths: vec!["hello".into(), "hello/src".into()],
+        }
+    );
+    let written = files(&plan.steps[1]);
+    assert_eq!(
+        written["hello/component.yaml"],
+        "name: hello\nport: 8080\ntls: true\n"
+    );
+    assert_eq!(
+        written["hello/certs/README.md"],
+        "Place certificates here.\n"
+    );
+    assert_eq!(written["hello/NOTES.md"], "Use {{name}} in templates.\n");
+    assert_eq!(
+        plan.steps[2],
+        WizardStep::RunCli {
+            command: "cargo".into(),
+            args: vec!["new".into(), "--lib".into(), "hello".into()],
+        }
+    );
+    let WizardStep::Delegate {
+        prefilled_answers, ..
+    } = &plan.steps[3]
+    else {
+        panic!("expected delegate");
+    };
+    assert_eq!(prefilled_answers["component"], json!("hello"));
+    assert_eq!(prefilled_answers["ports"], json!(["8080"]));
+
+    // Rendering is deterministic, and other answers drop conditional steps and files.
+    let go = answers(&[
+        ("name", Value::Text("svc".into())),
+        ("lang", Value::Text("go".into())),
+    ]);
+    let plan = render_wizard_template(&template, &go).expect("render go");
+    assert_eq!(
+        plan,
+        render_wizard_template(&template, &go).expect("render again")
+    );
+    assert_eq!(plan.steps.len(), 3);
+    assert_eq!(
+        plan.steps[0],
+        WizardStep::EnsureDir {
+            paths: vec!["svc".into(), "svc/cmd".into()],
+        }
+    );
+    let written = files(&plan.steps[1]);
+    assert_eq!(
+        written.keys().collect::<Vec<_>>(),
+        ["svc/NOTES.md", "svc/component.yaml"]
+    );
+    assert_eq!(written["svc/component.yaml"], "name: svc\nport: 8080\n");
+}
+
+#[test]
+fn condition_literals_may_contain_operators() {
+    let mut template = template();
+    template.steps[1] = WizardStep::WriteFiles {
+        files: BTreeMap::from([(
+            "out.txt".to_string(),
+            "{{#if name == \"a!=b\"}}eq{{else}}ne{{/if}} {{#if name!=\"a==b\"}}ne{{/if}}"
+                .to_string(),
+        )]),
+    }
+    .into();
+    let plan = render_wizard_template(
+        &template,
+        &answers(&[
+            ("name", Value::Text("a!=b".into())),
+            ("lang", Value::Text("go".into())),
+        ]),
+    )
+    .expect("render");
+    assert_eq!(files(&plan.steps[1])["out.txt"], "eq ne");
+}
+
+#[test]
+fn rejects_invalid_answers_and_templates() {
+    let mut template = template();
+    let err = render_wizard_template(&template, &answers(&[("name", Value::Text("x".into()))]))
+        .expect_err("lang is required");
+    let WizardTemplateError::InvalidAnswers(errors) = err else {
+        panic!("expected invalid answers");
+    };
+    assert_eq!(errors[0].question_id, "lang");
+    assert_eq!(errors[0].kind, AnswerErrorKind::Missing);
+
+    let valid = answers(&[
+        ("name", Value::Text("x".into())),
+        ("lang", Value::Text("go".into())),
+    ]);
+    for (content, message) in [
+        ("{{missing}}", "`missing` has no answer"),
+        ("{{#if tls}}open", "unclosed `{{#if}}`"),
+        ("{{/if}}", "`{{/if}}` without `{{#if}}`"),
+        ("{{name", "unclosed `{{`"),
+        (
+            "{{#if lang go}}x{{/if}}",
+            "expected `==` or `!=` after `lang` in `lang go`",
+        ),
+    ] {
+        template.steps[1] = WizardStep::WriteFiles {
+            files: BTreeMap::from([("out.txt".to_string(), content.to_string())]),
+        }
+        .into();
+        assert_eq!(
+            render_wizard_template(&template, &valid),
+            Err(WizardTemplateError::Template {
+                step: 1,
+                field: "file `out.txt`".into(),
+                message: message.into(),
+            })
+        );
+    }
+}